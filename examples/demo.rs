@@ -0,0 +1,12 @@
+//! The `--demo` tour: a sample board kept in memory, walked through step by
+//! step, for recording GIFs. Press any key to take over, `q` to quit.
+//!
+//!     cargo run --example demo
+
+use anyhow::Result;
+use kanban_cli::app::App;
+use kanban_cli::{demo, tui};
+
+fn main() -> Result<()> {
+    tui::run(&mut App::demo(), demo::script())
+}
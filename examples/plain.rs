@@ -0,0 +1,100 @@
+//! A line-based frontend over the library: it prints the board in view and reads
+//! one command per line. Run it next to a `kanban.db`:
+//!
+//!     cargo run --example plain
+//!
+//! `h`/`j`/`k`/`l` move the cursor, `H`/`L` move the task, an empty line opens
+//! the task, `b` goes back, `a <title>` adds a task, `x` toggles a todo item,
+//! `u` undoes, `:<command>` runs a command as in the TUI, and `q` quits.
+
+use anyhow::Result;
+use kanban_cli::app::{Action, ActiveContentRef, App, InputMode};
+use kanban_cli::view::{self, Tone};
+use std::io::{self, BufRead, Write};
+
+fn main() -> Result<()> {
+    let mut app = App::new()?;
+    let mut lines = io::stdin().lock().lines();
+    while !app.should_quit {
+        draw(&app);
+        print!("{:?}> ", app.input_mode);
+        io::stdout().flush()?;
+        let Some(line) = lines.next().transpose()? else { break };
+        for action in actions(&app, &line) {
+            app.update(action)?;
+        }
+    }
+    app.finish_session()
+}
+
+fn draw(app: &App) {
+    match (view::board(app), app.get_active_content()) {
+        (Some(board), _) => {
+            println!("\n{}", board.crumbs.join(" > "));
+            for column in board.columns {
+                let markers: String = column.markers.iter().map(|m| format!(" {}", m)).collect();
+                let over = if column.over_limit { " !" } else { "" };
+                println!("{} {} ({}){}{}", if column.selected { "▶" } else { " " }, column.title, column.count, over, markers);
+                for card in column.cards {
+                    let badges: Vec<String> = card.badges.iter().map(|b| match b.tone {
+                        Tone::Alert => format!("[{}!]", b.text),
+                        _ => format!("[{}]", b.text),
+                    }).collect();
+                    println!("    {} {} {} {}", if card.selected { "*" } else { " " }, card.kind.icon(), card.title, badges.join(" "));
+                }
+            }
+        }
+        (None, ActiveContentRef::Todo(items)) => {
+            println!("\n{}", app.get_breadcrumbs().join(" > "));
+            for (i, item) in items.iter().enumerate() {
+                println!("  {} [{}] {}", if i == app.pos.1 { "*" } else { " " }, if item.done { "x" } else { " " }, item.text);
+            }
+        }
+        (None, ActiveContentRef::Text(text)) => println!("\n{}\n{}", app.get_breadcrumbs().join(" > "), text),
+        (None, _) => println!("\n{}", app.get_breadcrumbs().join(" > ")),
+    }
+    if app.input_mode == InputMode::SelectType {
+        println!("Open as: b board, t todo list, n note");
+    }
+    if let Some((_, prompt)) = &app.pending_confirm {
+        println!("{} (y/n)", prompt);
+    }
+    if let Some(message) = &app.message {
+        println!("{}", message);
+    }
+}
+
+// What `line` does in the app's current mode
+fn actions(app: &App, line: &str) -> Vec<Action> {
+    let typed = |first: Action, text: &str| {
+        std::iter::once(first).chain(text.chars().map(Action::InputChar)).chain([Action::SubmitTask]).collect()
+    };
+    match app.input_mode {
+        InputMode::Normal => match line.trim() {
+            "" => vec![Action::DrillDown],
+            "h" => vec![Action::MoveLeft],
+            "j" => vec![Action::MoveDown],
+            "k" => vec![Action::MoveUp],
+            "l" => vec![Action::MoveRight],
+            "H" => vec![Action::MoveTaskLeft],
+            "L" => vec![Action::MoveTaskRight],
+            "b" => vec![Action::GoBack],
+            "x" => vec![Action::ToggleTodo],
+            "u" => vec![Action::Undo],
+            "q" => vec![Action::Quit],
+            command if command.starts_with(':') => typed(Action::EnterCommandMode, &command[1..]),
+            add if add.starts_with("a ") => typed(Action::EnterEditMode, &add[2..]),
+            _ => Vec::new(),
+        },
+        InputMode::Confirm => vec![if line.trim() == "y" { Action::ConfirmYes } else { Action::ConfirmNo }],
+        InputMode::SelectType => match line.trim() {
+            "b" => vec![Action::SelectBoard],
+            "t" => vec![Action::SelectTodo],
+            "n" => vec![Action::SelectText],
+            _ => vec![Action::ExitEditMode],
+        },
+        // Anything else is typing into the mode's input
+        _ if line.is_empty() => vec![Action::ExitEditMode],
+        _ => line.chars().map(Action::InputChar).chain([Action::SubmitTask]).collect(),
+    }
+}
@@ -0,0 +1,47 @@
+use crate::collate;
+use crate::config::{AdvisorConfig, Rule};
+use crate::model::{Board, Task};
+use crate::zone;
+use chrono::{DateTime, Utc};
+
+// Gentle suggestions about the board in view, shown one at a time in the
+// footer when `[advisor]` is enabled. Each rule reports at most one line per column.
+
+pub fn nudges(board: &Board, config: &AdvisorConfig, now: DateTime<Utc>) -> Vec<String> {
+    let today = zone::local(now).date();
+    let last = board.columns.len().saturating_sub(1);
+    let mut found = Vec::new();
+
+    for rule in &config.rules {
+        for (c, column) in board.columns.iter().enumerate() {
+            // Parked and finished tasks need no nudging, except past the WIP limit
+            let working = !column.icebox && c != last;
+            let count = |pred: &dyn Fn(&Task) -> bool| column.tasks.iter().filter(|t| !t.is_snoozed(today) && pred(t)).count();
+            let nudge = match rule {
+                Rule::Wip => column.wip_limit.filter(|_| column.over_wip_limit()).map(|limit| {
+                    format!("'{}' has {} tasks — over its WIP limit of {}", column.title, column.tasks.len(), limit)
+                }),
+                Rule::NoDue if config.no_due_columns.iter().any(|n| collate::eq(n, &column.title)) => {
+                    let n = count(&|t| t.due.is_none());
+                    (n > 0).then(|| format!("{} task{} in '{}' {} no due date", n, plural(n), column.title, if n == 1 { "has" } else { "have" }))
+                }
+                Rule::NoDue => None,
+                Rule::Stale if working => {
+                    let n = count(&|t| t.days_in_column(now) >= config.stale_days);
+                    (n > 0).then(|| format!("{} task{} in '{}' {} moved in {} days", n, plural(n), column.title, if n == 1 { "hasn't" } else { "haven't" }, config.stale_days))
+                }
+                Rule::Overdue if working => {
+                    let n = count(&|t| t.is_overdue(now));
+                    (n > 0).then(|| format!("{} task{} in '{}' {} overdue", n, plural(n), column.title, if n == 1 { "is" } else { "are" }))
+                }
+                Rule::Stale | Rule::Overdue => None,
+            };
+            found.extend(nudge);
+        }
+    }
+    found
+}
+
+fn plural(n: usize) -> &'static str {
+    if n == 1 { "" } else { "s" }
+}
@@ -0,0 +1,64 @@
+use crate::model::{Board, Task};
+use crate::search::{self, Match};
+use crate::zone;
+use chrono::{DateTime, Days, NaiveDate, Utc};
+
+// The agenda strip under a board: open tasks already overdue, then how many are
+// due on each of the next days, counting the boards inside it too. Only open
+// tasks count, as in `Board::open_tasks`.
+
+pub const DAYS: u64 = 7;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Slot {
+    Overdue,
+    Day(NaiveDate),
+}
+
+/// Overdue first, then today and the days after it.
+pub fn slots(today: NaiveDate) -> Vec<Slot> {
+    let days = (0..DAYS).filter_map(|n| today.checked_add_days(Days::new(n))).map(Slot::Day);
+    std::iter::once(Slot::Overdue).chain(days).collect()
+}
+
+impl Slot {
+    fn holds(self, task: &Task, now: DateTime<Utc>) -> bool {
+        match self {
+            Slot::Overdue => task.is_overdue(now),
+            Slot::Day(day) => task.due.is_some_and(|due| zone::due_date(due) == day) && !task.is_overdue(now),
+        }
+    }
+}
+
+/// Open tasks of `board` per slot of `slots`.
+pub fn counts(board: &Board, slots: &[Slot], now: DateTime<Utc>) -> Vec<usize> {
+    let mut counts = vec![0; slots.len()];
+    walk_open(board, &mut |_, _, _, task| {
+        for (count, slot) in counts.iter_mut().zip(slots) {
+            *count += usize::from(slot.holds(task, now));
+        }
+    });
+    counts
+}
+
+/// Open tasks of `board` in `slot`, located from `board`.
+pub fn tasks(board: &Board, slot: Slot, now: DateTime<Utc>) -> Vec<Match> {
+    let mut found = Vec::new();
+    walk_open(board, &mut |crumbs, location, column, task| {
+        if slot.holds(task, now) {
+            found.push(Match::new(crumbs, location, column, task, "due"));
+        }
+    });
+    found
+}
+
+/// Like `search::walk_tasks`, but only open tasks: none from a board's last column or its icebox.
+pub fn walk_open(board: &Board, visit: &mut search::Visitor) {
+    search::walk_tasks(board, &mut |crumbs, location, column, task| {
+        let (c, _) = location[location.len() - 1];
+        let last = board.board_at(&location[..location.len() - 1]).map_or(0, |b| b.columns.len().saturating_sub(1));
+        if (c < last || last == 0) && !column.icebox {
+            visit(crumbs, location, column, task);
+        }
+    });
+}
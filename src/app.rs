@@ -1,10 +1,47 @@
-use crate::model::{Board, Task, TaskContent, TodoItem};
-use anyhow::Result;
-use bincode::config;
-use std::fs;
-use std::path::PathBuf;
+use crate::advisor;
+use crate::agenda;
+use crate::autocomplete::{self, Suggestion};
+use crate::backup;
+use crate::collate;
+use crate::command::{self, Command, History};
+use crate::config::{AutosaveStrategy, Badge, Config, ConfirmOp, SummaryMode};
+use crate::date;
+use crate::defaults::TaskDefaults;
+use crate::editor::{Edit, Editor};
+use crate::demo;
+use crate::export;
+use crate::github;
+use crate::import;
+use crate::lanes::{self, Lanes};
+use crate::todotxt;
+use crate::ics;
+use crate::link;
+use crate::model::{self, Board, Subscription, Task, TaskContent, TodoItem, TodoLayout, WaitingOn};
+use crate::reminder;
+use crate::replace::{self, Answer, ReplaceSession};
+use crate::reset::{self, ColumnReset, ResetPeriod};
+use crate::search;
+use crate::session::Session;
+use crate::sort::{self, ColumnSort};
+use crate::stats::{self, WorkspaceStats};
+use crate::undo::UndoStack;
+use crate::validate;
+use crate::storage;
+use crate::zone;
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate, Utc};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use uuid::Uuid;
+use std::time::{Duration, Instant, SystemTime};
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
 
-const DB_FILE: &str = "kanban.db";
+// How often the TUI looks for reminders that came due
+const REMINDER_CHECK: Duration = Duration::from_secs(30);
+// How often the TUI looks for saves to the board file by headless commands
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum InputMode {
@@ -12,11 +49,73 @@ pub enum InputMode {
     Editing,
     EditingColumn, // New mode for adding columns
     SelectType, // New mode for choosing content type
+    Command, // `:` command line
+    EditingBoardNotes,
+    Confirm, // y/n prompt for `pending_confirm`
+    EditingBlockReason, // Reason asked for before `pending_move` happens
+    Checklist, // Every open todo item in the tree, see `checklist`
+    Replacing, // Stepping through `replace` occurrences
+    Badges, // Menu of card badges, see `badge_menu`
+    EditingDescription, // Description of the selected task
+    Renaming, // Title of `rename_target`
+    Searching, // Typing a `/` query for the current board
+    Results, // Matches of `:find` across the tree, see `results`
+    Workspaces, // Switcher listing `workspace_names`
+    Agenda, // Picking a day on the agenda strip, see `agenda_idx`
+    Jump, // Typing one of the `jump_labels` shown on the cards
+    EditingNote, // Text content in `note_editor`
+    Archive, // Archived tasks of the board in view, newest first, see `archive_idx`
+    Trash, // Deleted tasks and columns of the board in view, newest first, see `trash_idx`
+    EditingColumnDefaults, // What tasks created in the selected column start with
 }
 
+impl InputMode {
+    /// Whether the mode types into `input_buffer`, and so takes pasted text.
+    pub fn takes_text(&self) -> bool {
+        matches!(self, InputMode::Editing | InputMode::EditingColumn | InputMode::Command | InputMode::EditingBoardNotes
+            | InputMode::EditingBlockReason | InputMode::EditingDescription | InputMode::Renaming | InputMode::Searching
+            | InputMode::EditingColumnDefaults)
+    }
+}
+
+// Triage views one chord away, on top of the snoozed and waiting filters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QuickFilter {
+    DueThisWeek, // Due Monday to Sunday of the current week
+    Overdue,
+    HighPriority, // Tagged `#pri-A`, as todo.txt's `(A)` is imported
+}
+
+impl QuickFilter {
+    /// What the board shows with it, as in "Showing only …".
+    pub fn label(self) -> &'static str {
+        match self {
+            QuickFilter::DueThisWeek => "tasks due this week",
+            QuickFilter::Overdue => "overdue tasks",
+            QuickFilter::HighPriority => "high-priority tasks",
+        }
+    }
+
+    pub fn keeps(self, task: &Task, now: DateTime<Utc>) -> bool {
+        match self {
+            QuickFilter::DueThisWeek => task.due.is_some_and(|due| {
+                let (day, today) = (zone::due_date(due), zone::local(now).date());
+                day.iso_week() == today.iso_week()
+            }),
+            QuickFilter::Overdue => task.is_overdue(now),
+            QuickFilter::HighPriority => autocomplete::tags_in(&task.title).any(|t| collate::eq(t, "pri-A")),
+        }
+    }
+}
+
+// Keys for jump labels, home row first
+const JUMP_KEYS: &str = "asdfghjklqwertyuiopzxcvbnm";
+
+/// Everything a user can do; frontends turn their input into these for `App::update`.
 #[derive(Debug, Clone)]
 pub enum Action {
     Quit,
+    Save,
 
     MoveUp,
     MoveDown,
@@ -24,12 +123,15 @@ pub enum Action {
     MoveRight,
     MoveTaskLeft,
     MoveTaskRight,
+    MoveLane(i32), // To the first card of the next (1) or previous (-1) swimlane
     DrillDown,
     GoBack,
     EnterEditMode,
     EnterAddColumnMode, // New action
     ExitEditMode,
     InputChar(char),
+    InputPaste(String), // Bracketed paste, cleaned of control characters
+    EditNote(Edit),
     InputBackspace,
     SubmitTask,
     DeleteTask,
@@ -38,58 +140,688 @@ pub enum Action {
     SelectBoard,
     SelectTodo,
     SelectText,
+    EnterCommandMode,
+    HistoryPrev,
+    HistoryNext,
+    CompleteCommand,
+    SuggestionPrev,
+    SuggestionNext,
+    AcceptSuggestion,
+    ConfirmYes,
+    ConfirmNo,
+    JumpToLevel(usize), // Breadcrumb index, 0 = main board
+    ToggleLastBoard, // Back to the board viewed before this one, and again to return
+    BeginChord(char), // First key of a multi-key binding such as `gd`
+    CancelChord, // Esc or a key no chord continues with after the first key
+    ToggleBoardNotes,
+    EditBoardNotes,
+    ToggleDetails,
+    EditDescription,
+    ArchiveTask,
+    ToggleArchive,
+    ToggleTrash,
+    EditExternally, // Hand the note or description to `$EDITOR`, see `external_edit`
+    ExternalEdited(ExternalEdit), // What came back from the editor
+    EnterRenameMode,
+    EnterRenameColumnMode,
+    EditColumnDefaults,
+    DeleteColumn,
+    ConfirmDeleteColumn(ColumnTasks),
+    MoveColumn(i32), // Swap the selected column with its left (-1) or right (1) neighbor
+    ToggleChecklist,
+    ResizeTodo(i16), // Grow (or shrink) the pending pane by this many percent
+    ToggleTodoMerge,
+    BeginSnooze, // Opens the command line at `snooze `
+    BeginDue,    // Opens the command line at `due `
+    ToggleSnoozed,
+    BeginWait, // Opens the command line at `wait `
+    ToggleWaitingFilter,
+    ToggleQuickFilter(QuickFilter),
+    Replace(Answer),
+    ToggleBadgeMenu,
+    MoveBadge(i32), // Shift the selected badge earlier or later on cards
+    Undo,
+    Redo,
+    BeginSearch,
+    BeginFind, // Opens the command line at `find `
+    ToggleWorkspaces,
+    CloseResults,
+    SearchNext(i32), // Jump to the next (1) or previous (-1) match of `board_search`
+    ToggleAgendaFocus,
+    ToggleJump, // Label the cards on the board to jump to one, or stop
+    JumpKey(char), // Next key of a label while jumping
+    OpenAgendaSlot(usize), // Index into `agenda::slots`, e.g. from a click on the strip
+    SelectTask(Uuid), // Puts the cursor on this task of the board in view, e.g. from a click
+    SelectColumn(usize), // Moves the cursor into this column of the board in view
+}
+
+// A cross-cutting step of `App::update`. `before` runs ahead of the action and can
+// drop it by returning false; `after` runs once it is done. Undo recording, activity
+// logs and the like plug in here rather than into the action match.
+struct Middleware {
+    before: fn(&mut App, &Action) -> bool,
+    after: fn(&mut App, &Action),
 }
 
+const MIDDLEWARE: &[Middleware] = &[
+    Middleware { before: App::mark_damaged, after: App::done },
+    Middleware { before: App::end_chord, after: App::done },
+    Middleware { before: App::clear_message, after: App::done },
+    Middleware { before: App::validate, after: App::done },
+    Middleware { before: App::proceed, after: App::schedule_autosave },
+    Middleware { before: App::begin_undo_step, after: App::record_undo },
+    Middleware { before: App::proceed, after: App::guard_frozen },
+    Middleware { before: App::proceed, after: App::guard_subscribed },
+    Middleware { before: App::resolve_positions, after: App::record_positions },
+    Middleware { before: App::proceed, after: App::keep_sorted },
+    Middleware { before: App::invalidate_summaries, after: App::done },
+];
+
+/// The open workspace, where the user is in it, and the state of the UI around it.
+/// Frontends read it (and `view`) to draw and change it only through `update`.
 pub struct App {
-    pub root: Board,
-    pub path: Vec<(usize, usize)>, // Path to current context (col_idx, task_idx)
-    pub cursor: (usize, usize),    // (col, row) or (item_idx, 0) for lists
+    pub root: Board, // The open workspace
+    pub workspace: usize, // Index of `root` in `workspaces`
+    workspaces: Vec<Board>, // Every workspace in the file; the open one's slot is empty while it is `root`
+    pub workspace_idx: usize, // Row selected in the workspace switcher
+    session: Session, // Where tasks were when the TUI opened, for the summary on quit
+    pub nudges: Vec<String>, // Advisor suggestions for the board in view, shown in turn
+    nudge_idx: usize,
+    next_nudge: Option<Instant>, // When the advisor runs next; None when it is off
+    next_reminder: Instant, // When due reminders are looked for next
+    next_watch: Instant, // When the board file is checked for outside changes next
+    disk_stamp: Option<SystemTime>, // `storage::stamp` of the board file as last loaded or saved here
+    disk_changed: bool, // Another process saved the file while there were unsaved changes here
+    // Where the user is, by task id, so the view follows tasks that moved or were
+    // reordered. Positions in `location`/`pos` are resolved from these by `resync`.
+    pub path: Vec<Uuid>,        // Tasks opened from the main board down
+    pub cursor: Option<Uuid>,   // Task under the cursor on a board
+    viewing: (Vec<Uuid>, Option<Uuid>), // `path` and `cursor` after the last action
+    alternate: Option<(Vec<Uuid>, Option<Uuid>)>, // Where the user was before opening the board in `viewing`
+    pub location: Vec<(usize, usize)>, // `path` as (col_idx, task_idx) steps
+    pub pos: (usize, usize),    // (col, row) on boards, (0, row) in lists
     pub input_mode: InputMode,
     pub input_buffer: String,
+    pub note_editor: Option<Editor>, // While in `InputMode::EditingNote`
     pub should_quit: bool,
     pub show_help: bool,
     pub dirty: bool,
+    pub redraw: bool, // Screen is out of date; the event loop clears it after drawing
+    pub history: History,
+    pub message: Option<String>, // One-shot feedback shown in the footer
+    pub pending_key: Option<char>, // Set between the keys of a chord
+    pub show_board_notes: bool,
+    pub show_details: bool, // Detail panel for the selected task beside the board
+    pub rename_target: Option<RenameTarget>,
+    pub suggestions: Vec<Suggestion>, // Tag/icon dropdown under the input popup
+    pub suggestion_idx: usize,
+    pub config: Config,
+    pub pending_confirm: Option<(ConfirmOp, String)>, // Operation awaiting y/n, with its prompt
+    pub last_saved: Option<DateTime<Local>>,
+    dirty_since: Option<Instant>, // First unsaved change, for debounced autosave
+    pub save_failure: Option<SaveFailure>, // Last save error, shown until a save succeeds
+    pub pending_move: Option<usize>, // Target column of a move waiting for its blocking reason
+    pending_export: Option<PathBuf>, // File an export will replace once confirmed
+    pub checklist: Vec<ChecklistEntry>, // Built when the checklist view opens
+    pub checklist_idx: usize,
+    pub show_snoozed: bool, // Snoozed tasks are hidden from the board unless set
+    pub only_waiting: bool, // Board shows just the tasks waiting on someone
+    pub quick_filter: Option<QuickFilter>, // Built-in view picked with a `g` chord
+    pub replace: Option<ReplaceSession>,
+    pub badge_idx: usize, // Row selected in the badge menu
+    pub board_search: String, // Last `/` query; matching cards are highlighted while set
+    pub results: Vec<search::Match>, // Filled by `:find`, shown while in `InputMode::Results`
+    pub results_idx: usize,
+    pub results_query: String,
+    pub show_agenda: bool, // Agenda strip under the board
+    pub agenda_idx: usize, // Slot selected on the strip, see `agenda::slots`
+    pub jump_input: String, // Keys of a jump label typed so far
+    pub external_edit: Option<ExternalEdit>, // Waiting for the frontend to open it in an editor
+    pub archive_idx: usize, // Row selected in the archive view
+    pub trash_idx: usize, // Row selected in the trash view
+    board_summaries: RefCell<HashMap<Uuid, BoardSummary>>, // By the task holding the board, filled while drawing
+    undo: UndoStack,
+    undo_from: (Vec<Uuid>, Option<Uuid>), // `path` and `cursor` before the current action
+    was_dirty: bool, // `dirty` before the current action, which clears it to see if it changes anything
+    in_memory: bool, // The demo: saving, reminder and session logs do nothing
+}
+
+// What happens to the tasks of a deleted column
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColumnTasks {
+    Move, // Into the column to the left, or the right for the first one
+    Drop,
+}
+
+// What the rename popup writes back to, fixed when it opens
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RenameTarget {
+    Task,            // Under the board cursor
+    TodoItem(usize), // Index into the open todo list
+    Column(usize),
+}
+
+// Long-form text of a task, edited outside the TUI: the frontend takes it
+// from `external_edit`, and sends back `Action::ExternalEdited` with `text`
+// as saved
+#[derive(Debug, Clone)]
+pub struct ExternalEdit {
+    pub task: Uuid,
+    pub field: TextField,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextField {
+    Description,
+    Note, // Text content
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BoardSummary {
+    pub columns: usize,
+    pub open: usize,   // See `Board::open_tasks`
+    pub alerts: usize, // Tasks at any depth that `need_attention`
+}
+
+// One todo item in the checklist view
+pub struct ChecklistEntry {
+    pub task: Vec<(usize, usize)>, // Path to the task holding the todo list
+    pub group: String,             // "Board > Task" heading the item is listed under
+    pub item: usize,               // Index into the task's items
+    pub text: String,
+    pub done: bool, // Checked items stay listed until the view is reopened
+}
+
+pub struct SaveFailure {
+    pub error: String,
+    pub attempts: u32,
+    retry_at: Instant,
 }
 
 impl App {
+    /// Opens the board file, `[board] file` in the config (`kanban.db` by default).
     pub fn new() -> Result<Self> {
-        // Simple file path
-        let path = PathBuf::from(DB_FILE);
-        
-        let root = if path.exists() {
-            let data = fs::read(&path)?;
-            // Try Bincode
-            if let Ok(board) = bincode::serde::decode_from_slice(&data, config::standard()).map(|(b, _)| b) {
-                board
-            } else {
-                 Board::default()
-            }
-        } else {
-             Board::default()
+        let config = Config::load()?;
+        let mut workspaces = storage::load_workspaces(&config.board.file)?;
+
+        // Recurring column resets fire on the first start after their period rolls over
+        let today = zone::today();
+        let reset_count: usize = workspaces.boards.iter_mut().map(|root| reset::run_due(root, today)).sum();
+        // And the trash lets go of what has been in it long enough
+        let purged: usize = match Utc::now().checked_sub_days(Days::new(config.trash.purge_days)) {
+            Some(cutoff) if config.trash.purge_days > 0 => workspaces.boards.iter_mut().map(|root| root.purge_trash(cutoff)).sum(),
+            _ => 0,
         };
+        let mut app = Self::open(config, workspaces, false);
+        if reset_count + purged > 0 {
+            app.save()?;
+        }
+        if reset_count > 0 {
+            app.message = Some(format!("Recurring reset: {} task(s) archived or moved", reset_count));
+        }
+        Ok(app)
+    }
+
+    /// The demo board with default settings, kept in memory: nothing on disk is
+    /// read or written, command history included.
+    pub fn demo() -> Self {
+        Self::open(Config::default(), storage::Workspaces { active: 0, boards: vec![demo::board()] }, true)
+    }
+
+    fn open(config: Config, mut workspaces: storage::Workspaces, in_memory: bool) -> Self {
+        let session = Session::start(&workspaces.boards.iter().collect::<Vec<_>>());
+        let root = std::mem::take(&mut workspaces.boards[workspaces.active]);
 
-        Ok(Self {
+        let mut app = Self {
+            session,
+            nudges: Vec::new(),
+            nudge_idx: 0,
+            next_nudge: config.advisor.enabled.then(Instant::now),
+            next_reminder: Instant::now(),
+            next_watch: Instant::now() + WATCH_INTERVAL,
+            disk_stamp: if in_memory { None } else { storage::stamp(&config.board.file) },
+            disk_changed: false,
+            workspace: workspaces.active,
+            workspaces: workspaces.boards,
+            workspace_idx: 0,
+            undo: UndoStack::new(&root),
+            undo_from: (Vec::new(), None),
+            was_dirty: false,
             root,
             path: Vec::new(),
-            cursor: (0, 0),
+            cursor: None,
+            viewing: (Vec::new(), None),
+            alternate: None,
+            location: Vec::new(),
+            pos: (0, 0),
             input_mode: InputMode::Normal,
             input_buffer: String::new(),
+            note_editor: None,
             should_quit: false,
             show_help: false,
             dirty: false,
-        })
+            redraw: true,
+            history: if in_memory { History::default() } else { History::load(&config.board.file) },
+            message: None,
+            pending_key: None,
+            show_board_notes: true,
+            show_details: false,
+            rename_target: None,
+            suggestions: Vec::new(),
+            suggestion_idx: 0,
+            config,
+            pending_confirm: None,
+            last_saved: None,
+            dirty_since: None,
+            save_failure: None,
+            pending_move: None,
+            pending_export: None,
+            external_edit: None,
+            archive_idx: 0,
+            trash_idx: 0,
+            checklist: Vec::new(),
+            checklist_idx: 0,
+            show_snoozed: false,
+            only_waiting: false,
+            quick_filter: None,
+            replace: None,
+            badge_idx: 0,
+            board_search: String::new(),
+            results: Vec::new(),
+            results_idx: 0,
+            results_query: String::new(),
+            show_agenda: false,
+            agenda_idx: 0,
+            jump_input: String::new(),
+            board_summaries: RefCell::default(),
+            in_memory,
+        };
+        app.move_cursor(0, 0); // The first task may be snoozed
+        app.anchor();
+        app
     }
 
+    /// Writes every workspace to the board file, unless another process saved it
+    /// since it was loaded: that version is kept until `reload` or `overwrite`.
     pub fn save(&mut self) -> Result<()> {
-        let bytes = bincode::serde::encode_to_vec(&self.root, config::standard())?;
-        fs::write(DB_FILE, bytes)?;
+        if !self.in_memory {
+            let path = self.config.board.file.clone();
+            let _lock = storage::lock(&path)?;
+            if storage::stamp(&path) != self.disk_stamp {
+                self.disk_changed = true;
+                bail!("{} was changed by another kanban process; :reload takes that version, :write! keeps this one", path.display());
+            }
+            storage::save_workspaces(&path, self.workspace, self.all_workspaces())?;
+            self.disk_stamp = storage::stamp(&path);
+            self.last_saved = Some(Local::now());
+            // The save itself worked, so a failed copy is only reported
+            if let Err(e) = backup::run(&path, &self.config.backup, zone::local(Utc::now())) {
+                self.message = Some(format!("Backup failed: {:#}", e));
+            }
+        }
+        self.dirty = false;
+        self.was_dirty = false; // Saved mid-action, so `record_undo` leaves it clean
+        self.dirty_since = None;
+        self.disk_changed = false;
+        self.save_failure = None;
+        Ok(())
+    }
+
+    // Saves over whatever another process wrote since the file was loaded
+    fn overwrite(&mut self) {
+        if !self.in_memory {
+            self.disk_stamp = storage::stamp(&self.config.board.file);
+        }
+        self.save_or_report();
+    }
+
+    /// Loads the board file again as another process left it, dropping unsaved
+    /// changes. The view follows the tasks it was on; undo goes back to before.
+    fn reload(&mut self) -> Result<()> {
+        if self.in_memory {
+            return Ok(());
+        }
+        let path = self.config.board.file.as_path();
+        // Stamped first: a save while reading is then picked up by the next `watch`
+        let stamp = storage::stamp(path);
+        let mut workspaces = storage::load_workspaces(path)?;
+        if self.workspace >= workspaces.boards.len() {
+            self.workspace = workspaces.active;
+        }
+        self.root = std::mem::take(&mut workspaces.boards[self.workspace]);
+        self.workspaces = workspaces.boards;
+        self.disk_stamp = stamp;
+        self.undo.record(&mut self.root, self.path.clone(), self.cursor);
         self.dirty = false;
+        self.was_dirty = false;
+        self.dirty_since = None;
+        self.disk_changed = false;
+        self.save_failure = None;
+        self.resync();
+        self.redraw = true;
         Ok(())
     }
 
+    // Picks up saves by `kanban add` and other headless commands while the TUI is open.
+    // With unsaved changes here, `save` refuses to overwrite them and says so instead.
+    fn watch(&mut self) {
+        self.next_watch = Instant::now() + WATCH_INTERVAL;
+        if self.in_memory || storage::stamp(&self.config.board.file) == self.disk_stamp {
+            return;
+        }
+        if self.dirty {
+            if !std::mem::replace(&mut self.disk_changed, true) {
+                self.message = Some(format!("{} changed on disk: :reload to take it, :write! to keep yours", self.config.board.file.display()));
+                self.redraw = true;
+            }
+            return;
+        }
+        self.message = Some(match self.reload() {
+            Ok(()) => format!("Reloaded {}: changed by another kanban process", self.config.board.file.display()),
+            Err(e) => format!("reload failed: {:#}", e),
+        });
+        self.redraw = true;
+    }
+
+    /// Saves, recording a failure (and scheduling a retry with exponential backoff)
+    /// instead of returning it. The dirty flag stays set until a save succeeds.
+    fn save_or_report(&mut self) {
+        if let Err(e) = self.save() {
+            let attempts = self.save_failure.as_ref().map_or(0, |f| f.attempts) + 1;
+            let backoff = Duration::from_secs(1 << attempts.min(6)); // 2s, 4s, ... 64s
+            self.save_failure = Some(SaveFailure {
+                error: format!("{:#}", e),
+                attempts,
+                retry_at: Instant::now() + backoff,
+            });
+        }
+    }
+
+    /// How long the event loop may block before `tick` is due; `None` blocks indefinitely.
+    pub fn next_tick(&self) -> Option<Duration> {
+        let retry = self.save_failure.as_ref().map(|f| f.retry_at.saturating_duration_since(Instant::now()));
+        let advise = self.next_nudge.map(|at| at.saturating_duration_since(Instant::now()));
+        let remind = self.next_reminder.saturating_duration_since(Instant::now());
+        let watch = (!self.in_memory).then(|| self.next_watch.saturating_duration_since(Instant::now()));
+        [retry, self.debounce_remaining(), advise, Some(remind), watch].into_iter().flatten().min()
+    }
+
+    fn debounce_remaining(&self) -> Option<Duration> {
+        let since = self.dirty_since?;
+        if self.config.autosave.strategy != AutosaveStrategy::Debounce { return None; }
+        Some(Duration::from_secs(self.config.autosave.interval_secs).saturating_sub(since.elapsed()))
+    }
+
+    /// Called when `next_tick` elapsed without input.
+    pub fn tick(&mut self) {
+        if self.next_nudge.is_some_and(|at| at <= Instant::now()) {
+            self.advise();
+        }
+        if self.next_reminder <= Instant::now() {
+            self.remind();
+        }
+        if self.next_watch <= Instant::now() {
+            self.watch();
+        }
+        if !self.dirty { return; }
+        let retry_due = self.save_failure.as_ref().is_some_and(|f| f.retry_at <= Instant::now());
+        if retry_due || self.debounce_remaining() == Some(Duration::ZERO) {
+            self.save_or_report();
+            self.redraw = true;
+        }
+    }
+
+    // Re-checks the board in view and moves on to the next suggestion
+    fn advise(&mut self) {
+        let nudges = match self.get_active_content() {
+            ActiveContentRef::Board(board) => advisor::nudges(board, &self.config.advisor, Utc::now()),
+            _ => Vec::new(),
+        };
+        self.nudge_idx = if nudges.is_empty() { 0 } else { (self.nudge_idx + 1) % nudges.len() };
+        self.nudges = nudges;
+        self.next_nudge = Some(Instant::now() + Duration::from_secs(self.config.advisor.interval_secs.max(1)));
+        self.redraw = true;
+    }
+
+    pub fn nudge(&self) -> Option<&str> {
+        self.nudges.get(self.nudge_idx).map(String::as_str)
+    }
+
+    fn autosave(&mut self, navigated: bool) {
+        if !self.dirty { return; }
+        self.dirty_since.get_or_insert_with(Instant::now);
+        let due = match self.config.autosave.strategy {
+            AutosaveStrategy::Change => true,
+            AutosaveStrategy::Navigation => navigated || self.should_quit,
+            AutosaveStrategy::Debounce => self.should_quit,
+            AutosaveStrategy::Manual => false,
+        };
+        // While a save is failing, retries follow the backoff schedule in `tick`
+        if due && self.save_failure.is_none() {
+            self.save_or_report();
+        }
+    }
+
+    /// Applies `action` through the `MIDDLEWARE` pipeline: every `before` step in
+    /// order, the action itself, then the `after` steps in reverse. Sets `redraw`,
+    /// and `should_quit` once the user has quit.
     pub fn update(&mut self, action: Action) -> Result<()> {
+        for step in MIDDLEWARE {
+            if !(step.before)(self, &action) {
+                return Ok(());
+            }
+        }
+        self.dispatch(action.clone());
+        for step in MIDDLEWARE.iter().rev() {
+            (step.after)(self, &action);
+        }
+        Ok(())
+    }
+
+    // --- Middleware steps ---
+
+    fn proceed(&mut self, _: &Action) -> bool {
+        true
+    }
+
+    fn done(&mut self, _: &Action) {}
+
+    fn mark_damaged(&mut self, _: &Action) -> bool {
+        self.redraw = true;
+        true
+    }
+
+    // Whatever follows the first key of a chord completes or cancels it
+    fn end_chord(&mut self, action: &Action) -> bool {
+        if !matches!(action, Action::BeginChord(_)) {
+            self.pending_key = None;
+        }
+        true
+    }
+
+    fn clear_message(&mut self, _: &Action) -> bool {
+        self.message = None;
+        true
+    }
+
+    // Only closing it gets past the help overlay
+    fn validate(&mut self, action: &Action) -> bool {
+        !self.show_help || matches!(action, Action::ToggleHelp | Action::GoBack)
+    }
+
+    fn resolve_positions(&mut self, _: &Action) -> bool {
+        self.resync();
+        true
+    }
+
+    fn record_positions(&mut self, _: &Action) {
+        // Whatever happened, don't leave the cursor on a hidden task
+        if self.input_mode == InputMode::Normal {
+            self.move_cursor(0, 0);
+        }
+        self.anchor();
+        // Leaving a board keeps it, with its cursor, for `ToggleLastBoard`
+        if self.path != self.viewing.0 {
+            self.alternate = Some(std::mem::replace(&mut self.viewing, (self.path.clone(), self.cursor)));
+        } else {
+            self.viewing.1 = self.cursor;
+        }
+    }
+
+    // Reorders auto-sorted columns after a change, keeping the cursor on its task
+    fn keep_sorted(&mut self, _: &Action) {
+        if !self.dirty {
+            return;
+        }
+        self.anchor();
+        if sort::apply(&mut self.root) {
+            self.resync();
+        }
+    }
+
+    // Anything but moving the cursor may change what a sub-board holds
+    fn invalidate_summaries(&mut self, action: &Action) -> bool {
+        if !matches!(action, Action::MoveUp | Action::MoveDown | Action::MoveLeft | Action::MoveRight | Action::MoveLane(_) | Action::SelectTask(_) | Action::SelectColumn(_)) {
+            self.board_summaries.get_mut().clear();
+        }
+        true
+    }
+
+    fn begin_undo_step(&mut self, _: &Action) -> bool {
+        self.undo_from = (self.path.clone(), self.cursor);
+        self.was_dirty = std::mem::take(&mut self.dirty);
+        true
+    }
+
+    // Runs ahead of `schedule_autosave`, which clears `dirty` once saved
+    fn record_undo(&mut self, action: &Action) {
+        if self.dirty && !matches!(action, Action::Undo | Action::Redo) {
+            let (path, cursor) = std::mem::take(&mut self.undo_from);
+            self.undo.record(&mut self.root, path, cursor);
+        }
+        self.dirty |= self.was_dirty;
+    }
+
+    // Takes back a change that touched a frozen column or board, before it is recorded
+    fn guard_frozen(&mut self, action: &Action) {
+        if !self.dirty || matches!(action, Action::Undo | Action::Redo) {
+            return;
+        }
+        let Some(before) = self.undo.frozen_base() else { return };
+        if let Some(what) = model::frozen_change(&before, &self.root) {
+            self.root = before;
+            (self.path, self.cursor) = self.undo_from.clone();
+            self.resync();
+            self.dirty = false;
+            self.message = Some(format!("{} is frozen (:freeze to unfreeze it)", what));
+        }
+    }
+
+    // Takes back an edit to a task mirrored from a calendar, which the next refresh would undo anyway
+    fn guard_subscribed(&mut self, action: &Action) {
+        if !self.dirty || matches!(action, Action::Undo | Action::Redo) {
+            return;
+        }
+        let Some(before) = self.undo.subscribed_base() else { return };
+        if let Some(title) = ics::read_only_change(&before, &self.root) {
+            self.root = before;
+            (self.path, self.cursor) = self.undo_from.clone();
+            self.resync();
+            self.dirty = false;
+            self.message = Some(format!("'{}' comes from a calendar; it changes on :refresh", title));
+        }
+    }
+
+    fn schedule_autosave(&mut self, action: &Action) {
+        let navigated = matches!(action, Action::DrillDown | Action::GoBack | Action::JumpToLevel(_) | Action::ToggleLastBoard | Action::ToggleChecklist);
+        self.autosave(navigated);
+    }
+
+    fn dispatch(&mut self, action: Action) {
+        let in_checklist = self.input_mode == InputMode::Checklist;
+        let in_badges = self.input_mode == InputMode::Badges;
+        let in_results = self.input_mode == InputMode::Results;
+        let in_workspaces = self.input_mode == InputMode::Workspaces;
+        let in_agenda = self.input_mode == InputMode::Agenda;
+        let in_archive = self.input_mode == InputMode::Archive;
+        let in_trash = self.input_mode == InputMode::Trash;
+
         match action {
-            Action::Quit => self.should_quit = true,
+            // Checklist view reuses the list keys
+            Action::MoveUp if in_checklist => self.checklist_idx = self.checklist_idx.saturating_sub(1),
+            Action::MoveDown if in_checklist => {
+                if self.checklist_idx + 1 < self.checklist.len() {
+                    self.checklist_idx += 1;
+                }
+            },
+            Action::ToggleTodo if in_checklist => self.toggle_checklist_item(),
+            Action::DrillDown if in_checklist => self.open_checklist_item(),
+            Action::ToggleChecklist => self.toggle_checklist(),
+
+            Action::MoveUp if in_results => self.results_idx = self.results_idx.saturating_sub(1),
+            Action::MoveDown if in_results => self.results_idx = (self.results_idx + 1).min(self.results.len().saturating_sub(1)),
+            Action::DrillDown if in_results => self.open_result(),
+            Action::CloseResults => {
+                self.input_mode = InputMode::Normal;
+                self.results.clear();
+            }
+            Action::MoveUp if in_archive => self.archive_idx = self.archive_idx.saturating_sub(1),
+            Action::MoveDown if in_archive => self.archive_idx = (self.archive_idx + 1).min(self.archived().len().saturating_sub(1)),
+            Action::DrillDown if in_archive => self.restore_archived(),
+            Action::ToggleArchive => self.toggle_archive(),
+            Action::ArchiveTask => self.archive_task(),
+            Action::MoveUp if in_trash => self.trash_idx = self.trash_idx.saturating_sub(1),
+            Action::MoveDown if in_trash => self.trash_idx = (self.trash_idx + 1).min(self.trashed().len().saturating_sub(1)),
+            Action::DrillDown if in_trash => self.restore_trashed(),
+            Action::ToggleTrash => self.toggle_trash(),
+            Action::MoveUp if in_workspaces => self.workspace_idx = self.workspace_idx.saturating_sub(1),
+            Action::MoveDown if in_workspaces => self.workspace_idx = (self.workspace_idx + 1).min(self.workspaces.len() - 1),
+            Action::DrillDown if in_workspaces => {
+                self.input_mode = InputMode::Normal;
+                self.switch_workspace(self.workspace_idx);
+            }
+            Action::ToggleWorkspaces => {
+                self.input_mode = if in_workspaces { InputMode::Normal } else { InputMode::Workspaces };
+                self.workspace_idx = self.workspace;
+            }
+            Action::MoveLeft if in_agenda => self.agenda_idx = self.agenda_idx.saturating_sub(1),
+            Action::MoveRight if in_agenda => self.agenda_idx = (self.agenda_idx + 1).min(agenda::DAYS as usize),
+            Action::DrillDown if in_agenda => self.open_agenda_slot(self.agenda_idx),
+            Action::OpenAgendaSlot(i) => self.open_agenda_slot(i),
+            Action::ToggleAgendaFocus => {
+                self.input_mode = if in_agenda { InputMode::Normal } else { InputMode::Agenda };
+                self.show_agenda = true;
+            }
+            Action::ToggleJump => self.toggle_jump(),
+            Action::JumpKey(key) => self.jump_key(key),
+            Action::SelectTask(id) => {
+                self.cursor = Some(id);
+                self.resync();
+            }
+            Action::SelectColumn(c) => {
+                if let ActiveContentRef::Board(board) = self.get_active_content()
+                    && c < board.columns.len() {
+                        self.pos.0 = c;
+                        self.move_cursor(0, 0);
+                    }
+            }
+            Action::BeginFind => {
+                self.input_buffer = "find ".to_string();
+                self.input_mode = InputMode::Command;
+            }
+
+            Action::MoveUp if in_badges => self.badge_idx = self.badge_idx.saturating_sub(1),
+            Action::MoveDown if in_badges => self.badge_idx = (self.badge_idx + 1).min(Badge::ALL.len() - 1),
+            Action::ToggleTodo if in_badges => self.toggle_badge(),
+            Action::MoveBadge(delta) => self.move_badge(delta),
+            Action::ToggleBadgeMenu => {
+                self.input_mode = if in_badges { InputMode::Normal } else { InputMode::Badges };
+                self.badge_idx = 0;
+            },
+
+            Action::Quit => self.request_quit(),
+            Action::Save => self.save_or_report(),
 
             Action::ToggleHelp => self.show_help = !self.show_help,
             
@@ -98,56 +830,180 @@ impl App {
             Action::MoveDown => self.move_cursor(0, 1),
             Action::MoveLeft => self.move_cursor(-1, 0),
             Action::MoveRight => self.move_cursor(1, 0),
+            Action::MoveLane(dy) => self.move_lane(dy),
             Action::MoveTaskLeft => self.move_task_horizontal(-1),
             Action::MoveTaskRight => self.move_task_horizontal(1),
             
             Action::DrillDown => self.handle_drill_down(),
             Action::GoBack => self.go_back(),
+            Action::JumpToLevel(level) => self.jump_to_level(level),
+            Action::ToggleLastBoard => self.toggle_last_board(),
             
             // Editing
             Action::EnterEditMode => {
-                if !self.show_help {
-                     // Check if valid context for adding tasks (Board or Todo)
-                     // Using short block to limit borrow scope
-                     let can_edit = matches!(self.get_active_content(), ActiveContentRef::Board(_) | ActiveContentRef::Todo(_));
-                     if can_edit {
-                        self.input_mode = InputMode::Editing;
-                     } 
+                // Check if valid context for adding tasks (Board or Todo)
+                let can_edit = matches!(self.get_active_content(), ActiveContentRef::Board(_) | ActiveContentRef::Todo(_));
+                if can_edit {
+                    self.input_mode = InputMode::Editing;
                 }
             },
             Action::EnterAddColumnMode => {
-                if !self.show_help {
-                    // Only allow adding columns if we are viewing a board
-                    if let ActiveContentRef::Board(_) = self.get_active_content() {
-                        self.input_mode = InputMode::EditingColumn;
-                    }
+                // Only allow adding columns if we are viewing a board
+                if let ActiveContentRef::Board(_) = self.get_active_content() {
+                    self.input_mode = InputMode::EditingColumn;
                 }
             },
             Action::ExitEditMode => {
+                if self.input_mode == InputMode::Searching {
+                    self.board_search.clear();
+                }
                 self.input_mode = InputMode::Normal;
+                self.pending_move = None;
+                self.rename_target = None;
                 self.input_buffer.clear();
+                self.note_editor = None;
+                self.history.reset();
+                self.suggestions.clear();
             }
-            Action::InputChar(c) => self.input_buffer.push(c),
-            Action::InputBackspace => { self.input_buffer.pop(); },
+            Action::InputChar(c) if c.is_control() => {},
+            Action::InputChar(c) => {
+                if let Some(editor) = &mut self.note_editor {
+                    editor.insert(c);
+                } else {
+                    self.input_buffer.push(c);
+                }
+                self.refresh_suggestions();
+            },
+            Action::InputPaste(text) => {
+                if let Some(editor) = &mut self.note_editor {
+                    editor.insert_str(&validate::clean_lines(&text));
+                } else if self.input_mode.takes_text() {
+                    self.input_buffer.push_str(&validate::clean(&text));
+                    self.refresh_suggestions();
+                }
+            },
+            Action::InputBackspace => {
+                if let Some(editor) = &mut self.note_editor {
+                    editor.backspace();
+                } else {
+                    self.input_buffer.pop();
+                }
+                self.refresh_suggestions();
+            },
+            Action::EditNote(edit) => {
+                if let Some(editor) = &mut self.note_editor {
+                    editor.apply(edit);
+                }
+            },
+            Action::SuggestionPrev => self.suggestion_idx = self.suggestion_idx.saturating_sub(1),
+            Action::SuggestionNext => {
+                if self.suggestion_idx + 1 < self.suggestions.len() {
+                    self.suggestion_idx += 1;
+                }
+            },
+            Action::AcceptSuggestion => self.accept_suggestion(),
             Action::SubmitTask => self.submit_input(),
             
-            Action::DeleteTask => self.delete_item(),
+            Action::DeleteTask => self.request_delete(),
+            Action::DeleteColumn => self.request_delete_column(),
+            Action::MoveColumn(delta) => self.move_column(delta),
+            Action::ConfirmDeleteColumn(tasks) => {
+                self.input_mode = InputMode::Normal;
+                if let Some((ConfirmOp::DeleteColumn, _)) = self.pending_confirm.take() {
+                    self.delete_column(tasks);
+                }
+            },
+            Action::ConfirmYes => {
+                self.input_mode = InputMode::Normal;
+                match self.pending_confirm.take() {
+                    Some((ConfirmOp::DeleteTask, _)) => self.delete_item(),
+                    Some((ConfirmOp::QuitUnsaved, _)) => self.should_quit = true,
+                    Some((ConfirmOp::DeleteColumn, _)) => self.delete_column(ColumnTasks::Drop),
+                    Some((ConfirmOp::ExportOverwrite, _)) => {
+                        if let Some(path) = self.pending_export.take() {
+                            self.write_export(&path);
+                        }
+                    }
+                    _ => {}
+                }
+            },
+            Action::ConfirmNo => {
+                self.input_mode = InputMode::Normal;
+                self.pending_confirm = None;
+                self.pending_export = None;
+            },
             Action::ToggleTodo => self.toggle_todo(),
+            Action::ResizeTodo(delta) => self.change_todo_layout(|layout| {
+                layout.pending_percent = (layout.pending_percent as i16 + delta).clamp(10, 90) as u16;
+            }),
+            Action::ToggleTodoMerge => self.change_todo_layout(|layout| layout.merged = !layout.merged),
+            Action::BeginSnooze => self.begin_command("snooze "),
+            Action::BeginDue => self.begin_command("due "),
+            Action::ToggleSnoozed => self.toggle_show_snoozed(),
+            Action::BeginWait => self.begin_command("wait "),
+            Action::ToggleWaitingFilter => self.toggle_waiting_filter(),
+            Action::ToggleQuickFilter(filter) => self.toggle_quick_filter(filter),
+            Action::Replace(answer) => self.answer_replace(answer),
             
             // Type Selection
             Action::SelectBoard => self.initialize_content(TaskContent::Board(Board { title: "New Board".into(), ..Default::default() })),
             Action::SelectTodo => self.initialize_content(TaskContent::Todo(Vec::new())),
             Action::SelectText => self.initialize_content(TaskContent::Text(String::new())),
-        }
-
 
+            // Command line
+            Action::EnterCommandMode => self.input_mode = InputMode::Command,
+            Action::HistoryPrev => {
+                if let Some(line) = self.history.prev() {
+                    self.input_buffer = line.to_string();
+                }
+            },
+            Action::HistoryNext => {
+                if let Some(line) = self.history.next() {
+                    self.input_buffer = line.to_string();
+                }
+            },
+            Action::CompleteCommand => self.complete_command(),
 
-        // Auto-save only if dirty
-        if self.dirty {
-            let _ = self.save();
+            Action::BeginChord(key) => self.pending_key = Some(key),
+            Action::CancelChord => {}, // `end_chord` has dropped the first key
+            Action::ToggleBoardNotes => self.show_board_notes = !self.show_board_notes,
+            Action::EditBoardNotes => {
+                if let ActiveContentRef::Board(board) = self.get_active_content() {
+                    self.input_buffer = board.description.clone();
+                    self.input_mode = InputMode::EditingBoardNotes;
+                    self.show_board_notes = true;
+                }
+            },
+            Action::ToggleDetails => self.show_details = !self.show_details,
+            Action::BeginSearch => {
+                if let ActiveContentRef::Board(_) = self.get_active_content() {
+                    self.input_buffer.clear();
+                    self.input_mode = InputMode::Searching;
+                }
+            }
+            Action::SearchNext(step) => self.jump_to_match(step),
+            Action::Undo => self.step_history(true),
+            Action::Redo => self.step_history(false),
+            Action::EnterRenameMode => self.begin_rename(),
+            Action::EnterRenameColumnMode => {
+                if let ActiveContentRef::Board(board) = self.get_active_content()
+                    && let Some(column) = board.columns.get(self.pos.0) {
+                        self.input_buffer = column.title.clone();
+                        self.rename_target = Some(RenameTarget::Column(self.pos.0));
+                        self.input_mode = InputMode::Renaming;
+                    }
+            },
+            Action::EditDescription => {
+                if let Some(task) = self.selected_task() {
+                    self.input_buffer = task.description.clone();
+                    self.input_mode = InputMode::EditingDescription;
+                    self.show_details = true;
+                }
+            },
+            Action::EditColumnDefaults => self.edit_column_defaults(),
+            Action::EditExternally => self.begin_external_edit(),
+            Action::ExternalEdited(edit) => self.finish_external_edit(edit),
         }
-
-        Ok(())
     }
 
     fn move_cursor(&mut self, dx: i32, dy: i32) {
@@ -157,30 +1013,36 @@ impl App {
             ActiveContentRef::Board(board) => {
                 let col_count = board.columns.len();
                 if col_count == 0 { return; }
-                let (mut c, mut r) = (self.cursor.0 as i32, self.cursor.1 as i32);
-                
-                // Horizontal
-                if dx != 0 { c = (c + dx).clamp(0, col_count as i32 - 1); }
-                
-                // Vertical
-                let tasks_len = board.columns[c as usize].tasks.len();
-                let max_r = if tasks_len > 0 { tasks_len as i32 - 1 } else { 0 };
-                
-                if dy != 0 {
-                    if dx != 0 { r = r.min(max_r); } // moved col, clamp row
-                    else { r = (r + dy).clamp(0, max_r); }
-                } else if dx != 0 {
-                    r = r.min(max_r);
-                }
-
-                self.cursor = (c as usize, r as usize);
+                let c = (self.pos.0 as i32 + dx).clamp(0, col_count as i32 - 1) as usize;
+
+                // Only visible tasks take the cursor; with none, it rests past the end
+                let rows = self.lane_rows(board, c);
+                if rows.is_empty() {
+                    self.pos = (c, board.columns[c].tasks.len());
+                    return;
+                }
+                let lane = self.lane_rows(board, self.pos.0).into_iter().find(|&(i, _)| i == self.pos.1).map(|(_, l)| l);
+                let at = match (rows.iter().position(|&(i, _)| (c, i) == self.pos), lane) {
+                    (Some(at), _) => at,
+                    // Across columns the cursor keeps to its lane, or the next one down with cards here
+                    (None, Some(lane)) if board.lanes.is_some() => rows.iter().position(|&(_, l)| l >= lane).unwrap_or(rows.len() - 1),
+                    // Nearest visible row at or above the cursor
+                    _ => rows.iter().rposition(|&(i, _)| i <= self.pos.1).unwrap_or(0),
+                };
+
+                let (r, _) = if dy != 0 && dx == 0 {
+                    rows[(at as i32 + dy).clamp(0, rows.len() as i32 - 1) as usize]
+                } else {
+                    rows[at] // moved col (or nothing), clamp row
+                };
+                self.pos = (c, r);
             },
             ActiveContentRef::Todo(items) => {
                 let len = items.len();
                 if len == 0 { return; }
-                let mut r = self.cursor.1 as i32;
+                let mut r = self.pos.1 as i32;
                 if dy != 0 { r = (r + dy).clamp(0, len as i32 - 1); }
-                self.cursor = (0, r as usize);
+                self.pos = (0, r as usize);
             },
             ActiveContentRef::Text(_) => {
                 // No cursor movement in text view for now (view only)
@@ -189,37 +1051,114 @@ impl App {
         }
     }
 
+    // Down to the first card of the next swimlane in the column, or up to the
+    // first of the one before
+    fn move_lane(&mut self, dy: i32) {
+        if self.input_mode != InputMode::Normal || self.show_help { return; }
+        let ActiveContentRef::Board(board) = self.get_active_content() else { return };
+        if board.lanes.is_none() {
+            self.message = Some("No swimlanes on this board (:lanes assignee, tag or field)".to_string());
+            return;
+        }
+        let rows = self.lane_rows(board, self.pos.0);
+        let target = match rows.iter().find(|&&(i, _)| i == self.pos.1) {
+            Some(&(_, lane)) if dy > 0 => rows.iter().find(|&&(_, l)| l > lane),
+            Some(&(_, lane)) => rows.iter().rev().find(|&&(_, l)| l < lane).and_then(|&(_, prev)| rows.iter().find(|&&(_, l)| l == prev)),
+            None => rows.first(),
+        };
+        if let Some(&(r, _)) = target {
+            self.pos.1 = r;
+        }
+    }
+
+    /// The swimlanes of `board` in the order they show, from its visible
+    /// tasks; None on a board without lanes.
+    pub fn lane_order(&self, board: &Board) -> Option<Vec<Option<String>>> {
+        let lanes = board.lanes.as_ref()?;
+        let today = zone::today();
+        Some(lanes::order(lanes, board.columns.iter().flat_map(|c| &c.tasks).filter(|t| self.task_visible(t, today))))
+    }
+
+    // The rows of column `c` that take the cursor, in the order they show, with
+    // the place of their swimlane (all 0 on a board without lanes)
+    fn lane_rows(&self, board: &Board, c: usize) -> Vec<(usize, usize)> {
+        let Some(column) = board.columns.get(c) else { return Vec::new() };
+        let today = zone::today();
+        let order = self.lane_order(board).unwrap_or_default();
+        let mut rows: Vec<(usize, usize)> = column.tasks.iter().enumerate()
+            .filter(|(_, t)| self.task_visible(t, today))
+            .map(|(i, t)| (i, board.lanes.as_ref().and_then(|l| lanes::position(&order, &l.of(t))).unwrap_or(0)))
+            .collect();
+        rows.sort_by_key(|&(_, lane)| lane);
+        rows
+    }
+
     fn handle_drill_down(&mut self) {
         if let ActiveContentRef::Board(board) = self.get_active_content() {
-            let (c, r) = self.cursor;
-            if let Some(col) = board.columns.get(c) {
-                if let Some(task) = col.tasks.get(r) {
+            let (c, r) = self.pos;
+            if let Some(col) = board.columns.get(c)
+                && let Some(task) = col.tasks.get(r) {
                     if task.content.is_none() {
                         self.input_mode = InputMode::SelectType;
                     } else {
                         // Push path
-                        self.path.push((c, r));
-                        self.cursor = (0, 0);
+                        self.location.push((c, r));
+                        self.pos = (0, 0);
                         
-                        // If it's text, auto-enter edit mode? 
-                        // Let's keep it view-only first, then Enter again to edit?
-                        // For simplicity: If entering Text content, we just view it. 
-                        // User can press 'Enter' inside Text view to edit (implemented below).
-                        if let ActiveContentRef::Text(text) = self.get_active_content() {
-                             let text_content = text.clone();
-                             self.input_mode = InputMode::Editing;
-                             self.input_buffer = text_content;
-                        }
+                        // Notes open straight into the editor
+                        self.edit_note();
                     }
                 }
-            }
-        } else if let ActiveContentRef::Text(_) = self.get_active_content() {
-            // If already in text view, Enter to edit
-             if let ActiveContentRef::Text(text) = self.get_active_content() {
-                 let text_content = text.clone();
-                 self.input_mode = InputMode::Editing;
-                 self.input_buffer = text_content;
-             }
+        } else {
+            // Enter in a note's view edits it again
+            self.edit_note();
+        }
+    }
+
+    // The open note, or on a board the selected card's note, or its description
+    // when it holds something else
+    fn begin_external_edit(&mut self) {
+        let (task, field) = match self.get_active_content() {
+            ActiveContentRef::Text(_) => (self.active_task(), TextField::Note),
+            ActiveContentRef::Board(_) => match self.selected_task() {
+                Some(task) if matches!(task.content, Some(TaskContent::Text(_))) => (Some(task), TextField::Note),
+                task => (task, TextField::Description),
+            },
+            _ => (None, TextField::Note),
+        };
+        let Some(task) = task else {
+            self.message = Some("Nothing to edit here: open a note or select a card".to_string());
+            return;
+        };
+        let text = match (&task.content, field) {
+            (Some(TaskContent::Text(text)), TextField::Note) => text.clone(),
+            _ => task.description.clone(),
+        };
+        self.external_edit = Some(ExternalEdit { task: task.id, field, text });
+    }
+
+    fn finish_external_edit(&mut self, edit: ExternalEdit) {
+        let Some(task) = self.root.path_to(edit.task).and_then(|path| self.root.task_at_mut(&path)) else {
+            self.message = Some("The task was deleted while its editor was open".to_string());
+            return;
+        };
+        // Editors tend to end files with a line break the text didn't have
+        let text = edit.text.trim_end().to_string();
+        let current = match (&mut task.content, edit.field) {
+            (Some(TaskContent::Text(note)), TextField::Note) => note,
+            (_, TextField::Description) => &mut task.description,
+            _ => return,
+        };
+        if *current != text {
+            *current = text;
+            self.dirty = true;
+        }
+    }
+
+    fn edit_note(&mut self) {
+        if let ActiveContentRef::Text(text) = self.get_active_content() {
+            self.note_editor = Some(Editor::new(text));
+            self.input_mode = InputMode::EditingNote;
         }
     }
 
@@ -232,226 +1171,1663 @@ impl App {
             self.input_mode = InputMode::Normal;
             return;
         }
-        if let Some((col, row)) = self.path.pop() {
-            self.cursor = (col, row);
+        if let Some((col, row)) = self.location.pop() {
+            self.pos = (col, row);
         }
     }
 
-    fn initialize_content(&mut self, content: TaskContent) {
-         if self.input_mode != InputMode::SelectType { return; }
-         
-         // We need to set the content of the *current* selection (which is the parent's cursor)
-         // Wait, we are in SelectType mode, meaning we haven't pushed to path yet.
-         // We are sitting at the parent board.
-         
-         // Helper to mutate current selection
-         {
-         let (c, r) = self.cursor;
-         // We need to get the PARENT board.
-         let board = Self::get_board_recursive(&mut self.root, &self.path); // This gets the board we are LOOKING at.
-         if let Some(col) = board.columns.get_mut(c) {
-             if let Some(task) = col.tasks.get_mut(r) {
-                 task.content = Some(content.clone());
-                 self.dirty = true;
-             }
-         }
-         }
-         
-         self.input_mode = InputMode::Normal;
-         // Automatically drill down after creation
-         self.handle_drill_down();
+    /// Returns to breadcrumb `level` (0 is the main board).
+    fn jump_to_level(&mut self, level: usize) {
+        if self.input_mode != InputMode::Normal || level >= self.location.len() { return; }
+        self.pos = self.location[level];
+        self.location.truncate(level);
     }
 
-    fn submit_input(&mut self) {
-        if self.input_mode == InputMode::EditingColumn {
-            let title = self.input_buffer.trim().to_string();
-            if !title.is_empty() {
-                 let board = Self::get_board_recursive(&mut self.root, &self.path);
-                 board.columns.push(crate::model::Column::new(&title));
-                 self.dirty = true;
-            }
-            self.input_buffer.clear();
+    /// Labels for the cards on the board in view, in column order: one key each
+    /// when they fit in `JUMP_KEYS`, two keys each otherwise.
+    pub fn jump_labels(&self) -> Vec<(Uuid, String)> {
+        let ActiveContentRef::Board(board) = self.get_active_content() else { return Vec::new() };
+        let today = zone::today();
+        let ids: Vec<Uuid> = board.columns.iter()
+            .flat_map(|c| c.tasks.iter())
+            .filter(|t| self.task_visible(t, today))
+            .map(|t| t.id)
+            .collect();
+        let keys: Vec<char> = JUMP_KEYS.chars().collect();
+        let two = ids.len() > keys.len();
+        ids.into_iter().take(keys.len() * keys.len()).enumerate().map(|(i, id)| {
+            let label = if two { format!("{}{}", keys[i / keys.len()], keys[i % keys.len()]) } else { keys[i].to_string() };
+            (id, label)
+        }).collect()
+    }
+
+    fn toggle_jump(&mut self) {
+        self.jump_input.clear();
+        if self.input_mode == InputMode::Jump {
             self.input_mode = InputMode::Normal;
+        } else if self.input_mode == InputMode::Normal {
+            if self.jump_labels().is_empty() {
+                self.message = Some("No cards to jump to".to_string());
+            } else {
+                self.input_mode = InputMode::Jump;
+            }
+        }
+    }
+
+    // Moves the cursor once the keys typed make up a label; a key no label
+    // continues with ends jump mode
+    fn jump_key(&mut self, key: char) {
+        if self.input_mode != InputMode::Jump { return; }
+        self.jump_input.push(key);
+        let labels = self.jump_labels();
+        if let Some((id, _)) = labels.iter().find(|(_, l)| *l == self.jump_input) {
+            self.cursor = Some(*id);
+            self.resync();
+        } else if labels.iter().any(|(_, l)| l.starts_with(&self.jump_input)) {
             return;
+        } else {
+            self.message = Some(format!("No card labeled '{}'", self.jump_input));
         }
+        self.jump_input.clear();
+        self.input_mode = InputMode::Normal;
+    }
 
+    // Flips between this board and the one viewed before it, like Alt+Tab
+    fn toggle_last_board(&mut self) {
+        if self.input_mode != InputMode::Normal { return; }
+        let Some((path, cursor)) = self.alternate.clone() else {
+            self.message = Some("No other board viewed yet".to_string());
+            return;
+        };
+        self.path = path;
+        self.cursor = cursor;
+        self.resync();
+    }
+
+    /// Opens the board holding the task and puts the cursor on it (`kanban open`).
+    /// `target` is a kanban:// link or anything `cli::resolve_task` accepts.
+    pub fn reveal_task(&mut self, target: &str) -> Result<()> {
+        let location = match link::parse(target) {
+            Some(link) => link::resolve(&self.root, &link)?,
+            None => crate::cli::resolve_task(&self.root, target)?,
+        };
+        self.reveal(location);
+        Ok(())
+    }
+
+    // Opens the board holding the task at `location` with the cursor on it,
+    // lifting the filters that would hide it
+    fn reveal(&mut self, mut location: Vec<(usize, usize)>) {
+        if let Some(task) = self.root.task_at(&location) {
+            if task.is_snoozed(zone::today()) {
+                self.show_snoozed = true;
+            }
+            if task.waiting_on.is_none() {
+                self.only_waiting = false;
+            }
+            if self.quick_filter.is_some_and(|f| !f.keeps(task, Utc::now())) {
+                self.quick_filter = None;
+            }
+        }
+        self.pos = location.pop().unwrap_or((0, 0));
+        self.location = location;
+        self.anchor();
+    }
+
+    fn all_workspaces(&self) -> Vec<&Board> {
+        self.workspaces.iter().enumerate()
+            .map(|(i, b)| if i == self.workspace { &self.root } else { b })
+            .collect()
+    }
+
+    /// Stats of the workspace at `index`, counting this week in the configured zone.
+    pub fn workspace_stats(&self, index: usize) -> Option<WorkspaceStats> {
+        let root = self.all_workspaces().into_iter().nth(index)?;
+        Some(stats::workspace(root, stats::week_start(zone::today()), Utc::now()))
+    }
+
+    pub fn workspace_names(&self) -> Vec<&str> {
+        self.all_workspaces().into_iter().map(|b| b.title.as_str()).collect()
+    }
+
+    /// Prints or logs what changed since the TUI opened, as configured in `[session]`.
+    pub fn finish_session(&self) -> Result<()> {
+        let mode = self.config.session.summary;
+        if mode == SummaryMode::Off || self.in_memory {
+            return Ok(());
+        }
+        let Some(summary) = self.session.summary(&self.all_workspaces()) else { return Ok(()) };
+        if mode == SummaryMode::Print {
+            println!("{}", summary);
+            return Ok(());
+        }
+        let path = &self.config.session.file;
+        let mut log = OpenOptions::new().create(true).append(true).open(path)
+            .with_context(|| format!("cannot write the session summary to {}", path.display()))?;
+        writeln!(log, "{}\n", summary)?;
+        Ok(())
+    }
+
+    // Switches to the workspace called `name`, creating it when there is none
+    fn open_workspace(&mut self, name: &str) {
+        let existing = self.workspace_names().iter().position(|n| collate::eq(n, name));
+        let i = existing.unwrap_or_else(|| {
+            self.workspaces.push(Board { title: name.to_string(), ..Board::default() });
+            self.workspaces.len() - 1
+        });
+        self.switch_workspace(i);
+        if existing.is_none() {
+            self.message = Some(format!("New workspace '{}'", name));
+        }
+    }
+
+    fn switch_workspace(&mut self, i: usize) {
+        if i == self.workspace {
+            return;
+        }
+        let next = std::mem::take(&mut self.workspaces[i]);
+        self.workspaces[self.workspace] = std::mem::replace(&mut self.root, next);
+        self.workspace = i;
+        self.path.clear();
+        self.cursor = None;
+        self.location.clear();
+        self.pos = (0, 0);
+        // Undo steps belong to the workspace they were made in
+        self.undo = UndoStack::new(&self.root);
+        self.dirty = true; // The file remembers which one is open
+    }
+
+    fn export(&mut self, path: PathBuf) {
+        if path.exists() && self.config.confirm.requires(ConfirmOp::ExportOverwrite) {
+            let prompt = format!("{} exists. Replace it?", path.display());
+            self.pending_export = Some(path);
+            self.ask(ConfirmOp::ExportOverwrite, prompt);
+            return;
+        }
+        self.write_export(&path);
+    }
+
+    // `.txt` files get todo.txt, which also takes the todo list in view,
+    // `.ics` files the board's due dates and `.csv` files a row per task
+    fn write_export(&mut self, path: &Path) {
+        let todotxt = import::is_todotxt(path);
+        let has_extension = |ext: &str| path.extension().is_some_and(|e| e.eq_ignore_ascii_case(ext));
+        let (ics, csv) = (has_extension("ics"), has_extension("csv"));
+        let (title, text) = match self.get_active_content() {
+            ActiveContentRef::Board(board) if todotxt => (board.title.clone(), todotxt::render_board(board)),
+            ActiveContentRef::Board(board) if ics => (board.title.clone(), ics::render(board, &self.root.breadcrumbs(&self.location))),
+            ActiveContentRef::Board(board) if csv => (board.title.clone(), export::csv(board, &self.root.breadcrumbs(&self.location))),
+            ActiveContentRef::Board(board) => (board.title.clone(), export::Markdown { links: false }.render(board, &self.root.breadcrumbs(&self.location))),
+            ActiveContentRef::Todo(items) if todotxt => (self.active_task().map(|t| t.title.clone()).unwrap_or_default(), todotxt::render_items(items)),
+            _ => {
+                self.message = Some(format!("export: not viewing a board{}", if todotxt { " or todo list" } else { "" }));
+                return;
+            }
+        };
+        self.message = Some(match fs::write(path, text) {
+            Ok(()) => format!("Exported '{}' to {}", title, path.display()),
+            Err(e) => format!("export: {}: {}", path.display(), e),
+        });
+    }
+
+    // A card in the selected column, or on a todo list the lines of a todo.txt file
+    fn import(&mut self, path: &Path) {
+        let list = match self.get_active_content() {
+            ActiveContentRef::Board(_) => false,
+            ActiveContentRef::Todo(_) if import::is_todotxt(path) => true,
+            _ => {
+                self.message = Some("import: not viewing a board (or a todo list, for todo.txt)".to_string());
+                return;
+            }
+        };
+        let (title, content) = match import::card(path, list) {
+            Ok(card) => card,
+            Err(e) => {
+                self.message = Some(format!("import: {}: {}", path.display(), e));
+                return;
+            }
+        };
+        let summary = import::summary(&content);
+        match (ActiveContentMut::resolve(&mut self.root, &self.location), content) {
+            (ActiveContentMut::Todo(items), TaskContent::Todo(new)) => {
+                let added = import::merge_items(items, new);
+                self.message = Some(format!("Imported {} from {}: {} new", summary, path.display(), added));
+            }
+            (ActiveContentMut::Board(board), content) => {
+                if self.pos.0 >= board.columns.len() {
+                    return;
+                }
+                let (id, updated) = import::place(board, self.pos.0, import::source(path), &title, content);
+                self.message = Some(match updated {
+                    true => format!("Updated '{}' from {}: {}", title, path.display(), summary),
+                    false => format!("Imported '{}' into '{}': {}", title, board.columns[self.pos.0].title, summary),
+                });
+                self.cursor = Some(id);
+            }
+            _ => return,
+        }
+        self.resync(); // Onto the card, wherever an earlier import left it
+        self.dirty = true;
+    }
+
+    fn find(&mut self, query: &str) {
+        let results = search::search(&self.root, query);
+        if results.is_empty() {
+            self.message = Some(format!("No task matches '{}'", query));
+            return;
+        }
+        self.results = results;
+        self.results_idx = 0;
+        self.results_query = query.to_string();
+        self.input_mode = InputMode::Results;
+    }
+
+    // Lists the open tasks of the board in view that fall in an agenda slot
+    fn open_agenda_slot(&mut self, i: usize) {
+        let ActiveContentRef::Board(board) = self.get_active_content() else { return };
+        let Some(&slot) = agenda::slots(zone::today()).get(i) else { return };
+        let mut found = agenda::tasks(board, slot, Utc::now());
+        self.input_mode = InputMode::Normal;
+        self.agenda_idx = i;
+        let label = match slot {
+            agenda::Slot::Overdue => "overdue".to_string(),
+            agenda::Slot::Day(day) => format!("due {}", day.format("%a %b %d")),
+        };
+        if found.is_empty() {
+            self.message = Some(format!("Nothing {}", label));
+            return;
+        }
+        // Found from the board in view; results are located from the main board
+        let crumbs = self.root.breadcrumbs(&self.location);
+        for m in &mut found {
+            m.location.splice(0..0, self.location.iter().copied());
+            m.breadcrumbs.splice(0..1, crumbs.iter().cloned());
+        }
+        self.results = found;
+        self.results_idx = 0;
+        self.results_query = label;
+        self.input_mode = InputMode::Results;
+    }
+
+    fn open_result(&mut self) {
+        let Some(found) = self.results.get(self.results_idx) else { return };
+        let location = found.location.clone();
+        self.input_mode = InputMode::Normal;
+        self.results.clear();
+        self.reveal(location);
+    }
+
+    // Puts back the tree before the last change (or after the last undo) and
+    // returns the view to where the change was made
+    fn step_history(&mut self, back: bool) {
+        let (path, cursor) = (self.path.clone(), self.cursor);
+        let step = if back { self.undo.undo(&mut self.root, path, cursor) } else { self.undo.redo(&mut self.root, path, cursor) };
+        let Some((path, cursor)) = step else {
+            self.message = Some(if back { "Nothing to undo" } else { "Nothing to redo" }.to_string());
+            return;
+        };
+        self.path = path;
+        self.cursor = cursor;
+        self.resync();
+        self.dirty = true;
+        self.message = Some(if back { "Undone (Ctrl+R to redo)" } else { "Redone" }.to_string());
+    }
+
+    /// Re-resolves `location` and `pos` from `path` and `cursor`. Opened tasks that
+    /// no longer exist are closed; a vanished cursor task leaves the cursor at its old row.
+    pub fn resync(&mut self) {
+        while let Some(&id) = self.path.last() {
+            if let Some(location) = self.root.path_to(id) {
+                self.location = location;
+                break;
+            }
+            self.path.pop();
+        }
+        if self.path.is_empty() {
+            self.location.clear();
+        }
+        // Intermediate boards may differ if the task moved
+        self.path = self.ids_along(&self.location);
+
+        if let ActiveContentRef::Board(board) = self.get_active_content() {
+            let found = self.cursor.and_then(|id| board.columns.iter().enumerate().find_map(|(c, col)| {
+                col.tasks.iter().position(|t| t.id == id).map(|r| (c, r))
+            }));
+            if let Some(pos) = found {
+                self.pos = pos;
+            } else {
+                let c = self.pos.0.min(board.columns.len().saturating_sub(1));
+                let rows = board.columns.get(c).map_or(0, |col| col.tasks.len());
+                self.pos = (c, self.pos.1.min(rows.saturating_sub(1)));
+            }
+        }
+    }
+
+    /// Records the ids at `location` and `pos` after positions changed.
+    fn anchor(&mut self) {
+        self.path = self.ids_along(&self.location);
+        let (c, r) = self.pos;
+        self.cursor = match self.get_active_content() {
+            ActiveContentRef::Board(board) => board.columns.get(c).and_then(|col| col.tasks.get(r)).map(|t| t.id),
+            _ => None,
+        };
+    }
+
+    fn ids_along(&self, location: &[(usize, usize)]) -> Vec<Uuid> {
+        (1..=location.len()).map_while(|n| self.root.task_at(&location[..n]).map(|t| t.id)).collect()
+    }
+
+    fn initialize_content(&mut self, content: TaskContent) {
+         if self.input_mode != InputMode::SelectType { return; }
+         
+         // We need to set the content of the *current* selection (which is the parent's cursor)
+         // Wait, we are in SelectType mode, meaning we haven't pushed to path yet.
+         // We are sitting at the parent board.
+         
+         if let Some(task) = self.root.task_at_mut(&self.selected_path()) {
+             task.content = Some(content);
+             self.dirty = true;
+         }
+         
+         self.input_mode = InputMode::Normal;
+         // Automatically drill down after creation
+         self.handle_drill_down();
+    }
+
+    fn refresh_suggestions(&mut self) {
+        self.suggestion_idx = 0;
+        self.suggestions = if matches!(self.input_mode, InputMode::Editing | InputMode::Renaming) {
+            autocomplete::suggest(&self.input_buffer, &autocomplete::known_tags(&self.root))
+        } else {
+            Vec::new()
+        };
+    }
+
+    fn accept_suggestion(&mut self) {
+        if let Some(s) = self.suggestions.get(self.suggestion_idx) {
+            autocomplete::apply(&mut self.input_buffer, s);
+        }
+        self.suggestions.clear();
+    }
+
+    fn submit_input(&mut self) {
+        // Enter picks the highlighted suggestion while the dropdown is open
+        if !self.suggestions.is_empty() {
+            self.accept_suggestion();
+            return;
+        }
+        // Already shown in the popup; Enter waits for a fix or Esc
+        if self.input_problem().is_some() {
+            return;
+        }
+
+        if self.input_mode == InputMode::EditingNote {
+            if let Some(editor) = self.note_editor.take()
+                && let ActiveContentRef::Text(text) = self.get_active_content()
+                && *text != editor.text() {
+                    self.set_text_content(editor.text());
+                }
+            self.input_mode = InputMode::Normal;
+            return;
+        }
+
+        if self.input_mode == InputMode::Command {
+            let line = std::mem::take(&mut self.input_buffer);
+            self.input_mode = InputMode::Normal;
+            self.history.push(line.trim());
+            match command::parse(&line) {
+                Ok(cmd) => self.run_command(cmd),
+                Err(e) => self.message = Some(e),
+            }
+            return;
+        }
+
+        if self.input_mode == InputMode::Searching {
+            self.board_search = std::mem::take(&mut self.input_buffer).trim().to_string();
+            self.input_mode = InputMode::Normal;
+            if !self.board_search.is_empty() {
+                self.jump_to_match(0);
+            }
+            return;
+        }
+
+        if self.input_mode == InputMode::EditingBoardNotes {
+            if let ActiveContentMut::Board(board) = ActiveContentMut::resolve(&mut self.root, &self.location) {
+                board.description = self.input_buffer.trim().to_string();
+                self.dirty = true;
+            }
+            self.input_buffer.clear();
+            self.input_mode = InputMode::Normal;
+            return;
+        }
+
+        if self.input_mode == InputMode::Renaming {
+            let title = self.input_buffer.trim().to_string();
+            if title.is_empty() {
+                self.message = Some("A title can't be empty (Esc to cancel)".to_string());
+                return;
+            }
+            if let Some(target) = self.rename_target.take() {
+                self.rename(target, title);
+            }
+            self.input_buffer.clear();
+            self.input_mode = InputMode::Normal;
+            return;
+        }
+
+        if self.input_mode == InputMode::EditingDescription {
+            if let Some(task) = self.root.task_at_mut(&self.selected_path()) {
+                task.description = self.input_buffer.trim().to_string();
+                self.dirty = true;
+            }
+            self.input_buffer.clear();
+            self.input_mode = InputMode::Normal;
+            return;
+        }
+
+        if self.input_mode == InputMode::EditingColumnDefaults {
+            let spec = std::mem::take(&mut self.input_buffer);
+            self.input_mode = InputMode::Normal;
+            self.set_column_defaults(&spec);
+            return;
+        }
+
+        if self.input_mode == InputMode::EditingBlockReason {
+            let reason = self.input_buffer.trim().to_string();
+            if reason.is_empty() {
+                self.message = Some("A reason is required (Esc to cancel the move)".to_string());
+                return;
+            }
+            self.input_buffer.clear();
+            self.input_mode = InputMode::Normal;
+            if let Some(new_c) = self.pending_move.take() {
+                self.move_task_unchecked(new_c);
+                if let Some(task) = self.root.task_at_mut(&self.selected_path()) {
+                    task.blocked_reason = Some(reason);
+                }
+            }
+            return;
+        }
+
+        if self.input_mode == InputMode::EditingColumn {
+            let title = self.input_buffer.trim().to_string();
+            self.add_column(&title);
+            self.input_buffer.clear();
+            self.input_mode = InputMode::Normal;
+            return;
+        }
+
+        match self.get_active_content() {
+            ActiveContentRef::Board(_) => {
+                // Adding variable to avoid borrow checker hell
+                let title = self.input_buffer.trim().to_string();
+                self.add_task(&title);
+            },
+            ActiveContentRef::Todo(_) => {
+                let text = self.input_buffer.trim().to_string();
+                if !text.is_empty() {
+                    let _items: Vec<TodoItem> = Vec::new(); // placeholder - unused logic branch if we just want to submit simple task?
+                    // Actually, for Todo list, we add items via `a`. SubmitTask is for `Editing` mode.
+                    // If we are in `Editing` mode inside a Todo view?
+                    // We only use Editing mode for *renaming*? Or adding?
+                    // My logic in `submit_input`: `match active_content`.
+                    // If `ActiveContent::Todo`, `input_buffer` is the new item text?
+                    // Yes.
+                    
+                    let text = self.input_buffer.trim().to_string();
+                    if !text.is_empty() {
+                         self.add_todo_item(text);
+                         // self.dirty set inside add_todo_item
+                    }
+                }
+            },
+             _ => {}
+        }
+        self.input_buffer.clear();
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// What keeps the popup's input from being submitted, shown as it is typed.
+    pub fn input_problem(&self) -> Option<String> {
+        let limits = &self.config.limits;
+        match self.input_mode {
+            InputMode::EditingColumn => self.root.board_at(&self.location)
+                .and_then(|board| validate::new_column(board.columns.len(), limits))
+                .or_else(|| validate::title(&self.input_buffer, limits)),
+            InputMode::Renaming => validate::title(&self.input_buffer, limits),
+            InputMode::EditingColumnDefaults => TaskDefaults::parse(&self.input_buffer, zone::today()).err(),
+            InputMode::Editing => match self.get_active_content() {
+                ActiveContentRef::Board(_) | ActiveContentRef::Todo(_) => validate::title(&self.input_buffer, limits),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn add_task(&mut self, title: &str) {
+        if title.is_empty() { return; }
+        if let Some(problem) = validate::title(title, &self.config.limits) {
+            self.message = Some(format!("add: {}", problem));
+            return;
+        }
+        let (c, _) = self.pos;
+        if let ActiveContentMut::Board(board) = ActiveContentMut::resolve(&mut self.root, &self.location)
+            && let Some(col) = board.columns.get_mut(c) {
+                let mut task = Task::new(title, "");
+                if let Some(defaults) = &col.defaults {
+                    defaults.apply(&mut task, zone::today());
+                }
+                col.tasks.push(task);
+                self.dirty = true;
+            }
+    }
+
+    // On a board `r` renames the task under the cursor, or the column when it shows no task
+    fn begin_rename(&mut self) {
+        let (target, title) = match self.get_active_content() {
+            ActiveContentRef::Board(board) => match self.selected_task() {
+                Some(task) => (RenameTarget::Task, task.title.clone()),
+                None => match board.columns.get(self.pos.0) {
+                    Some(column) => (RenameTarget::Column(self.pos.0), column.title.clone()),
+                    None => return,
+                },
+            },
+            ActiveContentRef::Todo(items) => match self.todo_cursor_item() {
+                Some(i) => (RenameTarget::TodoItem(i), items[i].text.clone()),
+                None => return,
+            },
+            _ => return,
+        };
+        self.rename_target = Some(target);
+        self.input_buffer = title;
+        self.input_mode = InputMode::Renaming;
+    }
+
+    fn rename(&mut self, target: RenameTarget, title: String) {
+        let renamed = match target {
+            RenameTarget::Task => self.root.task_at_mut(&self.selected_path()).map(|t| t.title = title).is_some(),
+            RenameTarget::Column(c) => self.root.board_at_mut(&self.location)
+                .and_then(|b| b.columns.get_mut(c))
+                .map(|col| col.title = title)
+                .is_some(),
+            RenameTarget::TodoItem(i) => match ActiveContentMut::resolve(&mut self.root, &self.location) {
+                ActiveContentMut::Todo(items) => items.get_mut(i).map(|item| item.text = title).is_some(),
+                _ => false,
+            },
+        };
+        self.dirty |= renamed;
+    }
+
+    fn add_column(&mut self, title: &str) {
+        if title.is_empty() { return; }
+        if let ActiveContentMut::Board(board) = ActiveContentMut::resolve(&mut self.root, &self.location) {
+            let limits = &self.config.limits;
+            if let Some(problem) = validate::new_column(board.columns.len(), limits).or_else(|| validate::title(title, limits)) {
+                self.message = Some(format!("column: {}", problem));
+                return;
+            }
+            board.columns.push(crate::model::Column::new(title));
+            self.dirty = true;
+        }
+    }
+
+    fn run_command(&mut self, cmd: Command) {
+        match cmd {
+            Command::Add(text) => match self.get_active_content() {
+                ActiveContentRef::Board(_) => self.add_task(&text),
+                ActiveContentRef::Todo(_) => self.add_todo_item(text),
+                _ => self.message = Some("add: nothing to add to here".to_string()),
+            },
+            Command::Column(title) => match self.get_active_content() {
+                ActiveContentRef::Board(_) => self.add_column(&title),
+                _ => self.message = Some("column: not viewing a board".to_string()),
+            },
+            Command::Move(name) => match self.find_column(&name) {
+                Ok(c) => self.move_task_to_column(c),
+                Err(e) => self.message = Some(format!("move: {}", e)),
+            },
+            Command::Goto(name) => match self.find_column(&name) {
+                Ok(c) => self.pos = (c, 0),
+                Err(e) => self.message = Some(format!("goto: {}", e)),
+            },
+            Command::Open(name) => {
+                let found = match self.get_active_content() {
+                    ActiveContentRef::Board(board) => board.columns.iter().enumerate().find_map(|(c, col)| {
+                        col.tasks.iter().position(|t| collate::eq(&t.title, &name)).map(|r| (c, r))
+                    }),
+                    _ => None,
+                };
+                match found {
+                    Some(pos) => {
+                        self.pos = pos;
+                        self.handle_drill_down();
+                    }
+                    None => self.message = Some(format!("open: no task named '{}'", name)),
+                }
+            },
+            Command::Reset(spec) => self.set_column_reset(&spec),
+            Command::Sort(spec) => self.set_column_sort(&spec),
+            Command::Defaults(Some(spec)) => self.set_column_defaults(&spec),
+            Command::Defaults(None) => self.edit_column_defaults(),
+            Command::Blocked => self.toggle_requires_reason(),
+            Command::Freeze { board } => self.toggle_frozen(board),
+            Command::Wide => self.toggle_wide_cards(),
+            Command::Lanes(spec) => self.set_lanes(spec.as_deref()),
+            Command::Lane(name) => self.set_lane(&name),
+            Command::Checklist => self.toggle_checklist(),
+            Command::Icebox => self.toggle_icebox(),
+            Command::Archive { done: false } => self.archive_task(),
+            Command::Archive { done: true } => self.archive_done(),
+            Command::Archived => self.toggle_archive(),
+            Command::Trash => self.toggle_trash(),
+            Command::Subscribe(url) => self.subscribe(&url),
+            Command::Refresh => self.refresh_subscriptions(),
+            Command::Sync => self.sync_github(),
+            Command::Thaw(target) => self.thaw(target.as_deref()),
+            Command::Snooze(when) => match date::parse(&when, zone::today()) {
+                Some(until) => self.snooze(Some(until)),
+                None => self.message = Some(format!("snooze: '{}' is not a date (2026-11-03, tomorrow, next fri, in 2 weeks)", when)),
+            },
+            Command::Unsnooze => self.snooze(None),
+            Command::Due(when) if when.eq_ignore_ascii_case("off") => self.set_due(None),
+            Command::Due(when) => match date::parse_due(&when, zone::today()) {
+                Some(at) => self.set_due(Some(at)),
+                None => self.message = Some(format!("due: '{}' is not a date (2026-11-03, tomorrow, next fri, fri 17:00)", when)),
+            },
+            Command::ShowSnoozed => self.toggle_show_snoozed(),
+            Command::Wait(who) => self.set_waiting(&who),
+            Command::Agenda => {
+                self.show_agenda = !self.show_agenda;
+                if !self.show_agenda && self.input_mode == InputMode::Agenda {
+                    self.input_mode = InputMode::Normal;
+                }
+            }
+            Command::Remind(when) if when.eq_ignore_ascii_case("off") => self.set_reminder(None),
+            Command::Remind(when) => match reminder::parse(&when, zone::today()) {
+                Some(at) => self.set_reminder(Some(at)),
+                None => self.message = Some(format!("remind: '{}' is not a time (fri 9:00, tomorrow at 5pm, in 2 days)", when)),
+            },
+            Command::Waiting => self.toggle_waiting_filter(),
+            Command::Replace(spec) => self.start_replace(&spec),
+            Command::Duplicate { reset_done, strip_tasks } => self.duplicate_board(reset_done, strip_tasks),
+            Command::Delete => self.request_delete(),
+            Command::Back => self.go_back(),
+            Command::Help => self.show_help = true,
+            Command::Find(query) => self.find(&query),
+            Command::Export(file) => self.export(PathBuf::from(file)),
+            Command::Import(file) => self.import(Path::new(&file)),
+            Command::Workspace(None) => {
+                self.input_mode = InputMode::Workspaces;
+                self.workspace_idx = self.workspace;
+            }
+            Command::Workspace(Some(name)) => self.open_workspace(&name),
+            Command::Quit => self.request_quit(),
+            Command::Write { force: false } => self.save_or_report(),
+            Command::Write { force: true } => self.overwrite(),
+            Command::Reload => match self.reload() {
+                Ok(()) => self.message = Some(format!("Reloaded {}", self.config.board.file.display())),
+                Err(e) => self.message = Some(format!("reload failed: {:#}", e)),
+            },
+        }
+    }
+
+    fn complete_command(&mut self) {
+        let (columns, boards): (Vec<&str>, Vec<&str>) = match self.get_active_content() {
+            ActiveContentRef::Board(board) => (
+                board.columns.iter().map(|c| c.title.as_str()).collect(),
+                board.columns.iter()
+                    .flat_map(|c| c.tasks.iter())
+                    .filter(|t| t.content.is_some())
+                    .map(|t| t.title.as_str())
+                    .collect(),
+            ),
+            _ => (Vec::new(), Vec::new()),
+        };
+        if let Some(line) = command::complete(&self.input_buffer, &columns, &boards) {
+            self.input_buffer = line;
+        }
+    }
+
+    // Copies the board being viewed. A sub-board's copy lands next to the task that
+    // holds it; the main board's copy becomes a new task in the selected column.
+    fn duplicate_board(&mut self, reset_done: bool, strip_tasks: bool) {
+        let copy = match self.get_active_content() {
+            ActiveContentRef::Board(board) => board.duplicate(reset_done, strip_tasks),
+            _ => {
+                self.message = Some("duplicate: not viewing a board".to_string());
+                return;
+            }
+        };
+
+        let (parent_path, col) = match self.location.split_last() {
+            Some((&(c, _), parent)) => (parent.to_vec(), c),
+            None => (Vec::new(), self.pos.0),
+        };
+        let Some(column) = self.root.board_at_mut(&parent_path).and_then(|b| b.columns.get_mut(col)) else { return };
+
+        let mut task = Task::new(&copy.title, "");
+        task.content = Some(TaskContent::Board(copy));
+        self.message = Some(format!("Created '{}' in '{}'", task.title, column.title));
+        column.tasks.push(task);
+        self.dirty = true;
+    }
+
+    // `reset <daily|weekday|off> [target column]` for the selected column
+    fn set_column_reset(&mut self, spec: &str) {
+        let (period, target) = match spec.split_once(' ') {
+            Some((p, t)) => (p, Some(t.trim())),
+            None => (spec, None),
+        };
+
+        let rule = if period.eq_ignore_ascii_case("off") {
+            None
+        } else {
+            let Some(period) = ResetPeriod::parse(period) else {
+                self.message = Some(format!("reset: '{}' is not 'daily', a weekday or 'off'", period));
+                return;
+            };
+            let into = match target.map(|t| self.find_column(t)) {
+                Some(Ok(t)) => match self.get_active_content() {
+                    ActiveContentRef::Board(board) => Some(board.columns[t].title.clone()),
+                    _ => None,
+                },
+                Some(Err(e)) => {
+                    self.message = Some(format!("reset: {}", e));
+                    return;
+                }
+                None => None,
+            };
+            Some(ColumnReset::new(period, into, zone::today()))
+        };
+
+        let (c, _) = self.pos;
+        if let Some(board) = self.root.board_at_mut(&self.location)
+            && let Some(col) = board.columns.get_mut(c) {
+                self.message = Some(match &rule {
+                    Some(r) => format!("'{}' resets {}{}", col.title, r.period,
+                        r.into.as_ref().map(|t| format!(" into '{}'", t)).unwrap_or_default()),
+                    None => format!("'{}' no longer resets", col.title),
+                });
+                col.reset = rule;
+                self.dirty = true;
+            }
+    }
+
+    fn set_column_sort(&mut self, spec: &str) {
+        let sort = if spec.eq_ignore_ascii_case("off") {
+            None
+        } else {
+            let Some(sort) = ColumnSort::parse(spec) else {
+                self.message = Some(format!("sort: '{}' is not title, due or entered (with optional asc/desc), or 'off'", spec));
+                return;
+            };
+            Some(sort)
+        };
+        let (c, _) = self.pos;
+        if let Some(board) = self.root.board_at_mut(&self.location)
+            && let Some(col) = board.columns.get_mut(c) {
+                self.message = Some(match sort {
+                    Some(s) => format!("'{}' is sorted by {}", col.title, s),
+                    None => format!("'{}' keeps tasks in manual order", col.title),
+                });
+                col.sort = sort;
+                self.dirty = true;
+            }
+    }
+
+    // Opens the defaults popup on what the selected column has now
+    fn edit_column_defaults(&mut self) {
+        if let Some(board) = self.root.board_at(&self.location)
+            && let Some(col) = board.columns.get(self.pos.0) {
+                self.input_buffer = col.defaults.as_ref().map(ToString::to_string).unwrap_or_default();
+                self.input_mode = InputMode::EditingColumnDefaults;
+            }
+    }
+
+    fn set_column_defaults(&mut self, spec: &str) {
+        let defaults = match TaskDefaults::parse(spec, zone::today()) {
+            Ok(defaults) => defaults,
+            Err(e) => {
+                self.message = Some(format!("defaults: {}", e));
+                return;
+            }
+        };
+        let (c, _) = self.pos;
+        if let Some(board) = self.root.board_at_mut(&self.location)
+            && let Some(col) = board.columns.get_mut(c) {
+                self.message = Some(match &defaults {
+                    Some(d) => format!("New tasks in '{}' start with {}", col.title, d),
+                    None => format!("New tasks in '{}' start blank", col.title),
+                });
+                col.defaults = defaults;
+                self.dirty = true;
+            }
+    }
+
+    fn toggle_requires_reason(&mut self) {
+        let (c, _) = self.pos;
+        if let Some(board) = self.root.board_at_mut(&self.location) {
+            if let Some(col) = board.columns.get_mut(c) {
+                col.requires_reason = !col.requires_reason;
+                self.message = Some(if col.requires_reason {
+                    format!("Tasks moved into '{}' now need a reason", col.title)
+                } else {
+                    format!("'{}' no longer asks for a reason", col.title)
+                });
+                self.dirty = true;
+            }
+        } else {
+            self.message = Some("blocked: not viewing a board".to_string());
+        }
+    }
+
+    fn toggle_frozen(&mut self, whole_board: bool) {
+        let (c, _) = self.pos;
+        let Some(board) = self.root.board_at_mut(&self.location) else {
+            self.message = Some("freeze: not viewing a board".to_string());
+            return;
+        };
+        let (title, frozen) = if whole_board {
+            board.frozen = !board.frozen;
+            (format!("Board '{}'", board.title), board.frozen)
+        } else if let Some(col) = board.columns.get_mut(c) {
+            col.frozen = !col.frozen;
+            (format!("'{}'", col.title), col.frozen)
+        } else {
+            return;
+        };
+        self.message = Some(format!("{} is {}", title, if frozen { "frozen: read-only until :freeze again" } else { "no longer frozen" }));
+        self.dirty = true;
+    }
+
+    fn toggle_wide_cards(&mut self) {
+        let Some(board) = self.root.board_at_mut(&self.location) else {
+            self.message = Some("wide: not viewing a board".to_string());
+            return;
+        };
+        board.wide_cards = !board.wide_cards;
+        self.message = Some(if board.wide_cards { "Long titles wrap onto a second line" } else { "Titles fit on one line" }.to_string());
+        self.dirty = true;
+    }
+
+    // Without a spec, says what the board's lanes are
+    fn set_lanes(&mut self, spec: Option<&str>) {
+        let Some(board) = self.root.board_at_mut(&self.location) else {
+            self.message = Some("lanes: not viewing a board".to_string());
+            return;
+        };
+        let lanes = match spec.map(Lanes::parse) {
+            None => {
+                self.message = Some(match &board.lanes {
+                    Some(lanes) => format!("Swimlanes by {} (:lanes off to drop them)", lanes),
+                    None => "No swimlanes (:lanes assignee, tag [prefix] or field)".to_string(),
+                });
+                return;
+            }
+            Some(Err(e)) => {
+                self.message = Some(format!("lanes: {}", e));
+                return;
+            }
+            Some(Ok(lanes)) => lanes,
+        };
+        self.message = Some(match &lanes {
+            Some(lanes) => format!("Swimlanes by {} ({{ and }} move between them)", lanes),
+            None => "No more swimlanes".to_string(),
+        });
+        board.lanes = lanes;
+        self.dirty = true;
+    }
+
+    fn set_lane(&mut self, name: &str) {
+        let ActiveContentRef::Board(board) = self.get_active_content() else { return };
+        // The field is kept either way, but only shows as lanes keyed on it
+        let hint = if board.lanes == Some(Lanes::Field) { "" } else { " (:lanes field to show it)" };
+        let Some(task) = self.root.task_at_mut(&self.selected_path()) else {
+            self.message = Some("lane: no task selected".to_string());
+            return;
+        };
+        task.lane = if name.eq_ignore_ascii_case("off") {
+            self.message = Some(format!("'{}' is in no lane", task.title));
+            None
+        } else {
+            self.message = Some(format!("'{}' is in lane '{}'{}", task.title, name, hint));
+            Some(name.to_string())
+        };
+        self.dirty = true;
+    }
+
+    fn snooze(&mut self, until: Option<NaiveDate>) {
+        if !matches!(self.get_active_content(), ActiveContentRef::Board(_)) { return; }
+        let Some(task) = self.root.task_at_mut(&self.selected_path()) else {
+            self.message = Some("snooze: no task selected".to_string());
+            return;
+        };
+        task.snoozed_until = until;
+        self.message = Some(match until {
+            Some(day) => format!("'{}' snoozed until {}", task.title, day.format("%a %Y-%m-%d")),
+            None => format!("'{}' is back", task.title),
+        });
+        self.dirty = true;
+    }
+
+    fn set_due(&mut self, due: Option<DateTime<Utc>>) {
+        if !matches!(self.get_active_content(), ActiveContentRef::Board(_)) { return; }
+        let Some(task) = self.root.task_at_mut(&self.selected_path()) else {
+            self.message = Some("due: no task selected".to_string());
+            return;
+        };
+        task.due = due;
+        self.message = Some(match due {
+            Some(at) => format!("'{}' is due {}", task.title, zone::format_due(at, "%a %Y-%m-%d")),
+            None => format!("'{}' has no due date", task.title),
+        });
+        self.dirty = true;
+    }
+
+    // Adds a reminder, or with None drops them all
+    fn set_reminder(&mut self, at: Option<DateTime<Utc>>) {
+        if !matches!(self.get_active_content(), ActiveContentRef::Board(_)) { return; }
+        let Some(task) = self.root.task_at_mut(&self.selected_path()) else {
+            self.message = Some("remind: no task selected".to_string());
+            return;
+        };
+        match at {
+            Some(at) if at <= Utc::now() => {
+                self.message = Some(format!("remind: {} has already passed", zone::format_due(at, "%a %Y-%m-%d")));
+                return;
+            }
+            Some(at) => {
+                task.reminders.push(at);
+                task.reminders.sort();
+                task.reminders.dedup();
+                self.message = Some(format!("Reminder for '{}' at {}", task.title, zone::format_due(at, "%a %Y-%m-%d")));
+            }
+            None => {
+                task.reminders.clear();
+                self.message = Some(format!("'{}' has no reminders", task.title));
+            }
+        }
+        self.dirty = true;
+    }
+
+    // Fires reminders that came due in any workspace, once each
+    fn remind(&mut self) {
+        self.next_reminder = Instant::now() + REMINDER_CHECK;
+        if self.in_memory {
+            return;
+        }
+        let due = reminder::due(&self.config.board.file, &self.all_workspaces(), Utc::now());
+        let Some(first) = due.first() else { return };
+        self.message = Some(match due.len() {
+            1 => format!("⏰ {} ({})", first.title, first.board),
+            n => format!("⏰ {} ({}) and {} more", first.title, first.board, n - 1),
+        });
+        if let Err(e) = reminder::record(&self.config.board.file, &due) {
+            self.message = Some(format!("reminders: {:#}", e));
+        }
+        self.redraw = true;
+    }
+
+    fn start_replace(&mut self, spec: &str) {
+        if !matches!(self.get_active_content(), ActiveContentRef::Board(_)) {
+            self.message = Some("replace: not viewing a board".to_string());
+            return;
+        }
+        let (find, replace, tree) = match replace::parse(spec) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                self.message = Some(format!("replace: {}", e));
+                return;
+            }
+        };
+        match ReplaceSession::start(&mut self.root, &self.location, &find, &replace, tree) {
+            Some(session) => {
+                self.replace = Some(session);
+                self.input_mode = InputMode::Replacing;
+            }
+            None => self.message = Some(format!("replace: '{}' not found", find)),
+        }
+    }
+
+    fn answer_replace(&mut self, answer: Answer) {
+        let Some(session) = self.replace.as_mut() else { return };
+        let before = session.replaced;
+        let more = session.answer(&mut self.root, answer);
+        if session.replaced > before {
+            self.dirty = true;
+        }
+        if !more {
+            self.message = Some(format!("Replaced {} occurrence(s)", session.replaced));
+            self.replace = None;
+            self.input_mode = InputMode::Normal;
+        }
+    }
+
+    /// The `/` query in effect: the one being typed, or the last one entered.
+    pub fn search_query(&self) -> &str {
+        if self.input_mode == InputMode::Searching { &self.input_buffer } else { &self.board_search }
+    }
+
+    pub fn search_hit(&self, task: &Task) -> bool {
+        let query = self.search_query();
+        !query.is_empty() && search::matched_field(task, &collate::fold(query)).is_some()
+    }
+
+    // Moves the cursor to the next match of `board_search` on the board after
+    // `step` (0: at or after the cursor), wrapping around at either end
+    fn jump_to_match(&mut self, step: i32) {
+        if self.board_search.is_empty() {
+            self.message = Some("No search; press / to start one".to_string());
+            return;
+        }
+        let ActiveContentRef::Board(board) = self.get_active_content() else { return };
+        let today = zone::today();
+        let matches: Vec<(usize, usize)> = board.columns.iter().enumerate()
+            .flat_map(|(c, col)| col.tasks.iter().enumerate().map(move |(r, t)| (c, r, t)))
+            .filter(|(_, _, t)| self.task_visible(t, today) && self.search_hit(t))
+            .map(|(c, r, _)| (c, r))
+            .collect();
+        if matches.is_empty() {
+            self.message = Some(format!("No match for '{}'", self.board_search));
+            return;
+        }
+        let i = match step {
+            0 => matches.iter().position(|&m| m >= self.pos).unwrap_or(0),
+            s if s > 0 => matches.iter().position(|&m| m > self.pos).unwrap_or(0),
+            _ => matches.iter().rposition(|&m| m < self.pos).unwrap_or(matches.len() - 1),
+        };
+        self.pos = matches[i];
+        self.message = Some(format!("'{}': match {} of {}", self.board_search, i + 1, matches.len()));
+    }
+
+    /// Whether the board view lists `task`, given the snooze, waiting and quick filters.
+    pub fn task_visible(&self, task: &Task, today: NaiveDate) -> bool {
+        (self.show_snoozed || !task.is_snoozed(today)) && (!self.only_waiting || task.waiting_on.is_some())
+            && self.quick_filter.is_none_or(|f| f.keeps(task, Utc::now()))
+    }
+
+    // Command line prefilled for a board action that needs an argument
+    fn begin_command(&mut self, prefix: &str) {
+        if let ActiveContentRef::Board(_) = self.get_active_content() {
+            self.input_buffer = prefix.to_string();
+            self.input_mode = InputMode::Command;
+        }
+    }
+
+    fn set_waiting(&mut self, who: &str) {
+        if !matches!(self.get_active_content(), ActiveContentRef::Board(_)) { return; }
+        let Some(task) = self.root.task_at_mut(&self.selected_path()) else {
+            self.message = Some("wait: no task selected".to_string());
+            return;
+        };
+        task.waiting_on = if who.eq_ignore_ascii_case("off") {
+            self.message = Some(format!("'{}' is no longer waiting", task.title));
+            None
+        } else {
+            self.message = Some(format!("'{}' is waiting on {}", task.title, who));
+            Some(WaitingOn { who: who.to_string(), since: zone::today() })
+        };
+        self.dirty = true;
+    }
+
+    // Picking the filter already on turns it off
+    fn toggle_quick_filter(&mut self, filter: QuickFilter) {
+        self.quick_filter = (self.quick_filter != Some(filter)).then_some(filter);
+        self.message = Some(match self.quick_filter {
+            Some(f) => format!("Showing only {} (again to show all)", f.label()),
+            None => "Showing all tasks".to_string(),
+        });
+        self.move_cursor(0, 0);
+    }
+
+    fn toggle_waiting_filter(&mut self) {
+        self.only_waiting = !self.only_waiting;
+        self.message = Some(if self.only_waiting { "Showing only waiting tasks" } else { "Showing all tasks" }.to_string());
+    }
+
+    /// Summary of the board a task holds, computed on first use.
+    pub fn board_summary(&self, task: &Task) -> Option<BoardSummary> {
+        let Some(TaskContent::Board(ref board)) = task.content else { return None };
+        let summary = *self.board_summaries.borrow_mut().entry(task.id).or_insert_with(|| {
+            let (now, follow_up) = (Utc::now(), self.config.waiting.follow_up_days);
+            let mut alerts = 0;
+            search::walk_tasks(board, &mut |_, _, _, t| alerts += usize::from(t.needs_attention(now, follow_up)));
+            BoardSummary { columns: board.columns.len(), open: board.open_tasks(), alerts }
+        });
+        Some(summary)
+    }
+
+    /// Badge menu rows: the shown badges in card order, then the hidden ones.
+    pub fn badge_menu(&self) -> Vec<(Badge, bool)> {
+        let shown = &self.config.badges.show;
+        let hidden = Badge::ALL.iter().filter(|b| !shown.contains(b));
+        shown.iter().map(|&b| (b, true)).chain(hidden.map(|&b| (b, false))).collect()
+    }
+
+    // Menu changes last for the session; `[badges]` in the config makes them stick
+    fn toggle_badge(&mut self) {
+        let Some(&(badge, shown)) = self.badge_menu().get(self.badge_idx) else { return };
+        let show = &mut self.config.badges.show;
+        if shown {
+            show.retain(|&b| b != badge);
+        } else {
+            show.push(badge);
+        }
+        self.badge_idx = self.badge_menu().iter().position(|&(b, _)| b == badge).unwrap_or(0);
+    }
+
+    fn move_badge(&mut self, delta: i32) {
+        let show = &mut self.config.badges.show;
+        if self.input_mode != InputMode::Badges || self.badge_idx >= show.len() { return; }
+        let to = (self.badge_idx as i32 + delta).clamp(0, show.len() as i32 - 1) as usize;
+        show.swap(self.badge_idx, to);
+        self.badge_idx = to;
+    }
+
+    fn toggle_show_snoozed(&mut self) {
+        self.show_snoozed = !self.show_snoozed;
+        self.message = Some(if self.show_snoozed { "Showing snoozed tasks" } else { "Hiding snoozed tasks" }.to_string());
+    }
+
+    /// The archive of the board in view, newest first as the archive view lists it.
+    pub fn archived(&self) -> Vec<&model::Archived> {
         match self.get_active_content() {
-            ActiveContentRef::Board(_) => {
-                // Adding variable to avoid borrow checker hell
-                let title = self.input_buffer.trim().to_string();
-                if !title.is_empty() {
-                    let (c, _) = self.cursor;
-                    let board = Self::get_board_recursive(&mut self.root, &self.path);
-                    if c < board.columns.len() {
-                        board.columns[c].tasks.push(Task::new(&title, ""));
-                        self.dirty = true;
-                    }
+            ActiveContentRef::Board(board) => board.archive.iter().rev().collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn archive_task(&mut self) {
+        if self.input_mode != InputMode::Normal { return; }
+        let (c, r) = self.pos;
+        let ActiveContentMut::Board(board) = ActiveContentMut::resolve(&mut self.root, &self.location) else {
+            self.message = Some("archive: not viewing a board".to_string());
+            return;
+        };
+        let Some(archived) = board.archive_task(c, r) else { return };
+        self.message = Some(format!("Archived '{}' (g A lists the archive)", archived.task.title));
+        self.dirty = true;
+        if r > 0 && r >= board.columns[c].tasks.len() {
+            self.pos.1 -= 1;
+        }
+    }
+
+    // Clears out the last column, where finished tasks pile up
+    fn archive_done(&mut self) {
+        let ActiveContentMut::Board(board) = ActiveContentMut::resolve(&mut self.root, &self.location) else {
+            self.message = Some("archive: not viewing a board".to_string());
+            return;
+        };
+        let last = board.columns.len() - 1;
+        let count = board.columns[last].tasks.len();
+        for _ in 0..count {
+            board.archive_task(last, 0);
+        }
+        self.message = Some(format!("Archived {} task{} from '{}'", count, if count == 1 { "" } else { "s" }, board.columns[last].title));
+        self.dirty |= count > 0;
+    }
+
+    fn toggle_archive(&mut self) {
+        if self.input_mode == InputMode::Archive {
+            self.input_mode = InputMode::Normal;
+        } else if self.input_mode == InputMode::Normal {
+            match self.get_active_content() {
+                ActiveContentRef::Board(board) if board.archive.is_empty() => self.message = Some("Nothing archived on this board (A archives a task)".to_string()),
+                ActiveContentRef::Board(_) => {
+                    self.archive_idx = 0;
+                    self.input_mode = InputMode::Archive;
                 }
-            },
-            ActiveContentRef::Todo(_) => {
-                let text = self.input_buffer.trim().to_string();
-                if !text.is_empty() {
-                    let _items: Vec<TodoItem> = Vec::new(); // placeholder - unused logic branch if we just want to submit simple task?
-                    // Actually, for Todo list, we add items via `a`. SubmitTask is for `Editing` mode.
-                    // If we are in `Editing` mode inside a Todo view?
-                    // We only use Editing mode for *renaming*? Or adding?
-                    // My logic in `submit_input`: `match active_content`.
-                    // If `ActiveContent::Todo`, `input_buffer` is the new item text?
-                    // Yes.
-                    
-                    let text = self.input_buffer.trim().to_string();
-                    if !text.is_empty() {
-                         self.add_todo_item(text);
-                         // self.dirty set inside add_todo_item
-                    }
+                _ => self.message = Some("archived: not viewing a board".to_string()),
+            }
+        }
+    }
+
+    // Back to its column, with the cursor on it; the view stays open while more are left
+    fn restore_archived(&mut self) {
+        let ActiveContentMut::Board(board) = ActiveContentMut::resolve(&mut self.root, &self.location) else { return };
+        let Some(i) = board.archive.len().checked_sub(self.archive_idx + 1) else { return };
+        let Some(c) = board.restore_archived(i) else { return };
+        let task = board.columns[c].tasks.last().expect("just restored");
+        self.message = Some(format!("Restored '{}' to '{}'", task.title, board.columns[c].title));
+        self.pos = (c, board.columns[c].tasks.len() - 1);
+        self.dirty = true;
+        if board.archive.is_empty() {
+            self.input_mode = InputMode::Normal;
+        }
+        self.archive_idx = self.archive_idx.min(board.archive.len().saturating_sub(1));
+    }
+
+    /// The trash of the board in view, newest first as the trash view lists it.
+    pub fn trashed(&self) -> Vec<&model::Trashed> {
+        match self.get_active_content() {
+            ActiveContentRef::Board(board) => board.trash.iter().rev().collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn toggle_trash(&mut self) {
+        if self.input_mode == InputMode::Trash {
+            self.input_mode = InputMode::Normal;
+        } else if self.input_mode == InputMode::Normal {
+            match self.get_active_content() {
+                ActiveContentRef::Board(board) if board.trash.is_empty() => self.message = Some("The trash of this board is empty".to_string()),
+                ActiveContentRef::Board(_) => {
+                    self.trash_idx = 0;
+                    self.input_mode = InputMode::Trash;
                 }
-            },
-            ActiveContentRef::Text(_) => {
-                // Saving text content
-                let text = self.input_buffer.clone();
-                self.set_text_content(text);
-                // self.dirty set inside set_text_content
-            },
-             _ => {}
+                _ => self.message = Some("trash: not viewing a board".to_string()),
+            }
         }
-        self.input_buffer.clear();
-        self.input_mode = InputMode::Normal;
+    }
+
+    // Back where it was, with the cursor on it; the view stays open while more are left
+    fn restore_trashed(&mut self) {
+        let ActiveContentMut::Board(board) = ActiveContentMut::resolve(&mut self.root, &self.location) else { return };
+        let Some(i) = board.trash.len().checked_sub(self.trash_idx + 1) else { return };
+        let Some((c, r)) = board.restore_trashed(i) else { return };
+        self.message = Some(match r {
+            Some(r) => format!("Restored '{}' to '{}'", board.columns[c].tasks[r].title, board.columns[c].title),
+            None => format!("Restored column '{}'", board.columns[c].title),
+        });
+        self.pos = (c, r.unwrap_or(0));
+        self.dirty = true;
+        if board.trash.is_empty() {
+            self.input_mode = InputMode::Normal;
+        }
+        self.trash_idx = self.trash_idx.min(board.trash.len().saturating_sub(1));
+    }
+
+    // Only one icebox per board: marking a column unmarks the others
+    fn toggle_icebox(&mut self) {
+        let (c, _) = self.pos;
+        let Some(board) = self.root.board_at_mut(&self.location) else {
+            self.message = Some("icebox: not viewing a board".to_string());
+            return;
+        };
+        let Some(on) = board.columns.get(c).map(|col| !col.icebox) else { return };
+        for (i, col) in board.columns.iter_mut().enumerate() {
+            col.icebox = on && i == c;
+        }
+        self.message = Some(if on {
+            format!("'{}' is the icebox", board.columns[c].title)
+        } else {
+            "No icebox on this board".to_string()
+        });
+        self.dirty = true;
+    }
+
+    fn subscribe(&mut self, url: &str) {
+        let c = self.pos.0;
+        let Some(column) = self.root.board_at_mut(&self.location).and_then(|b| b.columns.get_mut(c)) else {
+            self.message = Some("subscribe: not viewing a board".to_string());
+            return;
+        };
+        self.dirty = true;
+        if url == "off" {
+            // What was mirrored stays, as ordinary tasks
+            column.subscription = None;
+            for task in column.tasks.iter_mut().filter(|t| ics::is_read_only(t)) {
+                task.source = None;
+            }
+            self.message = Some(format!("'{}' no longer follows a calendar", column.title));
+            return;
+        }
+        column.subscription = Some(Subscription { url: url.to_string(), refreshed_at: None });
+        self.refresh_subscriptions();
+    }
+
+    fn refresh_subscriptions(&mut self) {
+        let results = ics::refresh_all(&mut self.root);
+        if results.is_empty() {
+            self.message = Some("refresh: no column follows a calendar (:subscribe <url>)".to_string());
+            return;
+        }
+        let mut failed = Vec::new();
+        let (mut added, mut updated, mut removed) = (0, 0, 0);
+        // Even with nothing new, each feed read moved its `refreshed_at` on, which
+        // `ics::read_only_change` tells refreshes by
+        self.dirty |= results.iter().any(|(_, outcome)| outcome.is_ok());
+        for (column, outcome) in results {
+            match outcome {
+                Ok(r) => (added, updated, removed) = (added + r.added, updated + r.updated, removed + r.removed),
+                Err(e) => failed.push(format!("'{}': {:#}", column, e)),
+            }
+        }
+        self.message = Some(if failed.is_empty() {
+            format!("Calendars refreshed: {} added, {} updated, {} removed", added, updated, removed)
+        } else {
+            format!("Refresh failed for {}", failed.join("; "))
+        });
+    }
+
+    // Issues live on the main board, whichever board is in view
+    fn sync_github(&mut self) {
+        self.message = Some(match github::sync(&mut self.root, &self.config.github, &self.config.board.file) {
+            Ok(s) => {
+                self.dirty |= s.added + s.updated + s.closed > 0;
+                format!("GitHub synced: {} added, {} updated, {} closed there, {} moves pushed", s.added, s.updated, s.closed, s.pushed)
+            }
+            Err(e) => format!("sync: {:#}", e),
+        });
+    }
+
+    // Moves the selected task out of the icebox, into `target` or the first other column
+    fn thaw(&mut self, target: Option<&str>) {
+        let (c, r) = self.pos;
+        let ActiveContentRef::Board(board) = self.get_active_content() else {
+            self.message = Some("thaw: not viewing a board".to_string());
+            return;
+        };
+        if !board.columns.get(c).is_some_and(|col| col.icebox && r < col.tasks.len()) {
+            self.message = Some("thaw: select a task in the icebox column".to_string());
+            return;
+        }
+        let new_c = match target {
+            Some(name) => self.find_column(name),
+            None => board.columns.iter().position(|col| !col.icebox).ok_or_else(|| "no column outside the icebox".to_string()),
+        };
+        match new_c {
+            Ok(new_c) => self.move_task_to_column(new_c),
+            Err(e) => self.message = Some(format!("thaw: {}", e)),
+        }
+    }
+
+    fn find_column(&self, name: &str) -> Result<usize, String> {
+        match self.get_active_content() {
+            ActiveContentRef::Board(board) => board.match_column(name).map_err(|e| e.to_string()),
+            _ => Err("not viewing a board".to_string()),
+        }
+    }
+
+    fn ask(&mut self, op: ConfirmOp, prompt: String) {
+        self.pending_confirm = Some((op, prompt));
+        self.input_mode = InputMode::Confirm;
+    }
+
+    fn request_quit(&mut self) {
+        if self.dirty && self.config.autosave.strategy != AutosaveStrategy::Manual {
+            self.save_or_report();
+        }
+        // A failing save always needs an explicit go-ahead, whatever the confirm settings
+        if let Some(failure) = &self.save_failure {
+            let prompt = format!("Saving failed: {}. Quit and lose unsaved changes?", failure.error);
+            self.ask(ConfirmOp::QuitUnsaved, prompt);
+        } else if self.dirty && self.config.confirm.requires(ConfirmOp::QuitUnsaved) {
+            self.ask(ConfirmOp::QuitUnsaved, "Quit with unsaved changes?".to_string());
+        } else {
+            self.should_quit = true;
+        }
+    }
+
+    fn request_delete(&mut self) {
+        if !self.config.confirm.requires(ConfirmOp::DeleteTask) {
+            self.delete_item();
+            return;
+        }
+        let (c, r) = self.pos;
+        let target = match self.get_active_content() {
+            ActiveContentRef::Board(board) => board.columns.get(c).and_then(|col| col.tasks.get(r)).map(|t| t.title.clone()),
+            ActiveContentRef::Todo(items) => self.todo_cursor_item().and_then(|i| items.get(i)).map(|i| i.text.clone()),
+            _ => None,
+        };
+        if let Some(title) = target {
+            self.ask(ConfirmOp::DeleteTask, format!("Delete '{}'?", title));
+        }
+    }
+
+    fn request_delete_column(&mut self) {
+        let ActiveContentRef::Board(board) = self.get_active_content() else { return };
+        let Some(column) = board.columns.get(self.pos.0) else { return };
+        if board.columns.len() == 1 {
+            self.message = Some("A board needs at least one column".to_string());
+            return;
+        }
+        let (title, count) = (column.title.clone(), column.tasks.len());
+        if count > 0 {
+            self.ask(ConfirmOp::DeleteColumn, format!("Delete column '{}' and its {} task{}?", title, count, if count == 1 { "" } else { "s" }));
+        } else if self.config.confirm.requires(ConfirmOp::DeleteColumn) {
+            self.ask(ConfirmOp::DeleteColumn, format!("Delete empty column '{}'?", title));
+        } else {
+            self.delete_column(ColumnTasks::Drop);
+        }
+    }
+
+    /// While deleting a column that has tasks is awaiting an answer: the column
+    /// they would move to.
+    pub fn column_delete_neighbor(&self) -> Option<&str> {
+        if !matches!(self.pending_confirm, Some((ConfirmOp::DeleteColumn, _))) { return None; }
+        let ActiveContentRef::Board(board) = self.get_active_content() else { return None };
+        let c = self.pos.0;
+        if board.columns.get(c)?.tasks.is_empty() { return None; }
+        let neighbor = if c > 0 { c - 1 } else { c + 1 };
+        board.columns.get(neighbor).map(|col| col.title.as_str())
+    }
+
+    fn move_column(&mut self, delta: i32) {
+        let c = self.pos.0;
+        let Some(board) = self.root.board_at_mut(&self.location) else { return };
+        let target = c as i32 + delta;
+        if c >= board.columns.len() || target < 0 || target as usize >= board.columns.len() { return; }
+        board.columns.swap(c, target as usize);
+        self.pos.0 = target as usize;
+        self.dirty = true;
+    }
+
+    fn delete_column(&mut self, tasks: ColumnTasks) {
+        let c = self.pos.0;
+        let Some(board) = self.root.board_at_mut(&self.location) else { return };
+        if c >= board.columns.len() || board.columns.len() == 1 { return; }
+        // Tasks that move on leave the column empty in the trash
+        let moving = if tasks == ColumnTasks::Move { std::mem::take(&mut board.columns[c].tasks) } else { Vec::new() };
+        let Some(trashed) = board.trash_column(c) else { return };
+        let title = trashed.column.clone();
+        let neighbor = c.saturating_sub(1);
+        let moved = moving.len();
+        if moved > 0 {
+            let target = &mut board.columns[neighbor];
+            for mut task in moving {
+                task.enter_column();
+                if target.requires_reason {
+                    task.blocked_reason = Some(format!("moved from deleted column '{}'", title));
+                }
+                target.tasks.push(task);
+            }
+            self.message = Some(format!("Deleted '{}'; {} task{} moved to '{}'", title, moved, if moved == 1 { "" } else { "s" }, target.title));
+        } else {
+            self.message = Some(format!("Deleted '{}' (g x lists the trash)", title));
+        }
+        self.pos.0 = neighbor;
+        self.dirty = true;
     }
 
     fn delete_item(&mut self) {
         match self.get_active_content() {
             ActiveContentRef::Board(board) => {
-                let (c, r) = self.cursor;
+                let (c, r) = self.pos;
                 if c < board.columns.len() && r < board.columns[c].tasks.len() {
-                    let board_mut = Self::get_board_recursive(&mut self.root, &self.path);
-                    board_mut.columns[c].tasks.remove(r);
+                    let Some(board_mut) = self.root.board_at_mut(&self.location) else { return };
+                    let Some(trashed) = board_mut.trash_task(c, r) else { return };
+                    if let model::TrashedItem::Task(task) = &trashed.item {
+                        self.message = Some(format!("Deleted '{}' (g x lists the trash)", task.title));
+                    }
                     self.dirty = true;
                     // Adjust cursor
-                     if r >= board_mut.columns[c].tasks.len() && r > 0 {
-                        self.cursor.1 -= 1;
+                    if r >= board_mut.columns[c].tasks.len() && r > 0 {
+                        self.pos.1 -= 1;
                     }
                 }
             },
             ActiveContentRef::Todo(items) => {
-                let r = self.cursor.1;
-                if r < items.len() {
-                   self.remove_todo_item(r);
-                   // self.dirty handled inside
-                   if r > 0 { self.cursor.1 = r.saturating_sub(1); }
+                let len = items.len();
+                if let Some(i) = self.todo_cursor_item() {
+                   self.remove_todo_item(i);
+                   // self.dirty handled inside; stay on the item that took its place
+                   self.pos.1 = self.pos.1.min(len.saturating_sub(2));
                 }
             },
             _ => {}
         }
     }
 
-    fn toggle_todo(&mut self) {
-        if let ActiveContentRef::Todo(items) = self.get_active_content() {
-            let r = self.cursor.1;
-            if r < items.len() {
-                self.toggle_todo_item(r);
-            }
+    fn toggle_checklist(&mut self) {
+        if self.input_mode == InputMode::Checklist {
+            self.input_mode = InputMode::Normal;
+            self.checklist.clear();
+            return;
         }
-    }
-
-    // --- Helpers / View Logic ---
 
-    pub fn get_breadcrumbs(&self) -> Vec<String> {
-        let mut crumbs = vec!["Main Board".to_string()];
-        let mut board = &self.root;
-        
-        for &(col_idx, task_idx) in &self.path {
-            if let Some(col) = board.columns.get(col_idx) {
-                if let Some(task) = col.tasks.get(task_idx) {
-                    crumbs.push(task.title.clone());
-                    if let Some(TaskContent::Board(ref b)) = task.content {
-                        board = b;
-                    } 
+        let mut entries = Vec::new();
+        let today = zone::today();
+        let mut parked: Vec<Vec<(usize, usize)>> = Vec::new(); // Icebox and snoozed tasks; their sub-boards are skipped too
+        search::walk_tasks(&self.root, &mut |crumbs, location, column, task| {
+            if parked.iter().any(|p| location.starts_with(p)) {
+                return;
+            }
+            if column.icebox || task.is_snoozed(today) {
+                parked.push(location.to_vec());
+                return;
+            }
+            if let Some(TaskContent::Todo(items)) = &task.content {
+                let group = format!("{} > {}", crumbs.join(" > "), task.title);
+                for (i, item) in items.iter().enumerate().filter(|(_, item)| !item.done) {
+                    entries.push(ChecklistEntry { task: location.to_vec(), group: group.clone(), item: i, text: item.text.clone(), done: false });
                 }
             }
+        });
+        if entries.is_empty() {
+            self.message = Some("No open todo items".to_string());
+            return;
         }
-        crumbs
+        self.checklist = entries;
+        self.checklist_idx = 0;
+        self.input_mode = InputMode::Checklist;
     }
 
-    pub fn get_active_content(&self) -> ActiveContentRef<'_> {
-        // Traverse to the tip of path
-        let mut board = &self.root;
-
-        for &(col_idx, task_idx) in &self.path {
-            if let Some(col) = board.columns.get(col_idx) {
-                if let Some(task) = col.tasks.get(task_idx) {
-                    if let Some(TaskContent::Board(ref b)) = task.content {
-                        board = b;
-                    } else {
-                        // Leaf is not a board, so return its content
-                        if let Some(ref content) = task.content {
-                            match content {
-                                TaskContent::Todo(items) => return ActiveContentRef::Todo(items),
-                                TaskContent::Text(txt) => return ActiveContentRef::Text(txt),
-                                TaskContent::Board(_) => {}
-                            }
-                        } else {
-                             return ActiveContentRef::None;
-                        }
-                    }
-                }
+    fn toggle_checklist_item(&mut self) {
+        let Some(entry) = self.checklist.get(self.checklist_idx) else { return };
+        let (task_path, index) = (entry.task.clone(), entry.item);
+        let Some(task) = self.root.task_at_mut(&task_path) else { return };
+        let Some(TaskContent::Todo(ref mut items)) = task.content else { return };
+        let Some(item) = items.get_mut(index) else { return };
+        item.done = !item.done;
+
+        // Keep the pending-first order of the todo view, and follow the items that moved
+        let mut order: Vec<usize> = (0..items.len()).collect();
+        order.sort_by_key(|&i| items[i].done);
+        *items = order.iter().map(|&i| items[i].clone()).collect();
+        for e in self.checklist.iter_mut().filter(|e| e.task == task_path) {
+            if e.item == index {
+                e.done = !e.done;
             }
+            e.item = order.iter().position(|&i| i == e.item).unwrap_or(e.item);
         }
-        ActiveContentRef::Board(board)
+        self.dirty = true;
     }
 
+    // Leaves the checklist for the todo list holding the selected item
+    fn open_checklist_item(&mut self) {
+        let Some(entry) = self.checklist.get(self.checklist_idx) else { return };
+        let item = entry.item;
+        self.location = entry.task.clone();
+        let row = match self.get_active_content() {
+            ActiveContentRef::Todo(items) => model::todo_order(items).iter().position(|&i| i == item).unwrap_or(0),
+            _ => 0,
+        };
+        self.pos = (0, row);
+        self.input_mode = InputMode::Normal;
+        self.checklist.clear();
+    }
 
+    // Layout is stored on the task, so each list keeps its own
+    fn change_todo_layout(&mut self, change: impl FnOnce(&mut TodoLayout)) {
+        if !matches!(self.get_active_content(), ActiveContentRef::Todo(_)) { return; }
+        if let Some(task) = self.root.task_at_mut(&self.location) {
+            change(&mut task.todo_layout);
+            self.dirty = true;
+        }
+    }
 
-    fn get_board_recursive<'a>(board: &'a mut Board, path: &[(usize, usize)]) -> &'a mut Board {
-        if path.is_empty() {
-             return board;
+    // The cursor stays at its row, so the next item moves up under it
+    fn toggle_todo(&mut self) {
+        if let Some(i) = self.todo_cursor_item() {
+            self.toggle_todo_item(i);
         }
-        let (col_idx, task_idx) = path[0];
-        // We assume valid path
-        if let Some(TaskContent::Board(ref mut b)) = board.columns[col_idx].tasks[task_idx].content {
-            return Self::get_board_recursive(b, &path[1..]);
+    }
+
+    /// Index into the todo list of the item under the cursor (see `model::todo_order`).
+    fn todo_cursor_item(&self) -> Option<usize> {
+        match self.get_active_content() {
+            ActiveContentRef::Todo(items) => model::todo_order(items).get(self.pos.1).copied(),
+            _ => None,
         }
-        
-        // If we are here, logic error (asking for board but found something else)
-        panic!("Invalid path: expected Board");
+    }
+
+    // --- Helpers / View Logic ---
+
+    pub fn get_breadcrumbs(&self) -> Vec<String> {
+        let titles = (1..=self.location.len()).map_while(|n| self.root.task_at(&self.location[..n]).map(|t| t.title.clone()));
+        // With several workspaces the first crumb says which one is open
+        let first = if self.workspaces.len() > 1 { self.root.title.clone() } else { "Main Board".to_string() };
+        std::iter::once(first).chain(titles).collect()
+    }
+
+    /// Path to the task under the board cursor (which may not exist).
+    fn selected_path(&self) -> Vec<(usize, usize)> {
+        let mut path = self.location.clone();
+        path.push(self.pos);
+        path
+    }
+
+    /// The task under the cursor when viewing a board.
+    pub fn selected_task(&self) -> Option<&Task> {
+        if !matches!(self.get_active_content(), ActiveContentRef::Board(_)) || self.cursor.is_none() {
+            return None;
+        }
+        self.root.task_at(&self.selected_path())
+    }
+
+    /// The task whose content is being viewed; None on the main board.
+    pub fn active_task(&self) -> Option<&Task> {
+        self.root.task_at(&self.location)
+    }
+
+    pub fn get_active_content(&self) -> ActiveContentRef<'_> {
+        ActiveContentRef::resolve(&self.root, &self.location)
     }
 
     fn add_todo_item(&mut self, text: String) {
-        // We want the task at `self.path`.
-        if let Some(task) = Self::get_task_mut_recursive(&mut self.root, &self.path) {
-            if let Some(TaskContent::Todo(ref mut items)) = task.content {
-                items.push(TodoItem { text, done: false });
-                items.sort_by_key(|k| k.done);
-                self.dirty = true;
-            }
+        if let Some(problem) = validate::title(&text, &self.config.limits) {
+            self.message = Some(format!("add: {}", problem));
+            return;
+        }
+        if let ActiveContentMut::Todo(items) = ActiveContentMut::resolve(&mut self.root, &self.location) {
+            items.push(TodoItem { text, done: false });
+            items.sort_by_key(|k| k.done);
+            self.dirty = true;
         }
     }
 
     fn remove_todo_item(&mut self, index: usize) {
-        if let Some(task) = Self::get_task_mut_recursive(&mut self.root, &self.path) {
-            if let Some(TaskContent::Todo(ref mut items)) = task.content {
-                if index < items.len() { 
-                    items.remove(index); 
-                    self.dirty = true;
-                }
+        if let ActiveContentMut::Todo(items) = ActiveContentMut::resolve(&mut self.root, &self.location)
+            && index < items.len() {
+                items.remove(index);
+                self.dirty = true;
             }
-        }
     }
 
     fn toggle_todo_item(&mut self, index: usize) {
-        if let Some(task) = Self::get_task_mut_recursive(&mut self.root, &self.path) {
-             if let Some(TaskContent::Todo(ref mut items)) = task.content {
-                 if let Some(item) = items.get_mut(index) {
-                     item.done = !item.done;
-                     self.dirty = true;
-                 }
-                 items.sort_by_key(|k| k.done);
-             }
+        if let ActiveContentMut::Todo(items) = ActiveContentMut::resolve(&mut self.root, &self.location) {
+            if let Some(item) = items.get_mut(index) {
+                item.done = !item.done;
+                self.dirty = true;
+            }
+            items.sort_by_key(|k| k.done);
         }
     }
 
     fn set_text_content(&mut self, text: String) {
-        if let Some(task) = Self::get_task_mut_recursive(&mut self.root, &self.path) {
-            task.content = Some(TaskContent::Text(text));
+        if let ActiveContentMut::Text(current) = ActiveContentMut::resolve(&mut self.root, &self.location) {
+            *current = text;
             self.dirty = true;
         }
     }
@@ -461,64 +2837,91 @@ impl App {
         
         // Only works if active content is a Board (tasks move between columns)
         if let ActiveContentRef::Board(board) = self.get_active_content() {
-             let (c, r) = self.cursor;
-             let new_c = c as i32 + dir;
+             let new_c = self.pos.0 as i32 + dir;
              
              // Check bounds
              if new_c < 0 || new_c >= board.columns.len() as i32 {
                  return;
              }
-             let new_c = new_c as usize;
-             
-              // Mutate
-              {
-                  let board_mut = Self::get_board_recursive(&mut self.root, &self.path);
-                  if r < board_mut.columns[c].tasks.len() {
-                     let task = board_mut.columns[c].tasks.remove(r);
-                     board_mut.columns[new_c].tasks.push(task);
-                     self.dirty = true;
-                     
-                     // Adjust cursor
-                     // If we moved right, we are now at the bottom of new_c? 
-                     // Or should we try to stay at same relative index?
-                     // Standard Kanban: Move to bottom of new column usually.
-                     // But let's just update cursor to follow the task at the end of new list
-                     
-                     self.cursor = (new_c, board_mut.columns[new_c].tasks.len() - 1);
-                     
-                     // Also need to clamp the OLD column cursor if we were not at the bottom?
-                     // Actually, since we switch `self.cursor.0` to `new_c`, we don't care about old column row index anymore,
-                     // except if we move BACK? 
-                     // Wait, `cursor` is `(col, row)`.
-                     // If we just changed columns, we are fine.
-                 }
-             }
+             self.move_task_to_column(new_c as usize);
         }
     }
 
-    fn get_task_mut_recursive<'a>(board: &'a mut Board, path: &[(usize, usize)]) -> Option<&'a mut Task> {
-        if path.is_empty() { return None; }
-        let (col_idx, task_idx) = path[0];
-        
-        if path.len() == 1 {
-            return board.columns.get_mut(col_idx).and_then(|c| c.tasks.get_mut(task_idx));
-        }
+    fn move_task_to_column(&mut self, new_c: usize) {
+        let (c, r) = self.pos;
+        if c == new_c { return; }
+
+        // Columns like "Blocked" ask why before the task goes in
+        if let ActiveContentRef::Board(board) = self.get_active_content()
+            && board.columns.get(new_c).is_some_and(|col| col.requires_reason)
+            && board.columns.get(c).is_some_and(|col| r < col.tasks.len()) {
+                self.pending_move = Some(new_c);
+                self.input_buffer.clear();
+                self.input_mode = InputMode::EditingBlockReason;
+                return;
+            }
+        self.move_task_unchecked(new_c);
+    }
 
-        let task = &mut board.columns[col_idx].tasks[task_idx];
-        if let Some(TaskContent::Board(ref mut sub)) = task.content {
-            return Self::get_task_mut_recursive(sub, &path[1..]);
+    fn move_task_unchecked(&mut self, new_c: usize) {
+        let (c, r) = self.pos;
+        if c == new_c { return; }
+
+        let Some(board_mut) = self.root.board_at_mut(&self.location) else { return };
+        if new_c < board_mut.columns.len() && r < board_mut.columns[c].tasks.len() {
+            let mut task = board_mut.columns[c].tasks.remove(r);
+            task.enter_column();
+            board_mut.columns[new_c].tasks.push(task);
+            self.dirty = true;
+
+            // Standard Kanban: the task lands at the bottom of the new column,
+            // so the cursor follows it there
+            self.pos = (new_c, board_mut.columns[new_c].tasks.len() - 1);
         }
-        None
     }
 }
 
-// Helper enum to avoid cloning huge boards constantly? 
-// Actually we clone board for `get_active_content` which is not ideal for performance but fine for CLI.
-// Optimization: Return Cow or references? Complex with App struct borrowing.
-// For now, cloning Board is okay-ish if deep trees aren't huge.
+// What `location` points at: the board being viewed, or the content of the task
+// opened last. Borrows only the tree, so callers can still update other fields.
 pub enum ActiveContentRef<'a> {
     Board(&'a Board),
     Todo(&'a Vec<TodoItem>),
     Text(&'a String),
     None,
 }
+
+pub enum ActiveContentMut<'a> {
+    Board(&'a mut Board),
+    Todo(&'a mut Vec<TodoItem>),
+    Text(&'a mut String),
+    None,
+}
+
+impl<'a> ActiveContentRef<'a> {
+    /// `None` for an empty task, or a location that no longer exists.
+    pub fn resolve(root: &'a Board, location: &[(usize, usize)]) -> Self {
+        if location.is_empty() {
+            return Self::Board(root);
+        }
+        match root.task_at(location).and_then(|t| t.content.as_ref()) {
+            Some(TaskContent::Board(board)) => Self::Board(board),
+            Some(TaskContent::Todo(items)) => Self::Todo(items),
+            Some(TaskContent::Text(text)) => Self::Text(text),
+            None => Self::None,
+        }
+    }
+}
+
+impl<'a> ActiveContentMut<'a> {
+    pub fn resolve(root: &'a mut Board, location: &[(usize, usize)]) -> Self {
+        if location.is_empty() {
+            return Self::Board(root);
+        }
+        match root.task_at_mut(location).and_then(|t| t.content.as_mut()) {
+            Some(TaskContent::Board(board)) => Self::Board(board),
+            Some(TaskContent::Todo(items)) => Self::Todo(items),
+            Some(TaskContent::Text(text)) => Self::Text(text),
+            None => Self::None,
+        }
+    }
+}
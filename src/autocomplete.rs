@@ -0,0 +1,75 @@
+use crate::collate;
+use crate::model::{Board, TaskContent};
+use std::collections::BTreeSet;
+
+const MAX_SUGGESTIONS: usize = 6;
+
+// `:name` shortcodes offered while typing in the input popup
+pub const ICONS: &[(&str, &str)] = &[
+    ("bug", "🐛"),
+    ("check", "✅"),
+    ("clock", "⏰"),
+    ("fire", "🔥"),
+    ("idea", "💡"),
+    ("lock", "🔒"),
+    ("memo", "📝"),
+    ("rocket", "🚀"),
+    ("star", "⭐"),
+    ("warning", "⚠️"),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    pub label: String,       // What the dropdown shows
+    pub replacement: String, // What replaces the token being typed
+}
+
+/// Every `#tag` used in task titles anywhere in the tree.
+pub fn known_tags(board: &Board) -> BTreeSet<String> {
+    let mut tags = BTreeSet::new();
+    collect_tags(board, &mut tags);
+    tags
+}
+
+fn collect_tags(board: &Board, tags: &mut BTreeSet<String>) {
+    for task in board.columns.iter().flat_map(|c| c.tasks.iter()) {
+        tags.extend(tags_in(&task.title).map(str::to_string));
+        if let Some(TaskContent::Board(ref sub)) = task.content {
+            collect_tags(sub, tags);
+        }
+    }
+}
+
+pub fn tags_in(text: &str) -> impl Iterator<Item = &str> {
+    text.split_whitespace()
+        .filter_map(|w| w.strip_prefix('#'))
+        .filter(|t| !t.is_empty())
+}
+
+/// Suggestions for the token at the end of `input`, if it starts with `#` or `:`.
+pub fn suggest(input: &str, tags: &BTreeSet<String>) -> Vec<Suggestion> {
+    let token = input.rsplit(char::is_whitespace).next().unwrap_or("");
+    if let Some(prefix) = token.strip_prefix('#') {
+        let folded = collate::fold(prefix);
+        tags.iter()
+            .filter(|t| collate::fold(t).starts_with(&folded) && t.len() > prefix.len())
+            .take(MAX_SUGGESTIONS)
+            .map(|t| Suggestion { label: format!("#{}", t), replacement: format!("#{} ", t) })
+            .collect()
+    } else if let Some(prefix) = token.strip_prefix(':') {
+        ICONS.iter()
+            .filter(|(name, _)| name.starts_with(prefix))
+            .take(MAX_SUGGESTIONS)
+            .map(|(name, icon)| Suggestion { label: format!("{} :{}", icon, name), replacement: format!("{} ", icon) })
+            .collect()
+    } else {
+        Vec::new()
+    }
+}
+
+/// Replaces the trailing token of `input` with the chosen suggestion.
+pub fn apply(input: &mut String, suggestion: &Suggestion) {
+    let token_len = input.rsplit(char::is_whitespace).next().unwrap_or("").len();
+    input.truncate(input.len() - token_len);
+    input.push_str(&suggestion.replacement);
+}
@@ -0,0 +1,72 @@
+use crate::config::BackupConfig;
+use anyhow::{Context, Result};
+use chrono::{NaiveDateTime, TimeDelta};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Copies of the board file in a second place, such as a synced cloud folder,
+// after every save: one under the file's own name that is always the latest,
+// and dated ones (`kanban-20261016-0930.db`) taken at most every `every_mins`,
+// of which the newest `keep` stay.
+
+const STAMP: &str = "%Y%m%d-%H%M";
+
+/// Copies `file`, just saved, into the `[backup]` directory if there is one.
+/// `now` is local time, for the copy's name.
+pub fn run(file: &Path, config: &BackupConfig, now: NaiveDateTime) -> Result<()> {
+    let Some(dir) = config.dir.as_deref().map(expand) else { return Ok(()) };
+    fs::create_dir_all(&dir).with_context(|| format!("couldn't create {}", dir.display()))?;
+    let name = file.file_name().context("the board file has no name")?;
+    let (stem, ext) = split(&name.to_string_lossy());
+
+    // Whole or not at all, as a sync client may pick it up any time
+    let latest = dir.join(name);
+    let tmp = dir.join(format!(".{}.tmp", name.to_string_lossy()));
+    fs::copy(file, &tmp)?;
+    fs::rename(&tmp, &latest)?;
+
+    let mut dated = dated_copies(&dir, &stem, &ext)?;
+    let due = dated.last().is_none_or(|(at, _)| now - *at >= TimeDelta::minutes(config.every_mins.try_into().unwrap_or(i64::MAX)));
+    let copy = dir.join(format!("{}-{}{}", stem, now.format(STAMP), ext));
+    // Names go by the minute, so saves within one keep its first copy
+    if due && config.keep > 0 && !copy.exists() {
+        fs::copy(&latest, &copy)?;
+        dated.push((now, copy));
+    }
+    let surplus = dated.len().saturating_sub(config.keep);
+    for (_, old) in &dated[..surplus] {
+        fs::remove_file(old)?;
+    }
+    Ok(())
+}
+
+// Dated copies of the board file in `dir`, oldest first
+fn dated_copies(dir: &Path, stem: &str, ext: &str) -> Result<Vec<(NaiveDateTime, PathBuf)>> {
+    let mut found = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        let stamp = name.strip_prefix(stem).and_then(|n| n.strip_prefix('-')).and_then(|n| n.strip_suffix(ext));
+        if let Some(at) = stamp.and_then(|s| NaiveDateTime::parse_from_str(s, STAMP).ok()) {
+            found.push((at, path));
+        }
+    }
+    found.sort();
+    Ok(found)
+}
+
+// `kanban.db` -> ("kanban", ".db")
+fn split(name: &str) -> (String, String) {
+    match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem.to_string(), format!(".{}", ext)),
+        _ => (name.to_string(), String::new()),
+    }
+}
+
+// A leading `~` is the home directory, as in most configs people copy from
+fn expand(dir: &Path) -> PathBuf {
+    match (dir.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => dir.to_path_buf(),
+    }
+}
@@ -0,0 +1,189 @@
+use crate::cli;
+use crate::model::{self, Board, Column, Source};
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+
+// One entry of a batch file, e.g.
+//
+//   - op: column
+//     title: Review
+//   - op: add
+//     title: Write spec
+//     column: todo
+//     source: { system: github, id: "acme/web#12", url: "https://github.com/acme/web/issues/12" }
+//   - op: move
+//     task: Write spec
+//     column: review
+//   - op: set
+//     task: 1ba4e3cf
+//     description: Needs sign-off
+//
+// `board` (where accepted) names a task holding a sub-board; the main board otherwise.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Operation {
+    Column {
+        title: String,
+        #[serde(default)]
+        board: Option<String>,
+    },
+    Add {
+        title: String,
+        #[serde(default)]
+        description: String,
+        #[serde(default)]
+        column: Option<String>,
+        #[serde(default)]
+        board: Option<String>,
+        #[serde(default)]
+        source: Option<Source>, // Where the task was imported from
+    },
+    Move {
+        task: String,
+        column: String,
+        #[serde(default)]
+        reason: Option<String>, // Required when the column asks for one
+    },
+    Set {
+        task: String,
+        #[serde(default)]
+        title: Option<String>,
+        #[serde(default)]
+        description: Option<String>,
+    },
+}
+
+impl Operation {
+    fn name(&self) -> &'static str {
+        match self {
+            Operation::Column { .. } => "column",
+            Operation::Add { .. } => "add",
+            Operation::Move { .. } => "move",
+            Operation::Set { .. } => "set",
+        }
+    }
+}
+
+pub fn parse(text: &str) -> Result<Vec<Operation>> {
+    serde_yaml::from_str(text).context("invalid batch file")
+}
+
+/// Runs `ops` in order against a copy of `root`. The copy is returned only if
+/// every operation succeeded and the result still validates, along with a line
+/// per operation saying what it did (names resolved, for previews).
+pub fn apply(root: &Board, ops: &[Operation]) -> Result<(Board, Vec<String>)> {
+    let mut board = root.clone();
+    let mut done = Vec::new();
+    for (i, op) in ops.iter().enumerate() {
+        done.push(apply_one(&mut board, op).with_context(|| format!("operation {} ({})", i + 1, op.name()))?);
+    }
+
+    if let Err(errors) = model::validate(&board) {
+        let details: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        bail!("batch result failed validation: {}", details.join("; "));
+    }
+    Ok((board, done))
+}
+
+fn apply_one(root: &mut Board, op: &Operation) -> Result<String> {
+    let on = |board: &Option<String>| board.as_ref().map(|b| format!(" on '{}'", b)).unwrap_or_default();
+    Ok(match op {
+        Operation::Column { title, board } => {
+            let path = board_path(root, board.as_deref())?;
+            let target = root.board_at_mut(&path).ok_or_else(|| anyhow!("invalid board path"))?;
+            target.columns.push(Column::new(title));
+            format!("create column '{}'{}", title, on(board))
+        }
+        Operation::Add { title, description, column, board, source } => {
+            // Importing the same source task again updates it instead of adding a copy
+            if let Some(source) = source
+                && let Some(path) = find_source(root, source) {
+                    return refresh(root, &path, title, description, column.as_deref(), source);
+                }
+            let path = board_path(root, board.as_deref())?;
+            let id = cli::add_task(root, &path, title, description, column.as_deref())?;
+            if let Some(source) = source {
+                let task_path = root.path_to(id).ok_or_else(|| anyhow!("task no longer exists"))?;
+                root.task_at_mut(&task_path).ok_or_else(|| anyhow!("invalid task path"))?.source = Some(source.clone());
+            }
+            format!("create task '{}' in {}{}", title, column_of(root, id)?, on(board))
+        }
+        Operation::Move { task, column, reason } => {
+            let path = cli::resolve_task(root, task)?;
+            let target = root.task_at(&path).ok_or_else(|| anyhow!("invalid task path"))?;
+            let (id, title, from) = (target.id, target.title.clone(), column_of(root, target.id)?);
+            cli::move_task(root, &path, column, reason.as_deref())?;
+            format!("move '{}' from {} to {}", title, from, column_of(root, id)?)
+        }
+        Operation::Set { task, title, description } => {
+            let path = cli::resolve_task(root, task)?;
+            let target = root.task_at_mut(&path).ok_or_else(|| anyhow!("invalid task path"))?;
+            let (name, mut changed) = (target.title.clone(), Vec::new());
+            if let Some(title) = title {
+                changed.push(format!("title to '{}'", title));
+                target.title = title.clone();
+            }
+            if let Some(description) = description {
+                changed.push("description".to_string());
+                target.description = description.clone();
+            }
+            format!("change {} of '{}'", if changed.is_empty() { "nothing".to_string() } else { changed.join(" and ") }, name)
+        }
+    })
+}
+
+fn find_source(root: &Board, source: &Source) -> Option<Vec<(usize, usize)>> {
+    root.find_tasks(&|t| t.source.as_ref().is_some_and(|s| s.system == source.system && s.id == source.id)).into_iter().next()
+}
+
+// Brings an earlier import up to date: the source's title, description and
+// column win, everything else added locally (content, dates, notes) is kept.
+// The task stays on whatever board it was moved to.
+fn refresh(root: &mut Board, path: &[(usize, usize)], title: &str, description: &str, column: Option<&str>, source: &Source) -> Result<String> {
+    let task = root.task_at_mut(path).ok_or_else(|| anyhow!("invalid task path"))?;
+    let (name, mut changed) = (task.title.clone(), Vec::new());
+    if task.title != title {
+        task.title = title.to_string();
+        changed.push(format!("title to '{}'", title));
+    }
+    // An empty description means the import didn't say
+    if !description.is_empty() && task.description != description {
+        task.description = description.to_string();
+        changed.push("description".to_string());
+    }
+    if task.source.as_ref() != Some(source) {
+        task.source = Some(source.clone());
+        changed.push("source url".to_string());
+    }
+
+    if let Some(column) = column {
+        let (&(c, _), parent) = path.split_last().ok_or_else(|| anyhow!("invalid task path"))?;
+        let board = root.board_at(parent).ok_or_else(|| anyhow!("invalid task path"))?;
+        let target = board.match_column(column)?;
+        if target != c {
+            let to = board.columns[target].title.clone();
+            cli::move_task(root, path, column, Some(&format!("set by {} import", source.system)))?;
+            changed.push(format!("moved to {}", to));
+        }
+    }
+
+    Ok(if changed.is_empty() {
+        format!("unchanged '{}' ({} {})", name, source.system, source.id)
+    } else {
+        format!("update '{}': {}", name, changed.join(", "))
+    })
+}
+
+fn column_of(root: &Board, id: uuid::Uuid) -> Result<String> {
+    let path = root.path_to(id).ok_or_else(|| anyhow!("task no longer exists"))?;
+    let (&(c, _), parent) = path.split_last().ok_or_else(|| anyhow!("invalid task path"))?;
+    let board = root.board_at(parent).ok_or_else(|| anyhow!("invalid task path"))?;
+    Ok(board.columns[c].title.clone())
+}
+
+fn board_path(root: &Board, board: Option<&str>) -> Result<Vec<(usize, usize)>> {
+    match board {
+        Some(name) => cli::resolve_board(root, name),
+        None => Ok(Vec::new()),
+    }
+}
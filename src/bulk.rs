@@ -0,0 +1,155 @@
+use crate::autocomplete;
+use crate::cli;
+use crate::collate;
+use crate::date;
+use crate::model::{Board, WaitingOn};
+use crate::reminder;
+use crate::zone;
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::Args;
+use uuid::Uuid;
+
+// One edit applied to many tasks at once, e.g. every search match of `kanban edit`.
+#[derive(Args, Debug)]
+pub struct BulkEdit {
+    /// Append `#tag` to titles that don't have it (repeatable)
+    #[arg(long = "add-tag", value_name = "TAG")]
+    add_tags: Vec<String>,
+    /// Drop `#tag` from titles (repeatable)
+    #[arg(long = "remove-tag", value_name = "TAG")]
+    remove_tags: Vec<String>,
+    /// Move to this column of each task's board; fuzzy matched
+    #[arg(long = "move", value_name = "COLUMN")]
+    move_to: Option<String>,
+    /// Why the tasks are blocked, for columns that ask for one
+    #[arg(long, requires = "move_to")]
+    reason: Option<String>,
+    /// Hide until a date (2026-11-03, tomorrow, next fri, in 2 weeks) or `off`
+    #[arg(long, value_name = "WHEN")]
+    snooze: Option<String>,
+    /// Mark as waiting on someone from today, or `off`
+    #[arg(long, value_name = "WHO")]
+    wait: Option<String>,
+    /// Due date (2026-11-03, tomorrow, next fri, in 2 weeks), optionally with a time ("fri 5pm"), or `off`
+    #[arg(long, value_name = "WHEN")]
+    due: Option<String>,
+    /// Add a reminder (fri 9:00, tomorrow at 5pm; a day alone means 9:00), or `off` to drop them all
+    #[arg(long, value_name = "WHEN")]
+    remind: Option<String>,
+}
+
+/// What changed on one task, for the preview.
+pub struct Change {
+    pub title: String, // As it was before the edit
+    pub what: Vec<String>,
+}
+
+impl BulkEdit {
+    fn is_empty(&self) -> bool {
+        self.add_tags.is_empty() && self.remove_tags.is_empty() && self.move_to.is_none() && self.snooze.is_none() && self.wait.is_none() && self.due.is_none() && self.remind.is_none()
+    }
+
+    /// Applies the edit to every task in `ids`; tasks it leaves as they were are not reported.
+    pub fn apply(&self, root: &mut Board, ids: &[Uuid], today: NaiveDate) -> Result<Vec<Change>> {
+        if self.is_empty() {
+            bail!("nothing to change (see --help for the edits)");
+        }
+        let snooze = read_date(self.snooze.as_deref(), |s| date::parse(s, today))?;
+        let due = read_date(self.due.as_deref(), |s| date::parse_due(s, today))?;
+        let remind = read_date(self.remind.as_deref(), |s| reminder::parse(s, today))?;
+
+        let mut changes = Vec::new();
+        for &id in ids {
+            let change = self.apply_one(root, id, snooze, due, remind, today)
+                .with_context(|| format!("task {}", &id.to_string()[..8]))?;
+            if let Some(change) = change {
+                changes.push(change);
+            }
+        }
+        Ok(changes)
+    }
+
+    fn apply_one(&self, root: &mut Board, id: Uuid, snooze: Option<Option<NaiveDate>>, due: Option<Option<DateTime<Utc>>>, remind: Option<Option<DateTime<Utc>>>, today: NaiveDate) -> Result<Option<Change>> {
+        let path = root.path_to(id).ok_or_else(|| anyhow!("task no longer exists"))?;
+        let task = root.task_at_mut(&path).ok_or_else(|| anyhow!("invalid task path"))?;
+        let mut change = Change { title: task.title.clone(), what: Vec::new() };
+
+        for tag in &self.add_tags {
+            let tag = tag.trim_start_matches('#');
+            if !tag.is_empty() && !autocomplete::tags_in(&task.title).any(|t| collate::eq(t, tag)) {
+                task.title = format!("{} #{}", task.title.trim_end(), tag);
+                change.what.push(format!("+#{}", tag));
+            }
+        }
+        for tag in &self.remove_tags {
+            let tag = tag.trim_start_matches('#');
+            let is_tag = |w: &str| w.strip_prefix('#').is_some_and(|t| collate::eq(t, tag));
+            if autocomplete::tags_in(&task.title).any(|t| collate::eq(t, tag)) {
+                task.title = task.title.split_whitespace().filter(|w| !is_tag(w)).collect::<Vec<_>>().join(" ");
+                change.what.push(format!("-#{}", tag));
+            }
+        }
+        if let Some(until) = snooze
+            && task.snoozed_until != until {
+                task.snoozed_until = until;
+                change.what.push(match until {
+                    Some(day) => format!("snoozed until {}", day),
+                    None => "unsnoozed".to_string(),
+                });
+            }
+        if let Some(due) = due
+            && task.due != due {
+                task.due = due;
+                change.what.push(match due {
+                    Some(at) => format!("due {}", zone::format_due(at, "%Y-%m-%d")),
+                    None => "no due date".to_string(),
+                });
+            }
+        match remind {
+            Some(Some(at)) if !task.reminders.contains(&at) => {
+                task.reminders.push(at);
+                task.reminders.sort();
+                change.what.push(format!("reminder {}", zone::format_due(at, "%Y-%m-%d")));
+            }
+            Some(None) if !task.reminders.is_empty() => {
+                task.reminders.clear();
+                change.what.push("no reminders".to_string());
+            }
+            _ => {}
+        }
+        match self.wait.as_deref().map(str::trim) {
+            Some("off") if task.waiting_on.is_some() => {
+                task.waiting_on = None;
+                change.what.push("no longer waiting".to_string());
+            }
+            Some(who) if who != "off" && task.waiting_on.as_ref().is_none_or(|w| w.who != who) => {
+                task.waiting_on = Some(WaitingOn { who: who.to_string(), since: today });
+                change.what.push(format!("waiting on {}", who));
+            }
+            _ => {}
+        }
+
+        if let Some(column) = &self.move_to {
+            let (&(c, _), parent) = path.split_last().ok_or_else(|| anyhow!("invalid task path"))?;
+            let board = root.board_at(parent).ok_or_else(|| anyhow!("invalid task path"))?;
+            let target = board.match_column(column)?;
+            if target != c {
+                let name = board.columns[target].title.clone();
+                cli::move_task(root, &path, column, self.reason.as_deref())?;
+                change.what.push(format!("moved to {}", name));
+            }
+        }
+
+        Ok(Some(change).filter(|c| !c.what.is_empty()))
+    }
+}
+
+// None when the option wasn't given, Some(None) for `off`
+fn read_date<T>(arg: Option<&str>, parse: impl Fn(&str) -> Option<T>) -> Result<Option<Option<T>>> {
+    match arg.map(str::trim) {
+        None => Ok(None),
+        Some("off") => Ok(Some(None)),
+        Some(when) => parse(when).map(|d| Some(Some(d))).ok_or_else(|| anyhow!("can't read date '{}'", when)),
+    }
+}
@@ -0,0 +1,754 @@
+use crate::backup;
+use crate::batch;
+use crate::bulk;
+use crate::collate;
+use crate::config::{Config, LintConfig};
+use crate::date;
+use crate::export;
+use crate::github;
+use crate::ics;
+use crate::import;
+use crate::jira;
+use crate::stress;
+use crate::link;
+use crate::lint;
+use crate::mapping;
+use crate::planner;
+use crate::model::{self, Board, Task, TaskContent};
+use crate::reminder;
+use crate::storage::{self, DB_FILE};
+use crate::search;
+use crate::sort;
+use crate::stats;
+use crate::template;
+use crate::todotxt;
+use crate::zone;
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{TimeDelta, Utc};
+use clap::{ArgGroup, Args, Parser, Subcommand, ValueEnum};
+use ratatui::layout::Size;
+use serde::Serialize;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Instant;
+use uuid::Uuid;
+
+#[derive(Parser)]
+#[command(name = "kanban", version, about = "Hierarchical Kanban board for your terminal", args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+    /// Open a sample board kept in memory and play a short tour of it; nothing is read or saved
+    #[arg(long)]
+    pub demo: bool,
+    /// Write one screen to this file (`-` for stdout) as text instead of starting the TUI; with --demo, the one the tour ends on
+    #[arg(long, value_name = "FILE")]
+    pub frame: Option<PathBuf>,
+    /// Draw without color, showing state through markers, bold and underline ([ui] monochrome)
+    #[arg(long)]
+    pub no_color: bool,
+    /// Screen size for --frame
+    #[arg(long, value_name = "COLSxROWS", default_value = "120x32", value_parser = parse_size, requires = "frame")]
+    pub size: Size,
+}
+
+fn parse_size(s: &str) -> Result<Size, String> {
+    let (width, height) = s.split_once('x').ok_or("expected COLSxROWS, e.g. 100x30")?;
+    let (width, height) = (width.parse().map_err(|_| "bad column count")?, height.parse().map_err(|_| "bad row count")?);
+    if width < 20 || height < 10 {
+        return Err("at least 20x10".to_string());
+    }
+    Ok(Size { width, height })
+}
+
+// Headless subcommands; without one the TUI starts
+#[derive(Subcommand)]
+pub enum CliCommand {
+    /// Add a task to the main board
+    Add {
+        title: String,
+        /// Target column; fuzzy matched ("prog" -> "In Progress"). Defaults to the first column
+        #[arg(short, long)]
+        column: Option<String>,
+    },
+    /// Move a task to another column of the board it lives on
+    Move {
+        /// Task id (or unique id prefix) or exact title
+        task: String,
+        /// Target column; fuzzy matched
+        column: String,
+        /// Why the task is blocked; required by columns that ask for one
+        #[arg(short, long)]
+        reason: Option<String>,
+    },
+    /// Apply a YAML list of operations; nothing is saved unless all of them succeed
+    Apply {
+        file: PathBuf,
+        /// Print what each operation would do without saving
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Re-read every calendar feed columns subscribe to (`:subscribe` in the TUI)
+    Refresh,
+    /// Push task moves to the `[github]` repository's issues and pull its open issues in
+    Sync,
+    /// Fire reminders that came due: print each one once, e.g. from cron
+    Notify {
+        /// Also run this program for each, with the task title and its board as arguments (e.g. notify-send)
+        #[arg(long)]
+        command: Option<String>,
+    },
+    /// Create a new board file, optionally from a YAML/TOML template
+    Init {
+        #[arg(short, long)]
+        template: Option<PathBuf>,
+        /// Replace an existing board file
+        #[arg(long)]
+        force: bool,
+    },
+    /// Generate a large synthetic board for stress testing
+    Gen {
+        /// Total number of tasks, sub-board cards included
+        #[arg(long, default_value_t = 1000)]
+        tasks: usize,
+        /// How many boards deep the tree goes; 1 is a single flat board
+        #[arg(long, default_value_t = 3)]
+        depth: usize,
+        /// Same seed, same board
+        #[arg(long, default_value_t = 1)]
+        seed: u64,
+        /// Board file to write
+        #[arg(short, long, default_value = DB_FILE)]
+        output: PathBuf,
+        /// Replace an existing board file
+        #[arg(long)]
+        force: bool,
+    },
+    /// Search task titles, descriptions, notes and todo items (case-insensitive)
+    Search {
+        query: String,
+        /// Search every board file (*.db) in the workspace directory, not just kanban.db
+        #[arg(long)]
+        all_boards: bool,
+        /// Workspace directory
+        #[arg(long, default_value = ".")]
+        dir: PathBuf,
+        /// One JSON array of matches instead of text lines
+        #[arg(long)]
+        json: bool,
+        #[command(flatten)]
+        filter: ColumnFilter,
+    },
+    /// Apply one edit to every task matching a search; prints a preview unless --yes
+    Edit {
+        /// Text to search for, as in `search`; every task when left out
+        query: Option<String>,
+        #[command(flatten)]
+        filter: ColumnFilter,
+        #[command(flatten)]
+        edit: bulk::BulkEdit,
+        /// Save the changes instead of only listing them
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// List every task in the tree
+    List {
+        /// `picker` prints `id<TAB>breadcrumb<TAB>title` lines for fzf/rofi
+        #[arg(long, value_enum, default_value_t = ListFormat::Text)]
+        format: ListFormat,
+        #[command(flatten)]
+        filter: ColumnFilter,
+    },
+    /// Write the tree, or one board of it, to a file for sharing
+    Export {
+        /// Output file; `-` writes to stdout
+        file: PathBuf,
+        #[arg(long, value_enum, default_value_t = ExportFormat::Md)]
+        format: ExportFormat,
+        /// Export only this sub-board (id, id prefix or exact title of the task holding it), or with todotxt a todo list
+        #[arg(long, visible_alias = "filter")]
+        board: Option<String>,
+        /// Follow each task with its kanban:// link
+        #[arg(long)]
+        links: bool,
+        /// With planner, the week holding this date ("next week", "2026-11-02"); this week by default
+        #[arg(long)]
+        week: Option<String>,
+        /// Replace an existing file
+        #[arg(long)]
+        force: bool,
+        #[command(flatten)]
+        filter: ColumnFilter,
+    },
+    /// Add a Markdown checklist (headings and `- [ ]` items) or a todo.txt file (`.txt`) as a sub-board card on the main board; importing a file again updates its card
+    Import {
+        file: PathBuf,
+        /// Column for the card; fuzzy matched. Defaults to the first column
+        #[arg(short, long)]
+        column: Option<String>,
+        /// Make a todo.txt file a todo list card instead of a board
+        #[arg(long)]
+        list: bool,
+        /// Print whether the card would be added or updated without saving
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Import a Jira CSV export into the main board; statuses without a column are asked about once
+    ImportJira {
+        file: PathBuf,
+        /// Print what would be added or updated without saving
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Tasks per column, throughput, cycle time and overdue tasks of every board in the tree
+    #[command(group(ArgGroup::new("stats_format").args(["json", "csv"])))]
+    Stats {
+        /// Count tasks done and moved over this many days up to now
+        #[arg(long, default_value_t = 7)]
+        days: u64,
+        /// One JSON object with totals and each board, e.g. for Grafana
+        #[arg(long)]
+        json: bool,
+        /// A row per column of every board, for spreadsheets
+        #[arg(long)]
+        csv: bool,
+    },
+    /// Check every board of the tree against the `[lint]` policy; fails when anything breaks it, e.g. in CI
+    Lint {
+        /// Read the policy from this TOML file, with the keys of `[lint]`, instead of the config
+        #[arg(long)]
+        rules: Option<PathBuf>,
+    },
+    /// Start the TUI with the cursor on a task
+    #[command(group(ArgGroup::new("target").required(true).args(["link", "id"])))]
+    Open {
+        /// A kanban://board/column/short-id link, as printed by `list --format links`
+        link: Option<String>,
+        /// Task id (or unique id prefix) or exact title
+        #[arg(long)]
+        id: Option<String>,
+    },
+}
+
+// Column subsets for output commands. Names are compared case-insensitively on
+// every board of the tree; tasks in a left-out column take their sub-boards with them.
+#[derive(Args)]
+pub struct ColumnFilter {
+    /// Only include these columns (comma-separated)
+    #[arg(long, value_delimiter = ',')]
+    columns: Vec<String>,
+    /// Leave out these columns, e.g. --exclude done,icebox
+    #[arg(long, value_delimiter = ',')]
+    exclude: Vec<String>,
+}
+
+impl ColumnFilter {
+    fn keeps(&self, title: &str) -> bool {
+        let named = |names: &[String]| names.iter().any(|n| collate::eq(n.trim(), title));
+        (self.columns.is_empty() || named(&self.columns)) && !named(&self.exclude)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.columns.is_empty() && self.exclude.is_empty()
+    }
+
+    /// A copy of `board` with only the kept columns, at every level.
+    pub fn apply(&self, board: &Board) -> Board {
+        let mut board = board.clone();
+        if !self.is_empty() {
+            self.prune(&mut board);
+        }
+        board
+    }
+
+    fn prune(&self, board: &mut Board) {
+        board.columns.retain(|c| self.keeps(&c.title));
+        for task in board.columns.iter_mut().flat_map(|c| c.tasks.iter_mut()) {
+            if let Some(TaskContent::Board(ref mut sub)) = task.content {
+                self.prune(sub);
+            }
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+pub enum ListFormat {
+    Text,
+    Picker,
+    Links, // One kanban:// link per task
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+pub enum ExportFormat {
+    Md, // Nested headings, list items and checkboxes
+    Todotxt, // One line per task of the board, or per item with --board naming a todo list
+    Ics, // A VTODO per task with a due date, for calendar apps
+    Csv, // A row per task of the whole tree, for spreadsheets
+    Planner, // A page per week: what is due each day, to print or paste into notes
+}
+
+#[derive(Serialize)]
+struct FileMatch {
+    file: String,
+    #[serde(flatten)]
+    found: search::Match,
+}
+
+pub fn run(cmd: CliCommand, config: &Config) -> Result<()> {
+    let path = config.board.file.as_path();
+
+    if let CliCommand::Init { template, force } = cmd {
+        if path.exists() && !force {
+            bail!("{} already exists (use --force to replace it)", path.display());
+        }
+        let _lock = storage::lock(path)?;
+        let root = match template {
+            Some(file) => template::load(&file)?.build()?,
+            None => Board::default(),
+        };
+        return storage::save(path, &root);
+    }
+
+    if let CliCommand::Gen { tasks, depth, seed, output, force } = cmd {
+        if output.exists() && !force {
+            bail!("{} already exists (use --force to replace it)", output.display());
+        }
+        let started = Instant::now();
+        let (root, stats) = stress::generate(tasks, depth, seed);
+        let built = started.elapsed();
+        storage::save(&output, &root)?;
+        println!(
+            "{} tasks ({} sub-boards, {} todo items) in {:.0?}, saved in {:.0?}: {} ({} KiB)",
+            stats.tasks, stats.boards, stats.todo_items, built, started.elapsed() - built,
+            output.display(), fs::metadata(&output)?.len() / 1024,
+        );
+        return Ok(());
+    }
+
+    // Read-only commands
+    if let CliCommand::Search { query, all_boards, dir, json, filter } = cmd {
+        return search_workspace(&query, all_boards, &dir, &config.board.file, json, &filter);
+    }
+
+    if let CliCommand::Notify { command } = cmd {
+        return notify(path, command.as_deref());
+    }
+
+    if let CliCommand::Stats { days, json, csv } = cmd {
+        return print_stats(path, days, json, csv);
+    }
+
+    if let CliCommand::Lint { rules } = cmd {
+        let policy = match rules {
+            Some(file) => toml::from_str(&fs::read_to_string(&file)?).with_context(|| format!("invalid rules {}", file.display()))?,
+            None => config.lint.clone(),
+        };
+        return lint_board(path, &policy);
+    }
+
+    if let CliCommand::List { format, filter } = cmd {
+        list_tasks(&filter.apply(&storage::load(path)?), format);
+        return Ok(());
+    }
+
+    if let CliCommand::Export { file, format, board, links, week, force, filter } = cmd {
+        let root = storage::load(path)?;
+        if let (ExportFormat::Todotxt, Some(query)) = (format, &board)
+            && let Some(TaskContent::Todo(items)) = resolve_task(&root, query).ok().and_then(|p| root.task_at(&p)).and_then(|t| t.content.as_ref()) {
+                return write_export(&file, &todotxt::render_items(items), force);
+            }
+        let path = match board {
+            Some(query) => resolve_board(&root, &query)?,
+            None => Vec::new(),
+        };
+        let scope = filter.apply(board_at(&root, &path)?);
+        let text = match format {
+            ExportFormat::Md => export::Markdown { links }.render(&scope, &root.breadcrumbs(&path)),
+            ExportFormat::Todotxt => todotxt::render_board(&scope),
+            ExportFormat::Ics => ics::render(&scope, &root.breadcrumbs(&path)),
+            ExportFormat::Csv => export::csv(&scope, &root.breadcrumbs(&path)),
+            ExportFormat::Planner => {
+                let today = zone::today();
+                let day = match week {
+                    Some(when) => date::parse(&when, today).ok_or_else(|| anyhow!("--week: '{}' is not a date (2026-11-02, next week, in 2 weeks)", when))?,
+                    None => today,
+                };
+                planner::render(&scope, &root.breadcrumbs(&path), date::monday(day))
+            }
+        };
+        return write_export(&file, &text, force);
+    }
+
+    // Asking where unknown columns go and talking to servers come first, so
+    // neither holds the lock while it waits; what they got is applied to the
+    // board as it is once locked
+    let prepared = match &cmd {
+        CliCommand::Apply { file, dry_run } => {
+            let ops = batch::parse(&fs::read_to_string(file)?)?;
+            // Operations without a source of their own map columns under the file's name
+            let source = file.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+            let Some(ops) = map_operations(path, ops, &source, *dry_run)? else { return Ok(()) };
+            Prepared::Operations(ops)
+        }
+        CliCommand::ImportJira { file, dry_run } => {
+            let ops = jira::operations(&fs::read_to_string(file)?)?;
+            let Some(ops) = map_operations(path, ops, jira::SYSTEM, *dry_run)? else { return Ok(()) };
+            Prepared::Operations(ops)
+        }
+        CliCommand::Sync => Prepared::Issues(github::fetch(&storage::load(path)?, &config.github, path)?),
+        CliCommand::Refresh => Prepared::Feeds(ics::fetch_all(&storage::load(path)?)),
+        _ => Prepared::Nothing,
+    };
+
+    // Held until the changes are saved; an open TUI picks them up from there
+    let _lock = storage::lock(path)?;
+    let mut root = storage::load(path)?;
+    let loaded = root.clone(); // To refuse changes to frozen columns and boards, and subscribed tasks
+    match cmd {
+        CliCommand::Add { title, column } => {
+            let id = add_task(&mut root, &[], &title, "", column.as_deref())?;
+            println!("{}", id);
+        }
+        CliCommand::Move { task, column, reason } => {
+            let task_path = resolve_task(&root, &task)?;
+            move_task(&mut root, &task_path, &column, reason.as_deref())?;
+        }
+        CliCommand::Edit { query, filter, edit, yes } => {
+            // Match on the filtered copy, edit the real tree by id
+            let scope = filter.apply(&root);
+            let ids: Vec<Uuid> = match query {
+                Some(query) => search::search(&scope, &query).into_iter().map(|m| m.id).collect(),
+                None => {
+                    let mut ids = Vec::new();
+                    search::walk_tasks(&scope, &mut |_, _, _, task| ids.push(task.id));
+                    ids
+                }
+            };
+            let changes = edit.apply(&mut root, &ids, zone::today())?;
+            for change in &changes {
+                println!("{}: {}", change.title, change.what.join(", "));
+            }
+            if !yes {
+                println!("{} of {} matching tasks would change (run again with --yes to save)", changes.len(), ids.len());
+                return Ok(());
+            }
+            println!("changed {} of {} matching tasks", changes.len(), ids.len());
+        }
+        CliCommand::Apply { .. } | CliCommand::ImportJira { .. } => {
+            let Prepared::Operations(ops) = prepared else { unreachable!("mapped above") };
+            root = batch::apply(&root, &ops)?.0;
+            println!("applied {} operations", ops.len());
+        }
+        CliCommand::Import { file, column, list, dry_run } => {
+            let (title, content) = import::card(&file, list)?;
+            let c = match column {
+                Some(name) => root.match_column(&name)?,
+                None => 0,
+            };
+            if root.columns.is_empty() {
+                bail!("board has no columns");
+            }
+            let summary = import::summary(&content);
+            let (id, updated) = import::place(&mut root, c, import::source(&file), &title, content);
+            let what = if updated { "updated" } else { "added" };
+            if dry_run {
+                println!("{}: {} would be {}; nothing saved (drop --dry-run to import it)", title, summary, what);
+                return Ok(());
+            }
+            println!("{}: {}, {}", title, summary, what);
+            println!("{}", id);
+        }
+        CliCommand::Sync => {
+            let Prepared::Issues(fetched) = prepared else { unreachable!("fetched above") };
+            let s = github::apply(&mut root, &config.github, path, fetched)?;
+            println!("{} added, {} updated, {} closed on GitHub, {} moves pushed", s.added, s.updated, s.closed, s.pushed);
+        }
+        CliCommand::Refresh => {
+            let Prepared::Feeds(fetched) = prepared else { unreachable!("fetched above") };
+            let results = ics::apply_all(&mut root, fetched);
+            if results.is_empty() {
+                println!("no column subscribes to a calendar");
+                return Ok(());
+            }
+            for (column, outcome) in results {
+                match outcome {
+                    Ok(r) => println!("{}: {} added, {} updated, {} removed", column, r.added, r.updated, r.removed),
+                    Err(e) => eprintln!("{}: {:#}", column, e),
+                }
+            }
+        }
+        CliCommand::Init { .. } | CliCommand::Gen { .. } | CliCommand::Search { .. } | CliCommand::List { .. } | CliCommand::Export { .. } | CliCommand::Notify { .. } | CliCommand::Stats { .. } | CliCommand::Lint { .. } => unreachable!("handled above"),
+        CliCommand::Open { .. } => unreachable!("opens the TUI; handled in main"),
+    }
+
+    sort::apply(&mut root);
+    if let Some(what) = model::frozen_change(&loaded, &root) {
+        bail!("{} is frozen; unfreeze it with :freeze in the TUI first", what);
+    }
+    if let Some(title) = ics::read_only_change(&loaded, &root) {
+        bail!("'{}' comes from a calendar; it changes on refresh", title);
+    }
+    storage::save_active(path, &root)?;
+    if let Err(e) = backup::run(path, &config.backup, zone::local(Utc::now())) {
+        eprintln!("backup failed: {:#}", e);
+    }
+    Ok(())
+}
+
+// Nothing is saved to the board file, so this is safe while the TUI is open
+fn notify(path: &Path, command: Option<&str>) -> Result<()> {
+    let workspaces = storage::load_workspaces(path)?;
+    let due = reminder::due(path, &workspaces.boards.iter().collect::<Vec<_>>(), Utc::now());
+    for r in &due {
+        println!("⏰ {} {} ({})", zone::format_due(r.at, "%Y-%m-%d"), r.title, r.board);
+        if let Some(program) = command {
+            let status = Command::new(program).arg(&r.title).arg(&r.board).status()?;
+            if !status.success() {
+                eprintln!("{} exited with {}", program, status);
+            }
+        }
+    }
+    reminder::record(path, &due)
+}
+
+fn write_export(file: &Path, text: &str, force: bool) -> Result<()> {
+    if file == Path::new("-") {
+        print!("{}", text);
+        return Ok(());
+    }
+    if file.exists() && !force {
+        bail!("{} already exists (use --force to replace it)", file.display());
+    }
+    fs::write(file, text)?;
+    Ok(())
+}
+
+// What `run` got ready for a command before taking the lock
+enum Prepared {
+    Nothing,
+    Operations(Vec<batch::Operation>), // With unknown columns mapped
+    Issues(github::Fetched),
+    Feeds(Vec<ics::Fetched>),
+}
+
+// Maps unknown columns of `ops` against the board file as it is, asking on the
+// terminal; None for a dry run, which only prints what they would do
+fn map_operations(path: &Path, ops: Vec<batch::Operation>, source: &str, dry_run: bool) -> Result<Option<Vec<batch::Operation>>> {
+    let root = storage::load(path)?;
+    let mut mappings = mapping::Mappings::load(path)?;
+    let ops = mapping::map_columns(&root, ops, source, &mut mappings, &mut ask_column)?;
+    if dry_run {
+        for line in &batch::apply(&root, &ops)?.1 {
+            println!("{}", line);
+        }
+        println!("{} operations would apply; nothing saved (drop --dry-run to apply them)", ops.len());
+        return Ok(None);
+    }
+    // Answers from a dry run are asked again on the real one
+    mappings.save(path)?;
+    Ok(Some(ops))
+}
+
+// Asks on the terminal where tasks of an unknown list go
+fn ask_column(source: &str, list: &str, board: &str, columns: &[String]) -> Result<mapping::Choice> {
+    if !io::stdin().is_terminal() {
+        bail!("'{}' ({}) matches no column of '{}' (columns: {}); run this in a terminal to map it", list, source, board, columns.join(", "));
+    }
+    eprintln!("'{}' from {} has no column on '{}'. Put its tasks in:", list, source, board);
+    for (i, column) in columns.iter().enumerate() {
+        eprintln!("  {}) {}", i + 1, column);
+    }
+    eprintln!("  n) a new column '{}'", list);
+    loop {
+        eprint!("> ");
+        let mut answer = String::new();
+        if io::stdin().read_line(&mut answer)? == 0 {
+            bail!("no column chosen for '{}'", list);
+        }
+        match answer.trim() {
+            "n" | "N" => return Ok(mapping::Choice::Create),
+            n => match n.parse::<usize>().ok().and_then(|i| columns.get(i.wrapping_sub(1))) {
+                Some(column) => return Ok(mapping::Choice::Column(column.clone())),
+                None => eprintln!("pick 1-{} or n", columns.len()),
+            },
+        }
+    }
+}
+
+// Read-only like `notify`, so it can run while the TUI is open
+fn print_stats(path: &Path, days: u64, json: bool, csv: bool) -> Result<()> {
+    let root = storage::load(path)?;
+    let now = Utc::now();
+    let Some(since) = i64::try_from(days).ok().and_then(TimeDelta::try_days).and_then(|d| now.checked_sub_signed(d)) else {
+        bail!("--days {} reaches back too far", days);
+    };
+    let stats = stats::workspace(&root, since, now);
+    if json {
+        println!("{}", stats.json()?);
+        return Ok(());
+    }
+    if csv {
+        print!("{}", stats.csv());
+        return Ok(());
+    }
+    let cycle = |c: stats::CycleTime| match (c.average, c.median) {
+        (Some(average), Some(median)) => format!(", cycle time {:.1} days (median {:.1})", average, median),
+        _ => String::new(),
+    };
+    println!("Last {} days: {} open, {} done, {} overdue{}", days, stats.open(), stats.done(), stats.overdue(), cycle(stats.cycle_days()));
+    for board in &stats.boards {
+        let indent = "  ".repeat(board.depth());
+        println!("{}{}: {} open, {} done, {} overdue{}", indent, board.title(), board.open, board.done, board.overdue, cycle(board.cycle_days));
+        let columns: Vec<String> = board.columns.iter().map(|c| match c.overdue {
+            0 => format!("{} {}", c.title, c.tasks),
+            n => format!("{} {} ({} overdue)", c.title, c.tasks, n),
+        }).collect();
+        println!("{}  {}", indent, columns.join(" · "));
+    }
+    Ok(())
+}
+
+fn lint_board(path: &Path, policy: &LintConfig) -> Result<()> {
+    let problems = lint::check(&storage::load(path)?, policy, Utc::now());
+    for problem in &problems {
+        println!("{}", problem);
+    }
+    match problems.len() {
+        0 => Ok(()),
+        1 => bail!("1 problem"),
+        n => bail!("{} problems", n),
+    }
+}
+
+fn search_workspace(query: &str, all_boards: bool, dir: &Path, file: &Path, json: bool, filter: &ColumnFilter) -> Result<()> {
+    let files = if all_boards {
+        let mut files: Vec<PathBuf> = fs::read_dir(dir)?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|e| e == "db"))
+            .collect();
+        files.sort();
+        files
+    } else {
+        vec![dir.join(file)]
+    };
+
+    let mut results = Vec::new();
+    for file in files {
+        match storage::load_workspaces(&file) {
+            // Breadcrumbs start with the workspace name
+            Ok(workspaces) => for board in &workspaces.boards {
+                results.extend(search::search(&filter.apply(board), query).into_iter().map(|found| FileMatch {
+                    file: file.display().to_string(),
+                    found,
+                }));
+            },
+            // Other .db files may live in the workspace; skip what isn't a board
+            Err(e) => eprintln!("skipping {}: {:#}", file.display(), e),
+        }
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&results)?);
+    } else {
+        for r in &results {
+            let m = &r.found;
+            println!("{}: {} > [{}] {} ({}, {})", r.file, m.breadcrumbs.join(" > "), m.column, m.title, &m.id.to_string()[..8], m.field);
+        }
+    }
+    Ok(())
+}
+
+fn list_tasks(root: &Board, format: ListFormat) {
+    search::walk_tasks(root, &mut |crumbs, _, column, task| {
+        let column = column.title.as_str();
+        match format {
+            ListFormat::Text => println!("{} > [{}] {} ({})", crumbs.join(" > "), column, task.title, &task.id.to_string()[..8]),
+            // Tabs separate the fields, so keep them out of the values
+            ListFormat::Picker => {
+                let clean = |s: &str| s.replace('\t', " ");
+                println!("{}\t{} > {}\t{}", task.id, clean(&crumbs.join(" > ")), clean(column), clean(&task.title));
+            }
+            ListFormat::Links => println!("{}\t{}", link::format(crumbs, column, task.id), task.title),
+        }
+    });
+}
+
+/// Finds a single task by id, unique id prefix or title (ignoring case and accents).
+pub fn resolve_task(root: &Board, query: &str) -> Result<Vec<(usize, usize)>> {
+    let q = query.to_lowercase();
+    let mut found = root.find_tasks(&|t| t.id.to_string().starts_with(&q));
+    if found.is_empty() || q.len() < 4 {
+        let title = collate::fold(query);
+        found = root.find_tasks(&|t| collate::fold(&t.title) == title);
+    }
+    match found.len() {
+        0 => bail!("no task matches '{}'", query),
+        1 => Ok(found.remove(0)),
+        n => bail!("'{}' matches {} tasks; use a longer id", query, n),
+    }
+}
+
+/// Like `resolve_task`, but the task must hold a board; returns the path into it.
+pub fn resolve_board(root: &Board, query: &str) -> Result<Vec<(usize, usize)>> {
+    let path = resolve_task(root, query)?;
+    let (&(c, r), parent) = path.split_last().ok_or_else(|| anyhow!("invalid task path"))?;
+    let parent = board_at(root, parent)?;
+    match parent.columns[c].tasks[r].content {
+        Some(TaskContent::Board(_)) => Ok(path),
+        _ => bail!("'{}' is not a board", query),
+    }
+}
+
+fn board_at<'a>(root: &'a Board, path: &[(usize, usize)]) -> Result<&'a Board> {
+    let mut board = root;
+    for &(c, r) in path {
+        match board.columns.get(c).and_then(|col| col.tasks.get(r)).and_then(|t| t.content.as_ref()) {
+            Some(TaskContent::Board(sub)) => board = sub,
+            _ => bail!("invalid board path"),
+        }
+    }
+    Ok(board)
+}
+
+pub fn add_task(root: &mut Board, board_path: &[(usize, usize)], title: &str, description: &str, column: Option<&str>) -> Result<Uuid> {
+    let board = root.board_at_mut(board_path).ok_or_else(|| anyhow!("invalid board path"))?;
+    let c = match column {
+        Some(name) => board.match_column(name)?,
+        None => 0,
+    };
+    let col = board.columns.get_mut(c).ok_or_else(|| anyhow!("board has no columns"))?;
+    let mut task = Task::new(title, description);
+    if let Some(defaults) = &col.defaults {
+        defaults.apply(&mut task, zone::today());
+    }
+    let id = task.id;
+    col.tasks.push(task);
+    Ok(id)
+}
+
+pub fn move_task(root: &mut Board, task_path: &[(usize, usize)], column: &str, reason: Option<&str>) -> Result<()> {
+    let (&(c, r), parent_path) = task_path.split_last().ok_or_else(|| anyhow!("invalid task path"))?;
+    let board = root.board_at_mut(parent_path).ok_or_else(|| anyhow!("invalid task path"))?;
+    let new_c = board.match_column(column)?;
+    let reason = reason.map(str::trim).filter(|r| !r.is_empty());
+    let target = &board.columns[new_c];
+    if target.requires_reason && reason.is_none() && new_c != c {
+        bail!("'{}' requires a reason (--reason)", target.title);
+    }
+
+    let r = if new_c != c {
+        let mut task = board.columns[c].tasks.remove(r);
+        task.enter_column();
+        board.columns[new_c].tasks.push(task);
+        board.columns[new_c].tasks.len() - 1
+    } else {
+        r
+    };
+    // Moving a blocked task onto its own column updates the reason
+    if board.columns[new_c].requires_reason && let Some(reason) = reason {
+        board.columns[new_c].tasks[r].blocked_reason = Some(reason.to_string());
+    }
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use icu_normalizer::DecomposingNormalizerBorrowed;
+use std::cmp::Ordering;
+
+// How titles, column names and tags are compared wherever the user types a name
+// or a query: without regard to case or accents, so "concluido" finds
+// "Concluído", "acao" matches a column "Ação", and "Ágil" sorts with the A's.
+
+/// `s` in lowercase with accents and other combining marks dropped.
+pub fn fold(s: &str) -> String {
+    let nfd = DecomposingNormalizerBorrowed::new_nfd();
+    nfd.normalize_iter(s.chars())
+        .filter(|c| !is_combining(*c))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+pub fn eq(a: &str, b: &str) -> bool {
+    fold(a) == fold(b)
+}
+
+/// Alphabetical order of folded text; exact text breaks ties so the order is stable.
+pub fn cmp(a: &str, b: &str) -> Ordering {
+    fold(a).cmp(&fold(b)).then_with(|| a.cmp(b))
+}
+
+// Combining diacritical marks, their extensions and supplement, and the half marks
+fn is_combining(c: char) -> bool {
+    matches!(c, '\u{300}'..='\u{36F}' | '\u{1AB0}'..='\u{1AFF}' | '\u{1DC0}'..='\u{1DFF}' | '\u{20D0}'..='\u{20FF}' | '\u{FE20}'..='\u{FE2F}')
+}
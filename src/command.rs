@@ -0,0 +1,289 @@
+use crate::collate;
+use crate::storage;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const HISTORY_FILE: &str = "history"; // Beside the board file, see `storage::sibling`
+const HISTORY_LIMIT: usize = 200;
+
+// Command names with what they do, for the hint popup; kept sorted so
+// completion candidates come out in order
+pub const COMMANDS: &[(&str, &str)] = &[
+    ("add", "Add a task or todo item"),
+    ("agenda", "Show/hide the week-ahead strip"),
+    ("archive", "Archive the selected task ('done': every task in the last column)"),
+    ("archived", "Show this board's archived tasks"),
+    ("back", "Go up one board"),
+    ("blocked", "Ask for a reason when tasks enter this column"),
+    ("checklist", "Every open todo item in the tree"),
+    ("column", "Add a column"),
+    ("defaults", "Tags and due date for new tasks in this column (off clears)"),
+    ("delete", "Delete the selected task"),
+    ("due", "Set the due date and optional time (off clears)"),
+    ("duplicate", "Copy this board (reset, empty)"),
+    ("export", "Write this board to a Markdown file (also .txt, .ics, .csv)"),
+    ("find", "Search every board in the tree"),
+    ("freeze", "Make this column (or with 'board', this board) read-only, or writable again"),
+    ("goto", "Select a column"),
+    ("import", "Add a Markdown or todo.txt file as a sub-board card"),
+    ("help", "Show all keys"),
+    ("icebox", "Mark this column as the icebox"),
+    ("lane", "Put the task in a swimlane (off clears)"),
+    ("lanes", "Group this board into swimlanes: assignee, tag [prefix], field, off"),
+    ("move", "Move the task to a column"),
+    ("open", "Open a sub-board"),
+    ("quit", "Quit"),
+    ("refresh", "Re-read calendar subscriptions"),
+    ("reload", "Load the board file again, dropping unsaved changes"),
+    ("remind", "Add a reminder at a time (off clears)"),
+    ("replace", "Search and replace /old/new/"),
+    ("reset", "Clear this column daily or weekly"),
+    ("snooze", "Hide the task until a date"),
+    ("snoozed", "Show or hide snoozed tasks"),
+    ("sort", "Keep this column sorted (off: manual)"),
+    ("subscribe", "Mirror a calendar feed in this column"),
+    ("sync", "Sync issues with the [github] repository"),
+    ("thaw", "Move the task out of the icebox"),
+    ("trash", "Show this board's deleted tasks and columns, to restore them"),
+    ("unsnooze", "Show the task again"),
+    ("wait", "Mark the task waiting on someone"),
+    ("waiting", "Show only waiting tasks"),
+    ("wide", "Wrap long card titles onto two lines on this board, or cut them off again"),
+    ("workspace", "Switch to (or create) a workspace"),
+    ("write", "Save now (write! over changes made elsewhere)"),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Add(String),
+    Column(String),
+    Move(String),
+    Goto(String),
+    Open(String),
+    Reset(String),
+    Blocked,
+    Freeze { board: bool }, // The board in view rather than the selected column
+    Checklist,
+    Icebox,
+    Archive { done: bool }, // Every task in the last column rather than the selected one
+    Archived,
+    Trash,
+    Thaw(Option<String>), // Target column; the first one outside the icebox by default
+    Snooze(String),
+    Sort(String), // Key and direction for the selected column, or "off" for manual order
+    Defaults(Option<String>), // New-task defaults for the selected column; the popup without them
+    Subscribe(String), // iCalendar feed URL or path for the selected column; "off" stops
+    Refresh,
+    Sync, // Issues of the `[github]` repository, see github.rs
+    Due(String), // Date and optional time as `date::parse_due` reads them; "off" clears
+    Unsnooze,
+    ShowSnoozed,
+    Wait(String), // Who the task is waiting on; "off" clears
+    Agenda,
+    Remind(String), // Time as `reminder::parse` reads it; "off" clears all
+    Replace(String), // `/old/new/[t]`, parsed by `replace::parse`
+    Waiting,
+    Wide, // Toggles `wide_cards` on the board in view
+    Lanes(Option<String>), // How to group the board in view into swimlanes, see `Lanes::parse`; says which without one
+    Lane(String), // The selected task's lane field; "off" clears
+    Duplicate { reset_done: bool, strip_tasks: bool },
+    Delete,
+    Back,
+    Help,
+    Find(String),
+    Export(String), // File to write the board in view to, as Markdown, (`.txt`) todo.txt, (`.ics`) iCalendar or CSV
+    Import(String), // Markdown or todo.txt file to add as a card in the selected column
+    Workspace(Option<String>), // Name to switch to or create; the switcher without one
+    Quit,
+    Reload, // The board file as another process saved it
+    Write { force: bool }, // Forced, saves over changes another process made
+}
+
+pub fn parse(line: &str) -> Result<Command, String> {
+    let line = line.trim();
+    // Vim-style `s/old/new/` needs no space after the name
+    if let Some(rest) = line.strip_prefix('s')
+        && rest.starts_with(|c: char| !c.is_alphanumeric() && !c.is_whitespace())
+    {
+        return Ok(Command::Replace(rest.to_string()));
+    }
+    let (name, arg) = match line.split_once(' ') {
+        Some((name, arg)) => (name, arg.trim()),
+        None => (line, ""),
+    };
+
+    let needs_arg = |cmd: fn(String) -> Command| {
+        if arg.is_empty() { Err(format!("{}: missing argument", name)) } else { Ok(cmd(arg.to_string())) }
+    };
+
+    match name {
+        "add" | "a" => needs_arg(Command::Add),
+        "column" | "col" => needs_arg(Command::Column),
+        "move" | "mv" => needs_arg(Command::Move),
+        "goto" | "g" => needs_arg(Command::Goto),
+        "open" | "o" => needs_arg(Command::Open),
+        "reset" => needs_arg(Command::Reset),
+        "sort" => needs_arg(Command::Sort),
+        "defaults" => Ok(Command::Defaults(Some(arg.to_string()).filter(|a| !a.is_empty()))),
+        "duplicate" | "dup" => {
+            let (mut reset_done, mut strip_tasks) = (false, false);
+            for flag in arg.split_whitespace() {
+                match flag {
+                    "reset" => reset_done = true,
+                    "empty" => strip_tasks = true,
+                    other => return Err(format!("{}: unknown option '{}' (reset, empty)", name, other)),
+                }
+            }
+            Ok(Command::Duplicate { reset_done, strip_tasks })
+        }
+        "blocked" => Ok(Command::Blocked),
+        "freeze" => match arg {
+            "" => Ok(Command::Freeze { board: false }),
+            "board" => Ok(Command::Freeze { board: true }),
+            other => Err(format!("{}: unknown option '{}' (board)", name, other)),
+        },
+        "checklist" | "cl" => Ok(Command::Checklist),
+        "icebox" => Ok(Command::Icebox),
+        "archive" => match arg {
+            "" => Ok(Command::Archive { done: false }),
+            "done" => Ok(Command::Archive { done: true }),
+            other => Err(format!("{}: unknown option '{}' (done)", name, other)),
+        },
+        "archived" => Ok(Command::Archived),
+        "trash" => Ok(Command::Trash),
+        "snooze" | "z" => needs_arg(Command::Snooze),
+        "due" => needs_arg(Command::Due),
+        "subscribe" | "sub" => needs_arg(Command::Subscribe),
+        "refresh" => Ok(Command::Refresh),
+        "sync" => Ok(Command::Sync),
+        "unsnooze" => Ok(Command::Unsnooze),
+        "snoozed" => Ok(Command::ShowSnoozed),
+        "wait" => needs_arg(Command::Wait),
+        "remind" => needs_arg(Command::Remind),
+        "agenda" => Ok(Command::Agenda),
+        "replace" | "s" => needs_arg(Command::Replace),
+        "waiting" => Ok(Command::Waiting),
+        "wide" => Ok(Command::Wide),
+        "lanes" => Ok(Command::Lanes(Some(arg.to_string()).filter(|a| !a.is_empty()))),
+        "lane" => needs_arg(Command::Lane),
+        "thaw" => Ok(Command::Thaw(Some(arg.to_string()).filter(|a| !a.is_empty()))),
+        "delete" | "del" => Ok(Command::Delete),
+        "back" => Ok(Command::Back),
+        "help" => Ok(Command::Help),
+        "find" | "f" => needs_arg(Command::Find),
+        "export" => needs_arg(Command::Export),
+        "import" => needs_arg(Command::Import),
+        "workspace" | "ws" => Ok(Command::Workspace(Some(arg.to_string()).filter(|a| !a.is_empty()))),
+        "quit" | "q" => Ok(Command::Quit),
+        "reload" => Ok(Command::Reload),
+        "write" | "w" => Ok(Command::Write { force: false }),
+        "write!" | "w!" => Ok(Command::Write { force: true }),
+        "" => Err("empty command".to_string()),
+        other => Err(format!("unknown command: {}", other)),
+    }
+}
+
+/// Completes the command name, or its argument for commands that take a
+/// column (`move`, `goto`) or a sub-board (`open`). Returns the new line when
+/// there is something to complete; ambiguous matches extend to their common prefix.
+pub fn complete(line: &str, columns: &[&str], boards: &[&str]) -> Option<String> {
+    match line.split_once(' ') {
+        None => {
+            let matches: Vec<&str> = COMMANDS.iter().map(|(c, _)| *c).filter(|c| c.starts_with(line)).collect();
+            let completed = common_prefix(&matches)?;
+            if matches.len() == 1 {
+                Some(format!("{} ", completed))
+            } else if completed.len() > line.len() {
+                Some(completed)
+            } else {
+                None
+            }
+        }
+        Some((name, arg)) => {
+            let candidates = match name {
+                "move" | "mv" | "goto" | "g" | "thaw" => columns,
+                "open" | "o" => boards,
+                _ => return None,
+            };
+            let arg = arg.trim_start();
+            let folded = collate::fold(arg);
+            let matches: Vec<&str> = candidates.iter().copied().filter(|c| collate::fold(c).starts_with(&folded)).collect();
+            let completed = if matches.len() == 1 { matches[0].to_string() } else { common_prefix(&matches)? };
+            if completed.len() > arg.len() { Some(format!("{} {}", name, completed)) } else { None }
+        }
+    }
+}
+
+fn common_prefix(words: &[&str]) -> Option<String> {
+    let first = words.first()?;
+    let mut prefix: &str = first;
+    for word in &words[1..] {
+        let len = prefix
+            .char_indices()
+            .zip(word.chars())
+            .take_while(|((_, a), b)| a.eq_ignore_ascii_case(b))
+            .last()
+            .map(|((i, a), _)| i + a.len_utf8())
+            .unwrap_or(0);
+        prefix = &prefix[..len];
+    }
+    Some(prefix.to_string())
+}
+
+/// Command line history, persisted next to the database unless made with `default`.
+#[derive(Default)]
+pub struct History {
+    entries: Vec<String>,
+    pos: Option<usize>, // Index into `entries` while browsing with Up/Down
+    file: Option<PathBuf>, // Where it is saved; None keeps it in memory
+}
+
+impl History {
+    /// The history kept beside board file `file`.
+    pub fn load(file: &Path) -> Self {
+        let file = storage::sibling(file, HISTORY_FILE);
+        let entries = fs::read_to_string(&file)
+            .map(|s| s.lines().map(str::to_string).collect())
+            .unwrap_or_default();
+        Self { entries, pos: None, file: Some(file) }
+    }
+
+    pub fn push(&mut self, line: &str) {
+        self.pos = None;
+        if line.is_empty() || self.entries.last().is_some_and(|l| l == line) {
+            return;
+        }
+        self.entries.push(line.to_string());
+        if self.entries.len() > HISTORY_LIMIT {
+            self.entries.remove(0);
+        }
+        // History is a convenience; failing to persist it should not interrupt the user
+        if let Some(file) = &self.file {
+            let _ = fs::write(file, self.entries.join("\n"));
+        }
+    }
+
+    pub fn prev(&mut self) -> Option<&str> {
+        let pos = match self.pos {
+            Some(0) => 0,
+            Some(p) => p - 1,
+            None => self.entries.len().checked_sub(1)?,
+        };
+        self.pos = Some(pos);
+        self.entries.get(pos).map(String::as_str)
+    }
+
+    pub fn next(&mut self) -> Option<&str> {
+        let pos = self.pos? + 1;
+        if pos >= self.entries.len() {
+            self.pos = None;
+            return Some("");
+        }
+        self.pos = Some(pos);
+        self.entries.get(pos).map(String::as_str)
+    }
+
+    pub fn reset(&mut self) {
+        self.pos = None;
+    }
+}
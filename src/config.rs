@@ -0,0 +1,497 @@
+use crate::storage::DB_FILE;
+use anyhow::{Context, Result};
+use ratatui::style::Color;
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+const CONFIG_DIR: &str = "rust-kanban";
+const CONFIG_FILE: &str = "config.toml";
+
+// User settings from `<config dir>/rust-kanban/config.toml`; every key is optional.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub board: BoardConfig,
+    pub ui: UiConfig,
+    pub confirm: ConfirmConfig,
+    pub autosave: AutosaveConfig,
+    pub waiting: WaitingConfig,
+    pub badges: BadgesConfig,
+    pub session: SessionConfig,
+    pub advisor: AdvisorConfig,
+    pub time: TimeConfig,
+    pub github: GithubConfig,
+    pub keys: KeysConfig,
+    pub limits: LimitsConfig,
+    pub lint: LintConfig,
+    pub trash: TrashConfig,
+    pub backup: BackupConfig,
+}
+
+impl Config {
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|d| d.join(CONFIG_DIR).join(CONFIG_FILE))
+    }
+
+    /// Defaults when the file does not exist; a malformed file is an error.
+    pub fn load() -> Result<Self> {
+        let Some(path) = Self::path().filter(|p| p.exists()) else {
+            return Ok(Self::default());
+        };
+        let text = fs::read_to_string(&path)?;
+        toml::from_str(&text).with_context(|| format!("invalid config {}", path.display()))
+    }
+}
+
+// [board]
+// file = "kanban.db"                           # board file; relative paths are from the working directory
+// columns = ["To Do", "In Progress", "Done"]   # columns of new boards and workspaces
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct BoardConfig {
+    pub file: PathBuf,
+    pub columns: Vec<String>,
+}
+
+impl Default for BoardConfig {
+    fn default() -> Self {
+        Self {
+            file: PathBuf::from(DB_FILE),
+            columns: ["To Do", "In Progress", "Done"].map(String::from).to_vec(),
+        }
+    }
+}
+
+// [ui]
+// icons = "emoji" | "ascii"               # ascii for terminals without emoji fonts
+// theme = "dark" | "light" | "solarized"   # built-in palette the colors below adjust
+// monochrome = false                       # no color at all; also `--no-color`
+// color_depth = "auto" | "truecolor" | "256" | "16" | "none"   # auto reads NO_COLOR, COLORTERM and TERM
+//
+// [ui.colors]                 # names ("blue", "darkgray"), "#rrggbb" or a 0-255 palette index
+// border_active = "green"
+// selected_bg = "blue"
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct UiConfig {
+    pub icons: IconStyle,
+    pub theme: ThemeName,
+    pub monochrome: bool,
+    pub color_depth: ColorDepth,
+    pub colors: Colors,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum IconStyle {
+    #[default]
+    Emoji,
+    Ascii,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeName {
+    #[default]
+    Dark,
+    Light, // For terminals with a light background
+    Solarized,
+}
+
+// What the terminal can show; theme colors beyond it are swapped for the nearest it has
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub enum ColorDepth {
+    #[default]
+    #[serde(rename = "auto")]
+    Auto,
+    #[serde(rename = "truecolor")]
+    TrueColor,
+    #[serde(rename = "256")]
+    Ansi256,
+    #[serde(rename = "16")]
+    Ansi16,
+    #[serde(rename = "none")]
+    None, // Same as `monochrome`
+}
+
+// Overrides of the theme's colors, see `theme::Theme` for what each one paints
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct Colors {
+    #[serde(deserialize_with = "color")]
+    pub border_active: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub border: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub selected_bg: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub selected_fg: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub search_hit_bg: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub search_hit_fg: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub board: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub todo: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub text: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub alert: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub foreground: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub dim: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub muted: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub accent: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub heading: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub warning: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub success: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub popup_border: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub input_border: Option<Color>,
+}
+
+fn color<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Color>, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    name.parse().map(Some).map_err(|_| serde::de::Error::custom(format!("unknown color '{}'", name)))
+}
+
+// [limits]
+// title_length = 200   # longest task, column and todo item title, in characters
+// columns = 20         # most columns on one board
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct LimitsConfig {
+    pub title_length: usize,
+    pub columns: usize,
+}
+
+impl Default for LimitsConfig {
+    fn default() -> Self {
+        Self { title_length: 200, columns: 20 }
+    }
+}
+
+// [backup]
+// dir = "~/Dropbox/kanban"   # gets a copy of the board file after every save; no backups when left out
+// every_mins = 60            # at most one dated copy per this many minutes, next to the always-current one
+// keep = 24                  # dated copies kept; the oldest go first
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct BackupConfig {
+    pub dir: Option<PathBuf>,
+    pub every_mins: u64,
+    pub keep: usize,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self { dir: None, every_mins: 60, keep: 24 }
+    }
+}
+
+// [trash]
+// purge_days = 30      # deleted tasks and columns older than this are emptied from the trash on start; 0 keeps them
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct TrashConfig {
+    pub purge_days: u64,
+}
+
+impl Default for TrashConfig {
+    fn default() -> Self {
+        Self { purge_days: 30 }
+    }
+}
+
+// [lint]                     # policy `kanban lint` checks the board tree against
+// wip = true                 # columns over their WIP limit
+// max_days = 30              # longest a task may stay in a column, leaving out the last one and the icebox; 0 for no limit
+// [[lint.require]]           # something every task in a column must have, on every board
+// column = "In Progress"
+// has = "assignee"           # "assignee" (an @name in the title), "due", "description", "tag", "#<tag>" or "completed"
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct LintConfig {
+    pub wip: bool,
+    pub max_days: i64,
+    pub require: Vec<Requirement>,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self { wip: true, max_days: 0, require: Vec::new() }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Requirement {
+    pub column: String,
+    #[serde(deserialize_with = "must_have")]
+    pub has: MustHave,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MustHave {
+    Assignee, // An `@name` word in the title
+    Due,
+    Description,
+    Tag(Option<String>), // This tag, or any
+    Completed, // Moved into the column rather than created there, so it says when the task was done
+}
+
+fn must_have<'de, D: Deserializer<'de>>(deserializer: D) -> Result<MustHave, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    Ok(match name.as_str() {
+        "assignee" => MustHave::Assignee,
+        "due" => MustHave::Due,
+        "description" => MustHave::Description,
+        "tag" => MustHave::Tag(None),
+        "completed" => MustHave::Completed,
+        _ => match name.strip_prefix('#').filter(|t| !t.is_empty()) {
+            Some(tag) => MustHave::Tag(Some(tag.to_string())),
+            None => return Err(serde::de::Error::custom(format!(
+                "unknown requirement '{}' (assignee, due, description, tag, #<tag>, completed)", name))),
+        },
+    })
+}
+
+// [autosave]
+// strategy = "change" | "debounce" | "navigation" | "manual"
+// interval_secs = 5    # quiet period before a debounced save
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct AutosaveConfig {
+    pub strategy: AutosaveStrategy,
+    pub interval_secs: u64,
+}
+
+impl Default for AutosaveConfig {
+    fn default() -> Self {
+        Self { strategy: AutosaveStrategy::Change, interval_secs: 5 }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AutosaveStrategy {
+    #[default]
+    Change,     // After every mutating action
+    Debounce,   // Once no change happened for `interval_secs`
+    Navigation, // When moving between boards, and on quit
+    Manual,     // Only on `:write` / Ctrl+S
+}
+
+// [waiting]
+// follow_up_days = 3   # waiting badge turns yellow, and red at twice this
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct WaitingConfig {
+    pub follow_up_days: i64,
+}
+
+impl Default for WaitingConfig {
+    fn default() -> Self {
+        Self { follow_up_days: 3 }
+    }
+}
+
+// [time]
+// zone = "America/New_York"   # IANA zone for due times and "today"; the system's when unset
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct TimeConfig {
+    pub zone: Option<String>,
+}
+
+// [github]
+// repo = "acme/web"
+// token = "ghp_..."                            # or GITHUB_TOKEN; needs read/write access to issues
+// column = "Triage"                            # where new issues land; the first column when unset
+// labels = { "In Progress" = "in progress" }   # label an issue carries while its task is in that column
+// close_columns = ["Done"]                     # moving a task here closes its issue, moving it out reopens it
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct GithubConfig {
+    pub repo: Option<String>,
+    pub token: Option<String>,
+    pub column: Option<String>,
+    pub labels: BTreeMap<String, String>,
+    pub close_columns: Vec<String>,
+}
+
+impl Default for GithubConfig {
+    fn default() -> Self {
+        Self { repo: None, token: None, column: None, labels: BTreeMap::new(), close_columns: vec!["Done".to_string()] }
+    }
+}
+
+// [session]
+// summary = "off" | "print" | "file"   # what changed while the TUI was open, on quit
+// file = "kanban.worklog.md"           # appended to with "file"
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct SessionConfig {
+    pub summary: SummaryMode,
+    pub file: PathBuf,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self { summary: SummaryMode::Off, file: PathBuf::from("kanban.worklog.md") }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SummaryMode {
+    #[default]
+    Off,
+    Print, // To the terminal after it is restored
+    File,  // Appended to `file`, for a running work log
+}
+
+// [advisor]
+// enabled = true
+// interval_secs = 60                  # how often the board is checked and the next nudge shown
+// rules = ["wip", "no_due", "stale", "overdue"]
+// stale_days = 14                     # for "stale": days in a column before a task counts as stuck
+// no_due_columns = ["This Week"]      # for "no_due": columns whose tasks should have a due date
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct AdvisorConfig {
+    pub enabled: bool,
+    pub interval_secs: u64,
+    pub rules: Vec<Rule>,
+    pub stale_days: i64,
+    pub no_due_columns: Vec<String>,
+}
+
+impl Default for AdvisorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: 60,
+            rules: vec![Rule::Wip, Rule::NoDue, Rule::Stale, Rule::Overdue],
+            stale_days: 14,
+            no_due_columns: Vec::new(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Rule {
+    Wip,     // Columns over their WIP limit
+    NoDue,   // Tasks without a due date in `no_due_columns`
+    Stale,   // Tasks sitting in a working column for `stale_days`
+    Overdue, // Tasks past their due date outside the last column
+}
+
+// [badges]
+// show = ["age", "board", "blocked"]   # which badges cards show, in this order
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct BadgesConfig {
+    pub show: Vec<Badge>,
+}
+
+impl Default for BadgesConfig {
+    fn default() -> Self {
+        Self { show: Badge::ALL.to_vec() }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Badge {
+    Due,      // Due date, colored as it nears and passes
+    Age,      // Days in the current column
+    Snoozed,  // Date a snoozed task comes back
+    Waiting,  // Who it waits on, and for how long
+    Blocked,  // Reason given when entering a blocked column
+    Progress, // Done/total todo items
+    Board,    // Columns and open tasks of a sub-board
+    Alerts,   // Tasks needing attention anywhere below a sub-board
+}
+
+impl Badge {
+    pub const ALL: &[Badge] = &[Badge::Due, Badge::Age, Badge::Snoozed, Badge::Waiting, Badge::Blocked, Badge::Progress, Badge::Board, Badge::Alerts];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Badge::Due => "due",
+            Badge::Age => "age",
+            Badge::Snoozed => "snoozed",
+            Badge::Waiting => "waiting",
+            Badge::Blocked => "blocked",
+            Badge::Progress => "progress",
+            Badge::Board => "board",
+            Badge::Alerts => "alerts",
+        }
+    }
+}
+
+// [keys]                    # normal-mode action = keys; an action listed here loses its default keys
+// move_left = ["a", "left"]
+// delete_task = ["ctrl+d"]
+// quit = []                 # no key at all
+// Names and defaults are in `keymap::NORMAL_KEYS`; keys are a character or a
+// name like "enter" or "f5", after any of "ctrl+", "alt+" and "shift+".
+pub type KeysConfig = BTreeMap<String, Vec<String>>;
+
+// [confirm]
+// preset = "safe" | "default" | "fast"
+// delete_task = true   # per-operation overrides of the preset
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfirmConfig {
+    pub preset: SafetyPreset,
+    pub delete_task: Option<bool>,
+    pub delete_column: Option<bool>,
+    pub quit_unsaved: Option<bool>,
+    pub export_overwrite: Option<bool>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SafetyPreset {
+    Safe,
+    #[default]
+    Default,
+    Fast,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConfirmOp {
+    DeleteTask,
+    DeleteColumn, // Only when it is empty; a column with tasks always asks where they go
+    QuitUnsaved,
+    ExportOverwrite,
+}
+
+impl ConfirmConfig {
+    pub fn requires(&self, op: ConfirmOp) -> bool {
+        let (explicit, safe, default) = match op {
+            ConfirmOp::DeleteTask => (self.delete_task, true, false),
+            ConfirmOp::DeleteColumn => (self.delete_column, true, true),
+            ConfirmOp::QuitUnsaved => (self.quit_unsaved, true, true),
+            ConfirmOp::ExportOverwrite => (self.export_overwrite, true, true),
+        };
+        explicit.unwrap_or(match self.preset {
+            SafetyPreset::Safe => safe,
+            SafetyPreset::Default => default,
+            SafetyPreset::Fast => false,
+        })
+    }
+}
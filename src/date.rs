@@ -0,0 +1,178 @@
+use crate::zone;
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate, NaiveTime, Utc, Weekday};
+
+// Dates typed by the user, in any case and spacing:
+//   2026-11-03, "nov 3", "3 november"   (the next one that isn't past)
+//   "today", "tomorrow"
+//   "3d", "2w", "in 3 days", "in a week", "in 2 months"
+//   "fri", "friday"                     (the next one after today)
+//   "this fri"                          (today if it is one)
+//   "next fri"                          (in the week after this one; weeks start on Monday)
+//   "next week", "next month"           (their first day)
+pub fn parse(s: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let s = s.to_lowercase();
+    let words: Vec<&str> = s.split_whitespace().collect();
+    match words.as_slice() {
+        ["today"] => Some(today),
+        ["tomorrow" | "tmrw"] => today.checked_add_days(Days::new(1)),
+        ["next", "week"] => monday(today).checked_add_days(Days::new(7)),
+        ["next", "month"] => today.with_day(1)?.checked_add_months(Months::new(1)),
+        ["in", n, unit] => offset(count(n)?, unit, today),
+        [short] if let Some((n, unit)) = short.split_at_checked(short.len().saturating_sub(1))
+            && let Ok(n) = n.parse::<u32>() => match unit {
+                "d" => offset(n, "days", today),
+                "w" => offset(n, "weeks", today),
+                _ => None,
+            },
+        [iso] if let Ok(date) = NaiveDate::parse_from_str(iso, "%Y-%m-%d") => Some(date),
+        [day] => {
+            let day = day.parse::<Weekday>().ok()?;
+            let ahead = days_until(today, day);
+            today.checked_add_days(Days::new(if ahead == 0 { 7 } else { ahead }))
+        }
+        ["this", day] => today.checked_add_days(Days::new(days_until(today, day.parse().ok()?))),
+        ["next", day] => {
+            let day: Weekday = day.parse().ok()?;
+            monday(today).checked_add_days(Days::new(7 + u64::from(day.num_days_from_monday())))
+        }
+        [a, b] => month_day(a, b, today).or_else(|| month_day(b, a, today)),
+        _ => None,
+    }
+}
+
+/// A due: a date as `parse` reads it, optionally followed by a time of day
+/// ("fri 17:00", "tomorrow at 9am"), in the configured zone. Without a time it
+/// is due that day; a time alone ("5pm") is today.
+pub fn parse_due(s: &str, today: NaiveDate) -> Option<DateTime<Utc>> {
+    let s = s.trim();
+    if let Some(time) = time_of_day(s) {
+        return Some(zone::utc(today.and_time(time)));
+    }
+    if let Some((day, time)) = s.rsplit_once(' ')
+        && let Some(time) = time_of_day(time) {
+            let day = day.trim_end();
+            let day = day.strip_suffix(" at").unwrap_or(day);
+            return parse(day, today).map(|day| zone::utc(day.and_time(time)));
+        }
+    parse(s, today).map(zone::start_of_day)
+}
+
+// "17:00", "5pm", "5:30pm"
+fn time_of_day(s: &str) -> Option<NaiveTime> {
+    let s = s.to_lowercase();
+    if let Ok(time) = NaiveTime::parse_from_str(&s, "%H:%M") {
+        return Some(time);
+    }
+    let (clock, pm) = match (s.strip_suffix("am"), s.strip_suffix("pm")) {
+        (Some(clock), _) => (clock, false),
+        (_, Some(clock)) => (clock, true),
+        _ => return None,
+    };
+    let (hour, minute) = clock.split_once(':').unwrap_or((clock, "0"));
+    let (hour, minute) = (hour.parse::<u32>().ok()?, minute.parse::<u32>().ok()?);
+    if !(1..=12).contains(&hour) {
+        return None;
+    }
+    NaiveTime::from_hms_opt(hour % 12 + if pm { 12 } else { 0 }, minute, 0)
+}
+
+fn count(word: &str) -> Option<u32> {
+    match word {
+        "a" | "an" | "one" => Some(1),
+        "two" => Some(2),
+        "three" => Some(3),
+        n => n.parse().ok(),
+    }
+}
+
+fn offset(n: u32, unit: &str, today: NaiveDate) -> Option<NaiveDate> {
+    match unit.trim_end_matches('s') {
+        "day" => today.checked_add_days(Days::new(n.into())),
+        "week" => today.checked_add_days(Days::new(u64::from(n) * 7)),
+        "month" => today.checked_add_months(Months::new(n)),
+        _ => None,
+    }
+}
+
+/// The Monday of the week `day` is in.
+pub fn monday(day: NaiveDate) -> NaiveDate {
+    day - Days::new(day.weekday().num_days_from_monday().into())
+}
+
+// 0 when `today` is already `day`
+fn days_until(today: NaiveDate, day: Weekday) -> u64 {
+    ((7 + day.num_days_from_monday() - today.weekday().num_days_from_monday()) % 7).into()
+}
+
+// ("nov", "3") -> the next November 3rd, today included
+fn month_day(month: &str, day: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let month = month.parse::<chrono::Month>().ok()?.number_from_month();
+    let day = day.trim_end_matches(['s', 't', 'n', 'd', 'r', 'h']).parse().ok()?;
+    let this_year = NaiveDate::from_ymd_opt(today.year(), month, day)?;
+    if this_year >= today { Some(this_year) } else { NaiveDate::from_ymd_opt(today.year() + 1, month, day) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A Wednesday
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 14).unwrap()
+    }
+
+    fn day(m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, m, d).unwrap()
+    }
+
+    #[test]
+    fn parses_words_and_offsets() {
+        assert_eq!(parse("today", today()), Some(today()));
+        assert_eq!(parse(" Tomorrow ", today()), Some(day(10, 15)));
+        assert_eq!(parse("3d", today()), Some(day(10, 17)));
+        assert_eq!(parse("2w", today()), Some(day(10, 28)));
+        assert_eq!(parse("in a week", today()), Some(day(10, 21)));
+        assert_eq!(parse("in 2 months", today()), Some(day(12, 14)));
+        assert_eq!(parse("next week", today()), Some(day(10, 19)));
+        assert_eq!(parse("next month", today()), Some(day(11, 1)));
+    }
+
+    #[test]
+    fn parses_weekdays() {
+        assert_eq!(parse("fri", today()), Some(day(10, 16)));
+        assert_eq!(parse("wed", today()), Some(day(10, 21)), "a bare weekday is never today");
+        assert_eq!(parse("this wed", today()), Some(today()));
+        assert_eq!(parse("next fri", today()), Some(day(10, 23)));
+        assert_eq!(parse("next mon", today()), Some(day(10, 19)));
+    }
+
+    #[test]
+    fn parses_calendar_dates() {
+        assert_eq!(parse("2026-11-03", today()), Some(day(11, 3)));
+        assert_eq!(parse("nov 3", today()), Some(day(11, 3)));
+        assert_eq!(parse("3rd November", today()), Some(day(11, 3)));
+        assert_eq!(parse("oct 14", today()), Some(today()));
+        assert_eq!(parse("oct 13", today()), NaiveDate::from_ymd_opt(2027, 10, 13), "past days roll over to next year");
+    }
+
+    #[test]
+    fn rejects_nonsense() {
+        for s in ["", "someday", "3x", "in 3 fortnights", "feb 30", "next year"] {
+            assert_eq!(parse(s, today()), None, "{:?}", s);
+        }
+    }
+
+    #[test]
+    fn dues_take_an_optional_time() {
+        let at = |d: NaiveDate, h, m| zone::utc(d.and_hms_opt(h, m, 0).unwrap());
+        assert_eq!(parse_due("fri 17:00", today()), Some(at(day(10, 16), 17, 0)));
+        assert_eq!(parse_due("tomorrow at 9am", today()), Some(at(day(10, 15), 9, 0)));
+        assert_eq!(parse_due("5:30pm", today()), Some(at(today(), 17, 30)));
+        assert_eq!(parse_due("12am", today()), Some(at(today(), 0, 0)));
+        assert_eq!(parse_due("13pm", today()), None);
+
+        let whole_day = parse_due("nov 3", today()).unwrap();
+        assert_eq!(whole_day, zone::start_of_day(day(11, 3)));
+        assert!(!zone::has_time(whole_day));
+    }
+}
@@ -0,0 +1,68 @@
+use crate::autocomplete;
+use crate::collate;
+use crate::date;
+use crate::model::Task;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+// What a column gives the tasks created in it, e.g. `#bug` in Bugs or
+// `pri:A due:tomorrow` in Urgent. Tasks moved in later are left alone.
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TaskDefaults {
+    pub tags: Vec<String>,   // Without the `#`; priority is the `pri-A` tag, as todo.txt's `(A)`
+    pub due: Option<String>, // As typed ("3d", "fri 17:00"), resolved when a task is created
+}
+
+impl TaskDefaults {
+    /// Reads `#tag` words, `pri:A` for `#pri-A`, and `due:` with a date that
+    /// takes the rest of the line. Nothing at all is `None`, as is "off".
+    pub fn parse(s: &str, today: NaiveDate) -> Result<Option<Self>, String> {
+        if s.trim().eq_ignore_ascii_case("off") {
+            return Ok(None);
+        }
+        let (words, due) = match s.split_once("due:") {
+            Some((words, due)) => (words, Some(due.trim().to_string())),
+            None => (s, None),
+        };
+        let mut tags: Vec<String> = Vec::new();
+        for word in words.split_whitespace() {
+            let tag = match (word.strip_prefix('#'), word.strip_prefix("pri:")) {
+                (Some(tag), _) if !tag.is_empty() => tag.to_string(),
+                (_, Some(p)) if p.len() == 1 && p.chars().all(|c| c.is_ascii_alphabetic()) => format!("pri-{}", p.to_ascii_uppercase()),
+                _ => return Err(format!("'{}' is not a #tag, pri:A or due:<date>", word)),
+            };
+            if !tags.iter().any(|t| collate::eq(t, &tag)) {
+                tags.push(tag);
+            }
+        }
+        if let Some(due) = &due
+            && date::parse_due(due, today).is_none() {
+                return Err(format!("due: '{}' is not a date (tomorrow, 3d, next fri, fri 17:00)", due));
+            }
+        Ok((!tags.is_empty() || due.is_some()).then_some(Self { tags, due }))
+    }
+
+    /// Adds the tags `task` doesn't already have to its title, and the due date
+    /// unless it has one.
+    pub fn apply(&self, task: &mut Task, today: NaiveDate) {
+        for tag in &self.tags {
+            if !autocomplete::tags_in(&task.title).any(|t| collate::eq(t, tag)) {
+                task.title = format!("{} #{}", task.title, tag);
+            }
+        }
+        if task.due.is_none() {
+            task.due = self.due.as_deref().and_then(|due| date::parse_due(due, today));
+        }
+    }
+}
+
+impl std::fmt::Display for TaskDefaults {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut words: Vec<String> = self.tags.iter().map(|t| format!("#{}", t)).collect();
+        if let Some(due) = &self.due {
+            words.push(format!("due:{}", due));
+        }
+        write!(f, "{}", words.join(" "))
+    }
+}
@@ -0,0 +1,118 @@
+use crate::app::Action;
+use crate::model::{Board, Column, Task, TaskContent, TodoItem, WaitingOn};
+use crate::zone;
+use chrono::{Days, NaiveDate, TimeDelta, Utc};
+use std::time::Duration;
+
+// The board and tour behind `--demo` and `cargo run --example demo`: a small
+// product team's board, dated relative to today so the badges look lived in,
+// and a scripted walk through it for recordings.
+
+/// A scripted action and how long to pause before it.
+pub type Step = (Duration, Action);
+
+pub fn board() -> Board {
+    let today = zone::today();
+    let day = |n: i64| match n {
+        n if n < 0 => today.checked_sub_days(Days::new(n.unsigned_abs())),
+        n => today.checked_add_days(Days::new(n.unsigned_abs())),
+    }.unwrap_or(today);
+    let due = |n: i64| Some(zone::start_of_day(day(n)));
+
+    let mut backlog = Column::new("Backlog");
+    backlog.tasks = vec![
+        task("Plan Q3 roadmap #planning", |t| t.content = Some(TaskContent::Text("Themes: onboarding, speed, mobile.\nDraft by Friday.".to_string()))),
+        task("Dark mode #ui", |t| t.due = due(6)),
+        task("Try the new search index", |t| t.snoozed_until = Some(day(3))),
+    ];
+
+    let mut doing = Column::new("In Progress");
+    doing.wip_limit = Some(3);
+    doing.tasks = vec![
+        task("Website relaunch", |t| t.content = Some(TaskContent::Board(relaunch(today)))),
+        task("Release 1.4", |t| {
+            t.due = due(1);
+            t.content = Some(TaskContent::Todo(todos(&[("Freeze features", true), ("Update changelog", true), ("Tag release", false), ("Publish packages", false), ("Announce", false)])));
+        }),
+        task("Fix login redirect #bug", |t| {
+            t.due = due(-1);
+            t.entered_column_at = Utc::now() - TimeDelta::days(4);
+            t.created_at = t.entered_column_at;
+        }),
+    ];
+
+    let mut review = Column::new("Review");
+    review.requires_reason = true;
+    review.tasks = vec![
+        task("API rate limits", |t| {
+            t.waiting_on = Some(WaitingOn { who: "Sam".to_string(), since: day(-4) });
+            t.blocked_reason = Some("needs security sign-off".to_string());
+        }),
+    ];
+
+    let mut done = Column::new("Done");
+    done.tasks = vec![task("Set up CI", |_| {}), task("Pick a logo #design", |_| {})];
+
+    Board {
+        title: "Demo".to_string(),
+        columns: vec![backlog, doing, review, done],
+        description: "Welcome! This board lives in memory: change anything, nothing is saved.".to_string(),
+        frozen: false,
+        wide_cards: false,
+        archive: Vec::new(),
+        trash: Vec::new(),
+        lanes: None,
+    }
+}
+
+/// A short tour of `board`, ending back on it with the agenda and details open.
+pub fn script() -> Vec<Step> {
+    let (short, long) = (Duration::from_millis(600), Duration::from_millis(1200));
+    let mut steps = vec![
+        (long, Action::MoveRight),
+        (short, Action::MoveDown),
+        (long, Action::DrillDown),
+        (short, Action::MoveDown),
+        (short, Action::MoveDown),
+        (long, Action::ToggleTodo),
+        (long, Action::GoBack),
+        (short, Action::MoveUp),
+        (long, Action::DrillDown),
+        (long, Action::MoveTaskRight),
+        (long, Action::JumpToLevel(0)),
+        (short, Action::MoveLeft),
+        (long, Action::EnterEditMode),
+    ];
+    steps.extend("Write the changelog".chars().map(|c| (Duration::from_millis(60), Action::InputChar(c))));
+    steps.push((short, Action::SubmitTask));
+    steps.push((long, Action::EnterCommandMode));
+    steps.extend("agenda".chars().map(|c| (Duration::from_millis(80), Action::InputChar(c))));
+    steps.extend([
+        (short, Action::SubmitTask),
+        (long, Action::MoveRight),
+        (short, Action::MoveDown),
+        (short, Action::ToggleDetails),
+    ]);
+    steps
+}
+
+fn relaunch(today: NaiveDate) -> Board {
+    let soon = today.checked_add_days(Days::new(2)).map(zone::start_of_day);
+    let mut todo = Column::new("To Do");
+    todo.tasks = vec![task("Write copy", |t| t.due = soon), task("Redirects from old URLs", |_| {})];
+    let mut doing = Column::new("Doing");
+    doing.tasks = vec![task("New landing page #ui", |_| {})];
+    let mut done = Column::new("Done");
+    done.tasks = vec![task("Pick a CMS", |_| {})];
+    Board { title: "Website relaunch".to_string(), columns: vec![todo, doing, done], description: String::new(), frozen: false, wide_cards: false, archive: Vec::new(), trash: Vec::new(), lanes: None }
+}
+
+fn task(title: &str, set: impl FnOnce(&mut Task)) -> Task {
+    let mut task = Task::new(title, "");
+    set(&mut task);
+    task
+}
+
+fn todos(items: &[(&str, bool)]) -> Vec<TodoItem> {
+    items.iter().map(|&(text, done)| TodoItem { text: text.to_string(), done }).collect()
+}
@@ -0,0 +1,155 @@
+use std::cell::Cell;
+use unicode_width::UnicodeWidthStr;
+
+// Multi-line editing of a note: the text as lines, a cursor, and the part in view
+
+/// Keys of the note editor besides typing and Backspace, which come as
+/// `Action::InputChar` and `Action::InputBackspace`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Edit {
+    Newline,
+    Delete, // The character under the cursor, or the line break at its end
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Top,    // Start of the note
+    Bottom, // End of the note
+}
+
+pub struct Editor {
+    lines: Vec<String>,
+    row: usize,
+    col: usize, // In characters; may run past a shorter line, kept for Up/Down
+    top: Cell<usize>,    // First line in view
+    left: Cell<usize>,   // First display column in view
+    height: Cell<usize>, // Lines in view when last drawn, for PageUp/PageDown
+}
+
+impl Editor {
+    /// Edits `text` with the cursor at its end.
+    pub fn new(text: &str) -> Self {
+        let lines: Vec<String> = text.split('\n').map(String::from).collect();
+        let row = lines.len() - 1;
+        let col = lines[row].chars().count();
+        Self { lines, row, col, top: Cell::new(0), left: Cell::new(0), height: Cell::new(1) }
+    }
+
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    pub fn apply(&mut self, edit: Edit) {
+        let page = self.height.get().max(1);
+        match edit {
+            Edit::Newline => {
+                let at = self.byte_at();
+                let rest = self.lines[self.row].split_off(at);
+                self.row += 1;
+                self.lines.insert(self.row, rest);
+                self.col = 0;
+            }
+            Edit::Delete => {
+                let at = self.byte_at();
+                let line = &mut self.lines[self.row];
+                if at < line.len() {
+                    line.remove(at);
+                } else if self.row + 1 < self.lines.len() {
+                    let next = self.lines.remove(self.row + 1);
+                    self.lines[self.row].push_str(&next);
+                }
+            }
+            Edit::Left => {
+                self.col = self.col.min(self.line_len());
+                if self.col > 0 {
+                    self.col -= 1;
+                } else if self.row > 0 {
+                    self.row -= 1;
+                    self.col = self.line_len();
+                }
+            }
+            Edit::Right => {
+                self.col = self.col.min(self.line_len());
+                if self.col < self.line_len() {
+                    self.col += 1;
+                } else if self.row + 1 < self.lines.len() {
+                    self.row += 1;
+                    self.col = 0;
+                }
+            }
+            Edit::Up => self.row = self.row.saturating_sub(1),
+            Edit::Down => self.row = (self.row + 1).min(self.lines.len() - 1),
+            Edit::Home => self.col = 0,
+            Edit::End => self.col = self.line_len(),
+            Edit::PageUp => self.row = self.row.saturating_sub(page),
+            Edit::PageDown => self.row = (self.row + page).min(self.lines.len() - 1),
+            Edit::Top => (self.row, self.col) = (0, 0),
+            Edit::Bottom => {
+                self.row = self.lines.len() - 1;
+                self.col = self.line_len();
+            }
+        }
+    }
+
+    pub fn insert(&mut self, c: char) {
+        let at = self.byte_at();
+        self.lines[self.row].insert(at, c);
+        self.col = self.col.min(self.line_len()) + 1;
+    }
+
+    /// Inserts `text` at the cursor, line breaks included.
+    pub fn insert_str(&mut self, text: &str) {
+        for c in text.chars() {
+            if c == '\n' { self.apply(Edit::Newline) } else { self.insert(c) }
+        }
+    }
+
+    /// Deletes the character before the cursor, joining lines at the start of one.
+    pub fn backspace(&mut self) {
+        if self.col.min(self.line_len()) == 0 && self.row == 0 {
+            return;
+        }
+        self.apply(Edit::Left);
+        self.apply(Edit::Delete);
+    }
+
+    /// The cursor as line and display column.
+    pub fn cursor(&self) -> (usize, usize) {
+        let line = &self.lines[self.row];
+        (self.row, line[..self.byte_at()].width())
+    }
+
+    /// Where the view of `width` by `height` cells starts, as line and display
+    /// column: where it was last time, moved just enough to show the cursor.
+    pub fn scroll(&self, width: usize, height: usize) -> (usize, usize) {
+        let (row, x) = self.cursor();
+        self.height.set(height);
+        let follow = |start: &Cell<usize>, at: usize, size: usize| {
+            if at < start.get() {
+                start.set(at);
+            } else if size > 0 && at >= start.get() + size {
+                start.set(at + 1 - size);
+            }
+            start.get()
+        };
+        (follow(&self.top, row, height), follow(&self.left, x, width))
+    }
+
+    fn line_len(&self) -> usize {
+        self.lines[self.row].chars().count()
+    }
+
+    // Byte offset of the cursor in its line
+    fn byte_at(&self) -> usize {
+        let line = &self.lines[self.row];
+        line.char_indices().nth(self.col).map_or(line.len(), |(i, _)| i)
+    }
+}
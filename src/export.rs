@@ -0,0 +1,134 @@
+use crate::autocomplete;
+use crate::link;
+use crate::model::{Board, Task, TaskContent};
+use crate::zone;
+use std::fmt::Write;
+
+// Markdown rendering of a board tree for wikis and PRs: the board and its
+// sub-boards as nested headings, columns as the next heading level down, tasks
+// as list items and todo items as checkboxes.
+
+const MAX_HEADING: usize = 6;
+
+pub struct Markdown {
+    pub links: bool, // Follow each task with its kanban:// address
+}
+
+impl Markdown {
+    /// `crumbs` are the breadcrumbs of `board` from the main board, for links.
+    pub fn render(&self, board: &Board, crumbs: &[String]) -> String {
+        let mut out = String::new();
+        self.board(&mut out, board, &board.title, 1, &mut crumbs.to_vec());
+        out
+    }
+
+    fn board(&self, out: &mut String, board: &Board, title: &str, level: usize, crumbs: &mut Vec<String>) {
+        heading(out, level, title);
+        if !board.description.is_empty() {
+            let _ = writeln!(out, "{}\n", board.description.trim_end());
+        }
+        for column in &board.columns {
+            heading(out, level + 1, &column.title);
+            if column.tasks.is_empty() {
+                out.push_str("_No tasks_\n\n");
+                continue;
+            }
+            for task in &column.tasks {
+                self.task(out, task, &column.title, crumbs);
+                // A list item can't hold headings, so the sub-board follows it
+                if let Some(TaskContent::Board(sub)) = &task.content {
+                    out.push('\n');
+                    crumbs.push(task.title.clone());
+                    self.board(out, sub, &task.title, level + 2, crumbs);
+                    crumbs.pop();
+                }
+            }
+            if !out.ends_with("\n\n") {
+                out.push('\n');
+            }
+        }
+    }
+
+    fn task(&self, out: &mut String, task: &Task, column: &str, crumbs: &[String]) {
+        let _ = write!(out, "- {}", task.title);
+        if let Some(due) = task.due {
+            let _ = write!(out, " — due {}", zone::format_due(due, "%Y-%m-%d"));
+        }
+        if let Some(reason) = &task.blocked_reason {
+            let _ = write!(out, " — blocked: {}", reason);
+        }
+        if self.links {
+            let _ = write!(out, " <{}>", link::format(crumbs, column, task.id));
+        }
+        out.push('\n');
+        for line in task.description.lines().filter(|l| !l.trim().is_empty()) {
+            let _ = writeln!(out, "  {}", line);
+        }
+        match &task.content {
+            Some(TaskContent::Todo(items)) => {
+                for item in items {
+                    let _ = writeln!(out, "  - [{}] {}", if item.done { "x" } else { " " }, item.text);
+                }
+            }
+            Some(TaskContent::Text(text)) => {
+                for line in text.lines() {
+                    let _ = writeln!(out, "  > {}", line);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn heading(out: &mut String, level: usize, title: &str) {
+    let _ = writeln!(out, "{} {}\n", "#".repeat(level.min(MAX_HEADING)), title);
+}
+
+// --- CSV ---
+// One row per task of the whole tree, sub-board tasks included, for spreadsheets.
+
+const CSV_HEADER: &str = "board,column,title,description,tags,due,done,id";
+
+/// `crumbs` are the breadcrumbs of `board` from the main board; a task's board
+/// is written as the path down to it, `Main Board > Project X`.
+pub fn csv(board: &Board, crumbs: &[String]) -> String {
+    let mut out = format!("{}\n", CSV_HEADER);
+    csv_rows(&mut out, board, &mut crumbs.to_vec());
+    out
+}
+
+fn csv_rows(out: &mut String, board: &Board, crumbs: &mut Vec<String>) {
+    let last = board.columns.len().saturating_sub(1);
+    for (c, column) in board.columns.iter().enumerate() {
+        for task in &column.tasks {
+            let tags: Vec<&str> = autocomplete::tags_in(&task.title).collect();
+            let fields = [
+                crumbs.join(" > "),
+                column.title.clone(),
+                task.title.clone(),
+                task.description.clone(),
+                tags.join(" "),
+                task.due.map(|due| zone::format_due(due, "%Y-%m-%d")).unwrap_or_default(),
+                // The last column is taken as done, as everywhere else
+                (c == last && last > 0).to_string(),
+                task.id.to_string(),
+            ];
+            let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+            let _ = writeln!(out, "{}", row.join(","));
+            if let Some(TaskContent::Board(sub)) = &task.content {
+                crumbs.push(task.title.clone());
+                csv_rows(out, sub, crumbs);
+                crumbs.pop();
+            }
+        }
+    }
+}
+
+// Quoted when it holds a comma, quote or line break, with quotes doubled
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,265 @@
+use crate::collate;
+use crate::config::GithubConfig;
+use crate::model::{Board, Source, Task};
+use crate::storage;
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+// Two-way sync between the issues of one GitHub repository (`[github]` in the
+// config) and the columns of the main board. Open issues are pulled in as tasks,
+// matched by `Source::id` ("acme/web#123"). Moving a task is pushed back as
+// labels and closing or reopening. Issues closed on GitHub move to the first
+// close column. The column each issue was last synced in is kept beside the
+// board, so only moves made since then are pushed.
+
+pub const SYSTEM: &str = "github"; // `Source::system` of synced tasks
+const API: &str = "https://api.github.com";
+const STATE_FILE: &str = "github.json"; // Beside the board file, see `storage::sibling`
+const TIMEOUT: Duration = Duration::from_secs(20);
+const PER_PAGE: usize = 100;
+
+#[derive(Debug, Default)]
+pub struct Synced {
+    pub added: usize,
+    pub updated: usize,
+    pub closed: usize, // Closed on GitHub, so moved to a close column
+    pub pushed: usize, // Moves sent to GitHub
+}
+
+#[derive(Deserialize)]
+struct Issue {
+    number: u64,
+    title: String,
+    body: Option<String>,
+    html_url: String,
+    pull_request: Option<serde_json::Value>, // Pull requests are issues too, and skipped
+}
+
+// Issue id -> column title it was in after the last sync
+#[derive(Serialize, Deserialize, Default)]
+struct State {
+    columns: BTreeMap<String, String>,
+}
+
+struct Client<'a> {
+    repo: &'a str,
+    token: String,
+}
+
+/// Pushes moves made since the last sync, then pulls the repository's open issues into `board`.
+/// The sync state is kept beside board file `file`.
+pub fn sync(board: &mut Board, config: &GithubConfig, file: &Path) -> Result<Synced> {
+    let fetched = fetch(board, config, file)?;
+    apply(board, config, file, fetched)
+}
+
+/// What `fetch` got from GitHub, for `apply` to pull into the board.
+pub struct Fetched {
+    issues: Vec<Issue>,
+    state: State,
+    synced: Synced,
+}
+
+/// The network half of `sync`: pushes the moves made on `board` since the last
+/// sync and fetches the open issues, leaving `board` as it is. `kanban sync`
+/// does this before taking the board lock.
+pub fn fetch(board: &Board, config: &GithubConfig, file: &Path) -> Result<Fetched> {
+    let client = client(config)?;
+    target(board, config)?;
+
+    let mut state = State::load(file)?;
+    let mut synced = Synced::default();
+    let pushed = push(board, config, &client, &mut state, &mut synced);
+    // Keep what was pushed even if a later request failed
+    state.save(file)?;
+    pushed?;
+    let issues = client.open_issues()?;
+    Ok(Fetched { issues, state, synced })
+}
+
+/// Pulls the issues of `fetched` into `board`: new ones are added, known ones
+/// updated and those closed on GitHub moved to the first close column.
+pub fn apply(board: &mut Board, config: &GithubConfig, file: &Path, fetched: Fetched) -> Result<Synced> {
+    let client = client(config)?;
+    let target = target(board, config)?;
+    let Fetched { issues, mut state, mut synced } = fetched;
+    pull(board, config, &client, &issues, target, &mut state, &mut synced);
+    state.save(file)?;
+    Ok(synced)
+}
+
+fn client(config: &GithubConfig) -> Result<Client<'_>> {
+    let repo = config.repo.as_deref().ok_or_else(|| anyhow!("no repository; set repo in [github]"))?;
+    let token = config.token.clone().or_else(|| std::env::var("GITHUB_TOKEN").ok())
+        .ok_or_else(|| anyhow!("no token; set token in [github] or GITHUB_TOKEN"))?;
+    Ok(Client { repo, token })
+}
+
+// The column new issues go in
+fn target(board: &Board, config: &GithubConfig) -> Result<usize> {
+    let target = match &config.column {
+        Some(name) => board.match_column(name)?,
+        None => 0,
+    };
+    if board.columns.is_empty() {
+        bail!("the board has no columns");
+    }
+    Ok(target)
+}
+
+fn push(board: &Board, config: &GithubConfig, client: &Client, state: &mut State, synced: &mut Synced) -> Result<()> {
+    for column in &board.columns {
+        for task in &column.tasks {
+            let Some((id, number)) = client.issue_of(task) else { continue };
+            let Some(before) = state.columns.get(&id).cloned() else {
+                state.columns.insert(id, column.title.clone());
+                continue;
+            };
+            if collate::eq(&before, &column.title) {
+                continue;
+            }
+            let (was_closed, closes) = (closes(config, &before), closes(config, &column.title));
+            if closes != was_closed {
+                client.send("PATCH", &format!("/issues/{}", number), json!({ "state": if closes { "closed" } else { "open" } }))
+                    .with_context(|| format!("{} {}", if closes { "closing" } else { "reopening" }, id))?;
+            }
+            if let Some(label) = label(config, &before) {
+                // Already gone is fine
+                let _ = client.send("DELETE", &format!("/issues/{}/labels/{}", number, encode(label)), json!({}));
+            }
+            if let Some(label) = label(config, &column.title) {
+                client.send("POST", &format!("/issues/{}/labels", number), json!({ "labels": [label] }))
+                    .with_context(|| format!("labelling {}", id))?;
+            }
+            state.columns.insert(id, column.title.clone());
+            synced.pushed += 1;
+        }
+    }
+    Ok(())
+}
+
+fn pull(board: &mut Board, config: &GithubConfig, client: &Client, issues: &[Issue], target: usize, state: &mut State, synced: &mut Synced) {
+    for issue in issues {
+        let source = Source { system: SYSTEM.to_string(), id: client.id(issue.number), url: Some(issue.html_url.clone()) };
+        let description = issue.body.clone().unwrap_or_default();
+        let existing = board.columns.iter_mut().flat_map(|c| c.tasks.iter_mut()).find(|t| t.source.as_ref().is_some_and(|s| s.system == SYSTEM && s.id == source.id));
+        match existing {
+            Some(task) => {
+                if task.title != issue.title || task.description != description || task.source.as_ref() != Some(&source) {
+                    (task.title, task.description, task.source) = (issue.title.clone(), description, Some(source));
+                    synced.updated += 1;
+                }
+            }
+            None => {
+                let mut task = Task::new(&issue.title, &description);
+                state.columns.insert(source.id.clone(), board.columns[target].title.clone());
+                task.source = Some(source);
+                board.columns[target].tasks.push(task);
+                synced.added += 1;
+            }
+        }
+    }
+
+    // Known issues no longer open were closed on GitHub
+    let Some(done) = board.columns.iter().position(|c| closes(config, &c.title)) else { return };
+    for c in 0..board.columns.len() {
+        if closes(config, &board.columns[c].title) {
+            continue;
+        }
+        let (closed, kept): (Vec<Task>, Vec<Task>) = std::mem::take(&mut board.columns[c].tasks).into_iter().partition(|t| {
+            client.issue_of(t).is_some_and(|(_, number)| !issues.iter().any(|i| i.number == number))
+        });
+        board.columns[c].tasks = kept;
+        for mut task in closed {
+            task.enter_column();
+            if let Some((id, _)) = client.issue_of(&task) {
+                state.columns.insert(id, board.columns[done].title.clone());
+            }
+            board.columns[done].tasks.push(task);
+            synced.closed += 1;
+        }
+    }
+}
+
+fn closes(config: &GithubConfig, column: &str) -> bool {
+    config.close_columns.iter().any(|c| collate::eq(c, column))
+}
+
+fn label<'a>(config: &'a GithubConfig, column: &str) -> Option<&'a str> {
+    config.labels.iter().find(|(c, _)| collate::eq(c, column)).map(|(_, l)| l.as_str())
+}
+
+// Label names go into a URL path
+fn encode(s: &str) -> String {
+    s.bytes().map(|b| match b {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+        _ => format!("%{:02X}", b),
+    }).collect()
+}
+
+impl Client<'_> {
+    fn id(&self, number: u64) -> String {
+        format!("{}#{}", self.repo, number)
+    }
+
+    // (id, issue number) of a task synced from this repository
+    fn issue_of(&self, task: &Task) -> Option<(String, u64)> {
+        let source = task.source.as_ref().filter(|s| s.system == SYSTEM)?;
+        let (repo, number) = source.id.rsplit_once('#')?;
+        if repo != self.repo {
+            return None;
+        }
+        Some((source.id.clone(), number.parse().ok()?))
+    }
+
+    fn request(&self, method: &str, path: &str) -> ureq::Request {
+        ureq::request(method, &format!("{}/repos/{}{}", API, self.repo, path))
+            .set("Authorization", &format!("Bearer {}", self.token))
+            .set("Accept", "application/vnd.github+json")
+            .set("User-Agent", "kanban-cli")
+            .timeout(TIMEOUT)
+    }
+
+    fn open_issues(&self) -> Result<Vec<Issue>> {
+        let mut issues = Vec::new();
+        for page in 1.. {
+            let text = self.request("GET", &format!("/issues?state=open&per_page={}&page={}", PER_PAGE, page))
+                .call().with_context(|| format!("fetching issues of {}", self.repo))?
+                .into_string()?;
+            let batch: Vec<Issue> = serde_json::from_str(&text).context("reading the issue list")?;
+            let last = batch.len() < PER_PAGE;
+            issues.extend(batch.into_iter().filter(|i| i.pull_request.is_none()));
+            if last {
+                break;
+            }
+        }
+        Ok(issues)
+    }
+
+    fn send(&self, method: &str, path: &str, body: serde_json::Value) -> Result<()> {
+        self.request(method, path)
+            .set("Content-Type", "application/json")
+            .send_string(&body.to_string())?;
+        Ok(())
+    }
+}
+
+impl State {
+    fn load(file: &Path) -> Result<Self> {
+        let path = storage::sibling(file, STATE_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        serde_json::from_str(&fs::read_to_string(&path)?).with_context(|| format!("invalid {}", path.display()))
+    }
+
+    fn save(&self, file: &Path) -> Result<()> {
+        fs::write(storage::sibling(file, STATE_FILE), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
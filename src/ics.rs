@@ -0,0 +1,332 @@
+use crate::link;
+use crate::model::{Board, Source, Task, TaskContent};
+use crate::search;
+use crate::zone;
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use std::fmt::Write;
+use std::fs;
+use std::time::Duration;
+
+// iCalendar subscriptions: a column mirrors the events (and to-dos) of a feed as
+// read-only tasks due when the event starts. Tasks are matched to events by UID, so a
+// refresh updates them in place, wherever on the board they were moved.
+//
+// The other way, `render` writes the tasks that have a due date as to-dos for
+// calendar apps.
+
+pub const SYSTEM: &str = "ics"; // `Source::system` of subscribed tasks
+const TIMEOUT: Duration = Duration::from_secs(20);
+
+pub struct Event {
+    pub uid: String,
+    pub summary: String,
+    pub description: String,
+    pub url: Option<String>,
+    pub due: DateTime<Utc>,
+}
+
+#[derive(Debug, Default)]
+pub struct Refreshed {
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+}
+
+/// Reads a feed from an http(s) URL, a file:// URL or a local path.
+pub fn fetch(url: &str) -> Result<String> {
+    if url.starts_with("http://") || url.starts_with("https://") || url.starts_with("webcal://") {
+        let url = url.replacen("webcal://", "https://", 1);
+        let response = ureq::get(&url).timeout(TIMEOUT).call().with_context(|| format!("fetching {}", url))?;
+        return response.into_string().with_context(|| format!("reading {}", url));
+    }
+    let path = url.strip_prefix("file://").unwrap_or(url);
+    fs::read_to_string(path).with_context(|| format!("reading {}", path))
+}
+
+/// The events and to-dos of a feed that have a UID, a summary and a start or due time.
+pub fn parse(text: &str) -> Result<Vec<Event>> {
+    if !text.trim_start().starts_with("BEGIN:VCALENDAR") {
+        bail!("not an iCalendar feed");
+    }
+    let mut events = Vec::new();
+    let mut current: Option<Vec<(String, String, String)>> = None;
+    for line in unfold(text) {
+        let Some((key, value)) = line.split_once(':') else { continue };
+        // Parameters such as `;VALUE=DATE` or `;TZID=...` come before the colon
+        let (name, params) = key.split_once(';').unwrap_or((key, ""));
+        let name = name.to_ascii_uppercase();
+        match (name.as_str(), value) {
+            ("BEGIN", "VEVENT" | "VTODO") => current = Some(Vec::new()),
+            ("END", "VEVENT" | "VTODO") => {
+                if let Some(props) = current.take()
+                    && let Some(event) = event(&props) {
+                        events.push(event);
+                    }
+            }
+            _ => {
+                if let Some(props) = current.as_mut() {
+                    props.push((name, params.to_string(), unescape(value)));
+                }
+            }
+        }
+    }
+    Ok(events)
+}
+
+// Continuation lines start with a space or tab
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+fn unescape(value: &str) -> String {
+    value.replace("\\n", "\n").replace("\\N", "\n").replace("\\,", ",").replace("\\;", ";").replace("\\\\", "\\")
+}
+
+fn event(props: &[(String, String, String)]) -> Option<Event> {
+    let find = |name: &str| props.iter().find(|(n, _, _)| n == name);
+    let get = |name: &str| find(name).map(|(_, _, v)| v.clone());
+    Some(Event {
+        uid: get("UID")?,
+        summary: get("SUMMARY")?,
+        description: get("DESCRIPTION").unwrap_or_default(),
+        url: get("URL"),
+        due: find("DUE").or_else(|| find("DTSTART")).and_then(|(_, params, value)| due(params, value))?,
+    })
+}
+
+// `20261103` (all day), `20261103T170000Z` (UTC), `TZID=Europe/Berlin:20261103T170000`,
+// or `20261103T170000` (floating, taken in the configured zone)
+fn due(params: &str, value: &str) -> Option<DateTime<Utc>> {
+    if let Some(utc) = value.strip_suffix('Z') {
+        return NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok().map(|at| at.and_utc());
+    }
+    let Ok(at) = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S") else {
+        return NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok().map(zone::start_of_day);
+    };
+    let tz = params.split(';').find_map(|p| p.strip_prefix("TZID=")).and_then(|id| id.trim_matches('"').parse::<Tz>().ok());
+    Some(match tz {
+        Some(tz) => zone::resolve(&tz, at),
+        None => zone::utc(at),
+    })
+}
+
+/// Brings column `c` of `board` in line with `events`: new events are added
+/// there, known ones updated wherever they are on the board, and tasks of
+/// events that left the feed are removed from the column.
+fn sync(board: &mut Board, c: usize, events: &[Event]) -> Refreshed {
+    let mut refreshed = Refreshed::default();
+    for event in events {
+        let source = Source { system: SYSTEM.to_string(), id: event.uid.clone(), url: event.url.clone() };
+        let existing = board.columns.iter_mut().flat_map(|col| col.tasks.iter_mut())
+            .find(|t| t.source.as_ref().is_some_and(|s| s.system == SYSTEM && s.id == event.uid));
+        match existing {
+            Some(task) => {
+                let before = (task.title.clone(), task.description.clone(), task.due, task.source.clone());
+                task.title = event.summary.clone();
+                task.description = event.description.clone();
+                task.due = Some(event.due);
+                task.source = Some(source);
+                if before != (task.title.clone(), task.description.clone(), task.due, task.source.clone()) {
+                    refreshed.updated += 1;
+                }
+            }
+            None => {
+                let mut task = Task::new(&event.summary, &event.description);
+                task.due = Some(event.due);
+                task.source = Some(source);
+                board.columns[c].tasks.push(task);
+                refreshed.added += 1;
+            }
+        }
+    }
+
+    let tasks = &mut board.columns[c].tasks;
+    let before = tasks.len();
+    tasks.retain(|t| !t.source.as_ref().is_some_and(|s| s.system == SYSTEM && !events.iter().any(|e| e.uid == s.id)));
+    refreshed.removed = before - tasks.len();
+    refreshed
+}
+
+/// Refreshes every subscribed column in the tree, returning each column's title
+/// with its outcome; one failing feed doesn't stop the others.
+pub fn refresh_all(root: &mut Board) -> Vec<(String, Result<Refreshed>)> {
+    let fetched = fetch_all(root);
+    apply_all(root, fetched)
+}
+
+/// A subscribed column's feed, read but not applied yet.
+pub struct Fetched {
+    feed: Feed,
+    events: Result<Vec<Event>>,
+}
+
+/// Reads the feed of every subscribed column in `root`. This is the slow part
+/// of a refresh, so `kanban refresh` does it before taking the board lock.
+pub fn fetch_all(root: &Board) -> Vec<Fetched> {
+    let mut subscribed = Vec::new();
+    collect(root, &mut Vec::new(), &mut subscribed);
+    subscribed.into_iter()
+        .map(|feed| {
+            let events = fetch(&feed.2).and_then(|text| parse(&text));
+            Fetched { feed, events }
+        })
+        .collect()
+}
+
+/// Brings each column of `fetched` in line with its feed, as `refresh_all` does.
+/// A column that no longer follows the feed it was fetched for is left alone.
+pub fn apply_all(root: &mut Board, fetched: Vec<Fetched>) -> Vec<(String, Result<Refreshed>)> {
+    let mut results = Vec::new();
+    for Fetched { feed: (path, c, url), events } in fetched {
+        let outcome = events.and_then(|events| {
+            let board = root.board_at_mut(&path)
+                .filter(|b| b.columns.get(c).and_then(|col| col.subscription.as_ref()).is_some_and(|s| s.url == url))
+                .ok_or_else(|| anyhow!("the column no longer follows {}", url))?;
+            let refreshed = sync(board, c, &events);
+            if let Some(subscription) = board.columns[c].subscription.as_mut() {
+                subscription.refreshed_at = Some(Utc::now());
+            }
+            Ok(refreshed)
+        });
+        let title = root.board_at(&path).and_then(|b| b.columns.get(c)).map(|col| col.title.clone()).unwrap_or_default();
+        results.push((title, outcome));
+    }
+    results
+}
+
+// A subscribed column: path to its board, its index and the feed URL
+type Feed = (Vec<(usize, usize)>, usize, String);
+
+fn collect(board: &Board, path: &mut Vec<(usize, usize)>, out: &mut Vec<Feed>) {
+    for (c, column) in board.columns.iter().enumerate() {
+        if let Some(subscription) = &column.subscription {
+            out.push((path.clone(), c, subscription.url.clone()));
+        }
+        for (r, task) in column.tasks.iter().enumerate() {
+            if let Some(TaskContent::Board(sub)) = &task.content {
+                path.push((c, r));
+                collect(sub, path, out);
+                path.pop();
+            }
+        }
+    }
+}
+
+/// Subscribed tasks are overwritten on every refresh, so they aren't edited here.
+pub fn is_read_only(task: &Task) -> bool {
+    task.source.as_ref().is_some_and(|s| s.system == SYSTEM)
+}
+
+/// The title of the first subscribed task in `before` that `after` edited.
+/// Moving one, deleting it and unsubscribing its column (which makes it an
+/// ordinary task) are let through, and so is a refresh, which is how they change.
+pub fn read_only_change(before: &Board, after: &Board) -> Option<String> {
+    if refresh_times(before) != refresh_times(after) {
+        return None;
+    }
+    let mut changed = None;
+    search::walk_tasks(before, &mut |_, _, _, task| {
+        if changed.is_some() || !is_read_only(task) {
+            return;
+        }
+        let Some(now) = after.path_to(task.id).and_then(|p| after.task_at(&p)).filter(|t| is_read_only(t)) else { return };
+        if edited(task, now) {
+            changed = Some(task.title.clone());
+        }
+    });
+    changed
+}
+
+// Whether what a subscribed task shows of its event, or what the user might add
+// to it, differs
+fn edited(before: &Task, after: &Task) -> bool {
+    before.title != after.title || before.description != after.description || before.content != after.content
+        || before.due != after.due || before.reminders != after.reminders
+}
+
+// When the subscribed columns in the tree were last refreshed, earliest first
+fn refresh_times(root: &Board) -> Vec<Option<DateTime<Utc>>> {
+    let mut feeds = Vec::new();
+    collect(root, &mut Vec::new(), &mut feeds);
+    let mut times: Vec<_> = feeds.iter()
+        .filter_map(|(path, c, _)| root.board_at(path)?.columns[*c].subscription.as_ref().map(|s| s.refreshed_at))
+        .collect();
+    times.sort();
+    times
+}
+
+/// Every task under `board` with a due date as a VTODO, due at its time or, for a
+/// due without one, all day. `crumbs` are the breadcrumbs of `board` from the main
+/// board; each to-do's description starts with where the task is.
+pub fn render(board: &Board, crumbs: &[String]) -> String {
+    let mut out = String::new();
+    line(&mut out, "BEGIN:VCALENDAR");
+    line(&mut out, "VERSION:2.0");
+    line(&mut out, "PRODID:-//rust-kanban//kanban-cli//EN");
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    todos(&mut out, board, &mut crumbs.to_vec(), &stamp);
+    line(&mut out, "END:VCALENDAR");
+    out
+}
+
+fn todos(out: &mut String, board: &Board, crumbs: &mut Vec<String>, stamp: &str) {
+    let last = board.columns.len().saturating_sub(1);
+    for (c, column) in board.columns.iter().enumerate() {
+        for task in &column.tasks {
+            if let Some(due) = task.due {
+                let mut description = format!("{} > [{}]", crumbs.join(" > "), column.title);
+                if !task.description.trim().is_empty() {
+                    let _ = write!(description, "\n\n{}", task.description.trim_end());
+                }
+                line(out, "BEGIN:VTODO");
+                line(out, &format!("UID:{}@kanban-cli", task.id));
+                line(out, &format!("DTSTAMP:{}", stamp));
+                line(out, &format!("SUMMARY:{}", escape(&task.title)));
+                line(out, &format!("DESCRIPTION:{}", escape(&description)));
+                line(out, &if zone::has_time(due) {
+                    format!("DUE:{}", due.format("%Y%m%dT%H%M%SZ"))
+                } else {
+                    format!("DUE;VALUE=DATE:{}", zone::due_date(due).format("%Y%m%d"))
+                });
+                line(out, &format!("URL:{}", link::format(crumbs, &column.title, task.id)));
+                // The last column is taken as done, as everywhere else
+                if c == last && last > 0 {
+                    line(out, "STATUS:COMPLETED");
+                }
+                line(out, "END:VTODO");
+            }
+            if let Some(TaskContent::Board(sub)) = &task.content {
+                crumbs.push(task.title.clone());
+                todos(out, sub, crumbs, stamp);
+                crumbs.pop();
+            }
+        }
+    }
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace(';', "\\;").replace(',', "\\,").replace('\n', "\\n")
+}
+
+// Content lines end in CRLF and are folded after 75 bytes, without splitting a character
+fn line(out: &mut String, content: &str) {
+    let mut width = 0;
+    for ch in content.chars() {
+        if width + ch.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(ch);
+        width += ch.len_utf8();
+    }
+    out.push_str("\r\n");
+}
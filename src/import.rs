@@ -0,0 +1,282 @@
+use crate::collate;
+use crate::model::{Board, Column, Source, Task, TaskContent, TodoItem};
+use crate::todotxt;
+use crate::zone;
+use anyhow::{bail, Result};
+use std::fs;
+use std::path::Path;
+use uuid::Uuid;
+
+// Markdown notes pulled in as a board: the first `#` heading names it, every
+// heading below that starts a column, and top-level list items become tasks.
+// Indented items under a task are its checklist, other indented lines its
+// description, and `>` quotes its note. Ticked top-level items are finished
+// tasks and go to the Done column.
+//
+// A card remembers the file it came from as its source, so importing the same
+// file again replaces the card's title and content instead of adding a copy.
+
+pub const SYSTEM: &str = "file"; // `Source::system` of imported cards
+const FIRST_COLUMN: &str = "To Do"; // For items before any column heading
+const DONE_COLUMN: &str = "Done";
+
+/// Whether `path` is read as todo.txt rather than Markdown.
+pub fn is_todotxt(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e.eq_ignore_ascii_case("txt"))
+}
+
+/// The title and content of a card made from `path`: a board from Markdown or
+/// todo.txt, or with `list` a todo list from todo.txt.
+pub fn card(path: &Path, list: bool) -> Result<(String, TaskContent)> {
+    let name = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+    let text = fs::read_to_string(path)?;
+    let board = match (is_todotxt(path), list) {
+        (true, true) => return Ok((name, TaskContent::Todo(todotxt::items(&text)?))),
+        (true, false) => todotxt::board(&text, &name)?,
+        (false, true) => bail!("only todo.txt files can be imported as a todo list"),
+        (false, false) => markdown(&text, &name)?,
+    };
+    Ok((board.title.clone(), TaskContent::Board(board)))
+}
+
+/// The source of cards made from `path`: the file, by its full path when it can be resolved.
+pub fn source(path: &Path) -> Source {
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    Source { system: SYSTEM.to_string(), id: path.display().to_string(), url: None }
+}
+
+/// Puts a card made from the file `source` names on `board`. The card an earlier
+/// import of it made is updated wherever it was moved, keeping its id and
+/// dates; otherwise a new one goes at the bottom of column `c`, with the column's
+/// defaults. Returns the card's id and whether it was already there.
+pub fn place(board: &mut Board, c: usize, source: Source, title: &str, content: TaskContent) -> (Uuid, bool) {
+    let existing = board.columns.iter_mut().flat_map(|col| col.tasks.iter_mut()).find(|t| t.source.as_ref() == Some(&source));
+    if let Some(task) = existing {
+        task.title = title.to_string();
+        task.content = Some(content);
+        return (task.id, true);
+    }
+    let column = &mut board.columns[c];
+    let mut task = Task::new(title, "");
+    task.content = Some(content);
+    task.source = Some(source);
+    if let Some(defaults) = &column.defaults {
+        defaults.apply(&mut task, zone::today());
+    }
+    let id = task.id;
+    column.tasks.push(task);
+    (id, false)
+}
+
+/// Adds `new` todo items to `items`, ticking or unticking those already there by
+/// the same text, so a todo.txt file can be imported into a list again. Returns
+/// how many were added.
+pub fn merge_items(items: &mut Vec<TodoItem>, new: Vec<TodoItem>) -> usize {
+    let mut added = 0;
+    for item in new {
+        match items.iter_mut().find(|i| i.text == item.text) {
+            Some(known) => known.done = item.done,
+            None => {
+                items.push(item);
+                added += 1;
+            }
+        }
+    }
+    items.sort_by_key(|k| k.done);
+    added
+}
+
+/// "3 columns, 12 tasks" or "8 items", for import messages.
+pub fn summary(content: &TaskContent) -> String {
+    match content {
+        TaskContent::Board(board) => {
+            let tasks: usize = board.columns.iter().map(|c| c.tasks.len()).sum();
+            format!("{} columns, {} tasks", board.columns.len(), tasks)
+        }
+        TaskContent::Todo(items) => format!("{} items", items.len()),
+        TaskContent::Text(_) => "a note".to_string(),
+    }
+}
+
+/// Parses `text` into a board titled `fallback_title` unless it has a `#` heading.
+pub fn markdown(text: &str, fallback_title: &str) -> Result<Board> {
+    let mut title = None;
+    let mut description = Vec::new();
+    let mut columns: Vec<Column> = Vec::new();
+    let mut done = Vec::new();
+    let mut in_done = false; // The last task went to `done`
+
+    for line in text.lines() {
+        let indent = line.len() - line.trim_start().len() + line.chars().take_while(|&c| c == '\t').count() * 3;
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if let Some((level, heading)) = heading(trimmed) {
+            if level == 1 && title.is_none() && columns.is_empty() {
+                title = Some(heading.to_string());
+            } else {
+                columns.push(Column::new(heading));
+            }
+            in_done = false;
+            continue;
+        }
+
+        let preamble = columns.is_empty() && done.is_empty();
+        let current = if in_done { done.last_mut() } else { columns.last_mut().and_then(|c| c.tasks.last_mut()) };
+        match (list_item(trimmed), current) {
+            (Some((checked, text)), Some(task)) if indent >= 2 => {
+                let item = TodoItem { text: text.to_string(), done: checked == Some(true) };
+                match &mut task.content {
+                    Some(TaskContent::Todo(items)) => items.push(item),
+                    _ => task.content = Some(TaskContent::Todo(vec![item])),
+                }
+            }
+            (Some((checked, text)), _) => {
+                in_done = checked == Some(true);
+                let task = Task::new(text, "");
+                if in_done {
+                    done.push(task);
+                } else {
+                    if columns.is_empty() {
+                        columns.push(Column::new(FIRST_COLUMN));
+                    }
+                    columns.last_mut().expect("pushed above").tasks.push(task);
+                }
+            }
+            (None, Some(task)) if indent >= 2 => match trimmed.strip_prefix('>') {
+                Some(quote) => {
+                    let quote = quote.strip_prefix(' ').unwrap_or(quote);
+                    match &mut task.content {
+                        Some(TaskContent::Text(note)) => {
+                            note.push('\n');
+                            note.push_str(quote);
+                        }
+                        None => task.content = Some(TaskContent::Text(quote.to_string())),
+                        Some(_) => {}
+                    }
+                }
+                None => {
+                    if !task.description.is_empty() {
+                        task.description.push('\n');
+                    }
+                    task.description.push_str(trimmed);
+                }
+            },
+            // Prose between the title and the first column describes the board
+            (None, _) if preamble => description.push(trimmed),
+            (None, _) => {}
+        }
+    }
+
+    if !done.is_empty() {
+        let c = match columns.iter().position(|c| collate::eq(&c.title, DONE_COLUMN)) {
+            Some(c) => c,
+            None => {
+                columns.push(Column::new(DONE_COLUMN));
+                columns.len() - 1
+            }
+        };
+        columns[c].tasks.extend(done);
+    }
+    if columns.is_empty() {
+        bail!("no headings or list items to import");
+    }
+    Ok(Board {
+        title: title.unwrap_or_else(|| fallback_title.to_string()),
+        columns,
+        description: description.join("\n"),
+        frozen: false,
+        wide_cards: false,
+        archive: Vec::new(),
+        trash: Vec::new(),
+        lanes: None,
+    })
+}
+
+// `## Title` -> (2, "Title")
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    let text = line[level..].strip_prefix(' ')?.trim().trim_end_matches('#').trim_end();
+    (level > 0 && !text.is_empty()).then_some((level, text))
+}
+
+// `- [x] Text` -> (Some(true), "Text"); `* Text` or `1. Text` -> (None, "Text")
+fn list_item(line: &str) -> Option<(Option<bool>, &str)> {
+    let rest = line.strip_prefix(['-', '*', '+']).or_else(|| {
+        let digits = line.chars().take_while(char::is_ascii_digit).count();
+        line[digits..].strip_prefix(['.', ')']).filter(|_| digits > 0)
+    })?;
+    let rest = rest.strip_prefix(' ')?.trim_start();
+    let (checked, text) = match rest.get(..3) {
+        Some("[ ]") => (Some(false), &rest[3..]),
+        Some("[x]" | "[X]") => (Some(true), &rest[3..]),
+        _ => (None, rest),
+    };
+    let text = text.trim();
+    (!text.is_empty()).then_some((checked, text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn titles(column: &Column) -> Vec<&str> {
+        column.tasks.iter().map(|t| t.title.as_str()).collect()
+    }
+
+    #[test]
+    fn markdown_headings_become_columns() {
+        let text = "# Release\n\nEverything for 2.0\n\n## Backlog\n- Docs\n  Cover install\n  - [x] Outline\n  - [ ] Draft\n* Icons\n## Doing ##\n1. Website\n  > Keep it short\n  > and plain\n- [x] Changelog\n";
+        let board = markdown(text, "fallback").unwrap();
+        assert_eq!(board.title, "Release");
+        assert_eq!(board.description, "Everything for 2.0");
+        let columns: Vec<&str> = board.columns.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(columns, ["Backlog", "Doing", "Done"]);
+        assert_eq!(titles(&board.columns[0]), ["Docs", "Icons"]);
+        assert_eq!(titles(&board.columns[2]), ["Changelog"], "ticked items go to Done");
+
+        let docs = &board.columns[0].tasks[0];
+        assert_eq!(docs.description, "Cover install");
+        assert_eq!(docs.content, Some(TaskContent::Todo(vec![
+            TodoItem { text: "Outline".to_string(), done: true },
+            TodoItem { text: "Draft".to_string(), done: false },
+        ])));
+        assert_eq!(board.columns[1].tasks[0].content, Some(TaskContent::Text("Keep it short\nand plain".to_string())));
+    }
+
+    #[test]
+    fn markdown_without_headings() {
+        let board = markdown("- One\n- [x] Two\n- [ ] Three\n", "notes").unwrap();
+        assert_eq!(board.title, "notes");
+        assert_eq!(titles(&board.columns[0]), ["One", "Three"]);
+        assert_eq!(board.columns[0].title, FIRST_COLUMN);
+        assert_eq!(titles(&board.columns[1]), ["Two"]);
+
+        assert!(markdown("Just prose\n", "notes").is_err());
+        assert!(markdown("#hashtag\n-dash\n", "notes").is_err(), "needs a space after the marker");
+    }
+
+    #[test]
+    fn place_updates_the_card_it_made() {
+        let mut board = Board { columns: vec![Column::new("To Do"), Column::new("Done")], ..Default::default() };
+        let source = Source { system: SYSTEM.to_string(), id: "/notes.md".to_string(), url: None };
+        let (id, known) = place(&mut board, 0, source.clone(), "notes", TaskContent::Text("one".to_string()));
+        assert!(!known);
+
+        let card = board.columns[0].tasks.remove(0);
+        board.columns[1].tasks.push(card);
+        assert_eq!(place(&mut board, 0, source, "notes 2", TaskContent::Text("two".to_string())), (id, true));
+        assert!(board.columns[0].tasks.is_empty());
+        assert_eq!(board.columns[1].tasks[0].title, "notes 2");
+        assert_eq!(board.columns[1].tasks[0].content, Some(TaskContent::Text("two".to_string())));
+    }
+
+    #[test]
+    fn merge_items_ticks_known_items() {
+        let item = |text: &str, done| TodoItem { text: text.to_string(), done };
+        let mut items = vec![item("a", false), item("b", false)];
+        assert_eq!(merge_items(&mut items, vec![item("b", true), item("c", false)]), 1);
+        assert_eq!(items, [item("a", false), item("c", false), item("b", true)]);
+    }
+}
@@ -0,0 +1,116 @@
+use crate::batch::Operation;
+use crate::model::Source;
+use anyhow::{anyhow, bail, Result};
+
+// Jira's "Export issues > CSV" as batch operations: one `add` per issue, with
+// Summary as the title, Description as the description, each Labels value
+// appended as a `#tag`, and Status as the column. Statuses are mapped to columns
+// like any other import (see mapping.rs), and the issue key is the task's
+// source, so importing a newer export updates the same tasks.
+
+pub const SYSTEM: &str = "jira";
+
+/// One `add` operation per issue row of `text`.
+pub fn operations(text: &str) -> Result<Vec<Operation>> {
+    let mut rows = records(text.trim_start_matches('\u{feff}'))?.into_iter();
+    let header = rows.next().ok_or_else(|| anyhow!("empty CSV file"))?;
+    let find = |name: &str| header.iter().position(|h| h.trim().eq_ignore_ascii_case(name));
+    let summary = find("Summary").ok_or_else(|| anyhow!("no Summary column; is this a Jira CSV export?"))?;
+    let (key, status, description) = (find("Issue key"), find("Status"), find("Description"));
+    // Jira repeats the Labels header once per label
+    let labels: Vec<usize> = (0..header.len()).filter(|&i| header[i].trim().eq_ignore_ascii_case("Labels")).collect();
+
+    let mut ops = Vec::new();
+    for (n, row) in rows.enumerate() {
+        let field = |i: Option<usize>| i.and_then(|i| row.get(i)).map(|s| s.trim()).filter(|s| !s.is_empty());
+        let Some(title) = field(Some(summary)) else {
+            if row.iter().all(|f| f.trim().is_empty()) {
+                continue;
+            }
+            bail!("row {} has no Summary", n + 2);
+        };
+        let mut title = title.to_string();
+        for label in labels.iter().filter_map(|&i| field(Some(i))) {
+            title.push_str(&format!(" #{}", label.replace(' ', "-")));
+        }
+        ops.push(Operation::Add {
+            title,
+            description: field(description).unwrap_or_default().replace("\r\n", "\n"),
+            column: field(status).map(str::to_string),
+            board: None,
+            source: field(key).map(|key| Source { system: SYSTEM.to_string(), id: key.to_string(), url: None }),
+        });
+    }
+    if ops.is_empty() {
+        bail!("no issues in the CSV file");
+    }
+    Ok(ops)
+}
+
+// RFC 4180 records: quoted fields may hold commas, newlines and doubled quotes
+fn records(text: &str) -> Result<Vec<Vec<String>>> {
+    let (mut records, mut record, mut field) = (Vec::new(), Vec::new(), String::new());
+    let mut chars = text.chars().peekable();
+    let mut quoted = false;
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            ('"', true) => quoted = false,
+            ('"', false) if field.is_empty() => quoted = true,
+            (',', false) => record.push(std::mem::take(&mut field)),
+            ('\r', false) if chars.peek() == Some(&'\n') => {}
+            ('\n', false) => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            (c, _) => field.push(c),
+        }
+    }
+    if quoted {
+        bail!("unterminated quoted field");
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_follow_rfc_4180() {
+        let text = "a,\"b, c\",\"say \"\"hi\"\"\"\r\n\"two\nlines\",,x";
+        assert_eq!(records(text).unwrap(), vec![
+            vec!["a".to_string(), "b, c".to_string(), "say \"hi\"".to_string()],
+            vec!["two\nlines".to_string(), String::new(), "x".to_string()],
+        ]);
+        assert!(records("a,\"open").is_err());
+    }
+
+    #[test]
+    fn issues_become_adds() {
+        let csv = "\u{feff}Summary,Issue key,Status,Labels,Labels,Description\n\
+                   Fix login,WEB-1,In QA,auth,two words,\"Steps:\r\n1. log in\"\n\
+                   ,,,,,\n";
+        let ops = operations(csv).unwrap();
+        assert_eq!(ops.len(), 1, "blank rows are skipped");
+        let Operation::Add { title, description, column, source, .. } = &ops[0] else { panic!("not an add") };
+        assert_eq!(title, "Fix login #auth #two-words");
+        assert_eq!(description, "Steps:\n1. log in");
+        assert_eq!(column.as_deref(), Some("In QA"));
+        assert_eq!(source.as_ref().map(|s| (s.system.as_str(), s.id.as_str())), Some((SYSTEM, "WEB-1")));
+    }
+
+    #[test]
+    fn rejects_other_csv() {
+        assert!(operations("Name,Age\nAda,36\n").is_err());
+        assert!(operations("Summary,Status\n,Done\n").is_err(), "a row without a summary");
+        assert!(operations("Summary\n").is_err(), "no issues");
+    }
+}
@@ -0,0 +1,480 @@
+use crate::app::{Action, App, ColumnTasks, InputMode, QuickFilter};
+use crate::config::KeysConfig;
+use crate::editor::Edit;
+use crate::replace;
+use anyhow::{bail, Context, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
+
+// Turns key presses into actions. Each input mode has its own handler, so a new
+// mode is one more struct and one more `register` call rather than another arm
+// of a match in the event loop.
+
+// Second keys after a chord prefix, with what they do for the hint popup
+pub const CHORDS: &[(char, char, Action, &str)] = &[
+    ('g', 'd', Action::ToggleBoardNotes, "Show/hide board notes"),
+    ('g', 'D', Action::EditBoardNotes, "Edit board notes"),
+    ('g', 'c', Action::ToggleChecklist, "Checklist of all open todos"),
+    ('g', 'b', Action::ToggleBadgeMenu, "Choose card badges"),
+    ('g', '/', Action::BeginFind, "Search all boards"),
+    ('g', 'w', Action::ToggleWorkspaces, "Switch workspace"),
+    ('g', 'a', Action::ToggleAgendaFocus, "Pick a day on the agenda strip"),
+    ('g', 'u', Action::ToggleQuickFilter(QuickFilter::DueThisWeek), "Only tasks due this week"),
+    ('g', 'o', Action::ToggleQuickFilter(QuickFilter::Overdue), "Only overdue tasks"),
+    ('g', 'p', Action::ToggleQuickFilter(QuickFilter::HighPriority), "Only high-priority tasks (#pri-A)"),
+    ('g', 'A', Action::ToggleArchive, "Archived tasks of this board"),
+    ('g', 'x', Action::ToggleTrash, "Deleted tasks and columns of this board"),
+    ('g', 'n', Action::EditColumnDefaults, "Defaults for new tasks in this column"),
+];
+
+pub trait ModeHandler {
+    fn action(&self, app: &mut App, key: KeyEvent) -> Option<Action>;
+}
+
+pub struct Keymap {
+    modes: Vec<(InputMode, Box<dyn ModeHandler>)>,
+}
+
+impl Keymap {
+    pub fn new() -> Self {
+        Self::with_keys(&KeysConfig::new()).expect("NORMAL_KEYS parse")
+    }
+
+    /// Like `new`, with normal-mode keys rebound by the `[keys]` config.
+    pub fn with_keys(keys: &KeysConfig) -> Result<Self> {
+        let mut keymap = Self { modes: Vec::new() };
+        keymap.register(InputMode::Normal, NormalMode::new(keys)?);
+        for mode in [InputMode::Editing, InputMode::EditingColumn, InputMode::EditingBoardNotes, InputMode::EditingBlockReason, InputMode::EditingDescription, InputMode::Renaming, InputMode::EditingColumnDefaults] {
+            keymap.register(mode, EditMode);
+        }
+        keymap.register(InputMode::Command, CommandMode);
+        keymap.register(InputMode::Searching, SearchMode);
+        keymap.register(InputMode::Checklist, ChecklistMode);
+        keymap.register(InputMode::Results, ResultsMode);
+        keymap.register(InputMode::Archive, ArchiveMode);
+        keymap.register(InputMode::Trash, TrashMode);
+        keymap.register(InputMode::Workspaces, WorkspacesMode);
+        keymap.register(InputMode::Agenda, AgendaMode);
+        keymap.register(InputMode::Jump, JumpMode);
+        keymap.register(InputMode::EditingNote, NoteMode);
+        keymap.register(InputMode::Badges, BadgesMode);
+        keymap.register(InputMode::Replacing, ReplaceMode);
+        keymap.register(InputMode::Confirm, ConfirmMode);
+        keymap.register(InputMode::SelectType, SelectTypeMode);
+        Ok(keymap)
+    }
+
+    /// Sets the handler for `mode`, replacing any earlier one.
+    pub fn register(&mut self, mode: InputMode, handler: impl ModeHandler + 'static) {
+        self.modes.retain(|(m, _)| *m != mode);
+        self.modes.push((mode, Box::new(handler)));
+    }
+
+    pub fn action(&self, app: &mut App, key: KeyEvent) -> Option<Action> {
+        // Help is modal: `?` opens it from normal mode and any key closes it
+        if app.show_help || (key.code == KeyCode::Char('?') && app.input_mode == InputMode::Normal) {
+            return Some(Action::ToggleHelp);
+        }
+        let (_, handler) = self.modes.iter().find(|(m, _)| *m == app.input_mode)?;
+        handler.action(app, key)
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Normal-mode actions by their `[keys]` name, with the keys they have unless
+// the config rebinds them
+pub const NORMAL_KEYS: &[(&str, Action, &[&str])] = &[
+    ("quit", Action::Quit, &["q"]),
+    ("move_left", Action::MoveLeft, &["left", "h"]),
+    ("move_right", Action::MoveRight, &["right", "l"]),
+    ("move_up", Action::MoveUp, &["up", "k"]),
+    ("move_down", Action::MoveDown, &["down", "j"]),
+    ("lane_up", Action::MoveLane(-1), &["{"]),
+    ("lane_down", Action::MoveLane(1), &["}"]),
+    ("move_task_left", Action::MoveTaskLeft, &["shift+left", "H"]),
+    ("move_task_right", Action::MoveTaskRight, &["shift+right", "L"]),
+    ("drill_down", Action::DrillDown, &["enter"]),
+    ("go_back", Action::GoBack, &["backspace", "esc"]),
+    ("add_task", Action::EnterEditMode, &["a"]),
+    ("add_column", Action::EnterAddColumnMode, &["c"]),
+    ("rename", Action::EnterRenameMode, &["r"]),
+    ("rename_column", Action::EnterRenameColumnMode, &["R"]),
+    ("delete_column", Action::DeleteColumn, &["X"]),
+    ("move_column_left", Action::MoveColumn(-1), &["<"]),
+    ("move_column_right", Action::MoveColumn(1), &[">"]),
+    ("delete_task", Action::DeleteTask, &["d"]),
+    ("undo", Action::Undo, &["u"]),
+    ("redo", Action::Redo, &["ctrl+r"]),
+    ("save", Action::Save, &["ctrl+s"]),
+    ("search", Action::BeginSearch, &["/"]),
+    ("search_next", Action::SearchNext(1), &["n"]),
+    ("search_prev", Action::SearchNext(-1), &["N"]),
+    ("toggle_todo", Action::ToggleTodo, &["space"]),
+    ("command", Action::EnterCommandMode, &[":"]),
+    ("chord", Action::BeginChord('g'), &["g"]), // Then a second key from `CHORDS`
+    ("last_board", Action::ToggleLastBoard, &["`"]),
+    ("jump", Action::ToggleJump, &["f"]),
+    ("grow_todo", Action::ResizeTodo(10), &["+", "="]),
+    ("shrink_todo", Action::ResizeTodo(-10), &["-"]),
+    ("todo_merge", Action::ToggleTodoMerge, &["m"]),
+    ("snooze", Action::BeginSnooze, &["z"]),
+    ("show_snoozed", Action::ToggleSnoozed, &["Z"]),
+    ("wait", Action::BeginWait, &["w"]),
+    ("waiting_filter", Action::ToggleWaitingFilter, &["W"]),
+    ("due", Action::BeginDue, &["D"]),
+    ("details", Action::ToggleDetails, &["i"]),
+    ("edit_description", Action::EditDescription, &["I"]),
+    ("edit_external", Action::EditExternally, &["e"]),
+    ("archive", Action::ArchiveTask, &["A"]),
+    ("level_1", Action::JumpToLevel(0), &["alt+1"]),
+    ("level_2", Action::JumpToLevel(1), &["alt+2"]),
+    ("level_3", Action::JumpToLevel(2), &["alt+3"]),
+    ("level_4", Action::JumpToLevel(3), &["alt+4"]),
+    ("level_5", Action::JumpToLevel(4), &["alt+5"]),
+    ("level_6", Action::JumpToLevel(5), &["alt+6"]),
+    ("level_7", Action::JumpToLevel(6), &["alt+7"]),
+    ("level_8", Action::JumpToLevel(7), &["alt+8"]),
+    ("level_9", Action::JumpToLevel(8), &["alt+9"]),
+];
+
+// A key with its modifiers. Shift is folded into characters ('H', ':'), since
+// terminals disagree on whether to report it there.
+type Key = (KeyCode, KeyModifiers);
+
+fn key_of(event: KeyEvent) -> Key {
+    match event.code {
+        KeyCode::Char(_) => (event.code, event.modifiers - KeyModifiers::SHIFT),
+        code => (code, event.modifiers),
+    }
+}
+
+/// Parses a key as written in `[keys]`: "x", "X", "space", "shift+left", "ctrl+d".
+fn parse_key(spec: &str) -> Result<Key> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut rest = spec;
+    // A lone "+" is the plus key, not a modifier separator
+    while let Some((prefix, tail)) = rest.split_once('+').filter(|(_, tail)| !tail.is_empty()) {
+        modifiers |= match prefix.to_lowercase().as_str() {
+            "ctrl" => KeyModifiers::CONTROL,
+            "alt" => KeyModifiers::ALT,
+            "shift" => KeyModifiers::SHIFT,
+            _ => bail!("unknown modifier '{}' in key '{}'", prefix, spec),
+        };
+        rest = tail;
+    }
+    let mut chars = rest.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) => KeyCode::Char(c),
+        _ => match rest.to_lowercase().as_str() {
+            "space" => KeyCode::Char(' '),
+            "enter" => KeyCode::Enter,
+            "esc" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backspace" => KeyCode::Backspace,
+            "delete" => KeyCode::Delete,
+            "insert" => KeyCode::Insert,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            name => match name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                Some(n @ 1..=12) => KeyCode::F(n),
+                _ => bail!("unknown key '{}'", spec),
+            },
+        },
+    };
+    // "shift+h" means 'H', like pressing it would
+    if let KeyCode::Char(c) = code
+        && modifiers.contains(KeyModifiers::SHIFT) {
+            let upper = c.to_uppercase().next().unwrap_or(c);
+            return Ok(key_of(KeyEvent::new(KeyCode::Char(upper), modifiers)));
+        }
+    Ok(key_of(KeyEvent::new(code, modifiers)))
+}
+
+pub struct NormalMode {
+    keys: HashMap<Key, Action>,
+}
+
+impl NormalMode {
+    /// `NORMAL_KEYS`, with the actions named in `overrides` bound to its keys instead.
+    pub fn new(overrides: &KeysConfig) -> Result<Self> {
+        if let Some(name) = overrides.keys().find(|name| !NORMAL_KEYS.iter().any(|(n, _, _)| n == name)) {
+            bail!("unknown action '{}' in [keys]", name);
+        }
+        let mut keys = HashMap::new();
+        // Defaults first, so a rebound key takes over from the action that had it
+        for (name, action, defaults) in NORMAL_KEYS {
+            if !overrides.contains_key(*name) {
+                for spec in *defaults {
+                    keys.insert(parse_key(spec)?, action.clone());
+                }
+            }
+        }
+        for (name, action, _) in NORMAL_KEYS {
+            for spec in overrides.get(*name).into_iter().flatten() {
+                keys.insert(parse_key(spec).with_context(|| format!("in [keys] {}", name))?, action.clone());
+            }
+        }
+        Ok(Self { keys })
+    }
+}
+
+impl ModeHandler for NormalMode {
+    fn action(&self, app: &mut App, key: KeyEvent) -> Option<Action> {
+        // Second key of a chord (e.g. `g d`) takes priority over everything else
+        if let Some(prefix) = app.pending_key {
+            let chord = CHORDS.iter().find(|(p, k, _, _)| *p == prefix && key.code == KeyCode::Char(*k));
+            return Some(chord.map_or(Action::CancelChord, |(_, _, action, _)| action.clone()));
+        }
+        self.keys.get(&key_of(key)).cloned()
+    }
+}
+
+// Single-line text entry: task titles, column names, board notes, block reasons, descriptions, renames
+pub struct EditMode;
+
+impl ModeHandler for EditMode {
+    fn action(&self, _app: &mut App, key: KeyEvent) -> Option<Action> {
+        match key.code {
+            KeyCode::Enter => Some(Action::SubmitTask),
+            KeyCode::Up => Some(Action::SuggestionPrev),
+            KeyCode::Down => Some(Action::SuggestionNext),
+            KeyCode::Tab => Some(Action::AcceptSuggestion),
+            KeyCode::Esc => Some(Action::ExitEditMode),
+            KeyCode::Char(c) => Some(Action::InputChar(c)),
+            KeyCode::Backspace => Some(Action::InputBackspace),
+            _ => None,
+        }
+    }
+}
+
+// Multi-line note editing: Enter breaks the line, Esc or Ctrl+S saves and closes
+pub struct NoteMode;
+
+impl ModeHandler for NoteMode {
+    fn action(&self, _app: &mut App, key: KeyEvent) -> Option<Action> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let edit = match key.code {
+            KeyCode::Esc => return Some(Action::SubmitTask),
+            KeyCode::Char('s') if ctrl => return Some(Action::SubmitTask),
+            KeyCode::Char(c) => return Some(Action::InputChar(c)),
+            KeyCode::Backspace => return Some(Action::InputBackspace),
+            KeyCode::Enter => Edit::Newline,
+            KeyCode::Delete => Edit::Delete,
+            KeyCode::Left => Edit::Left,
+            KeyCode::Right => Edit::Right,
+            KeyCode::Up => Edit::Up,
+            KeyCode::Down => Edit::Down,
+            KeyCode::Home if ctrl => Edit::Top,
+            KeyCode::End if ctrl => Edit::Bottom,
+            KeyCode::Home => Edit::Home,
+            KeyCode::End => Edit::End,
+            KeyCode::PageUp => Edit::PageUp,
+            KeyCode::PageDown => Edit::PageDown,
+            _ => return None,
+        };
+        Some(Action::EditNote(edit))
+    }
+}
+
+pub struct CommandMode;
+
+impl ModeHandler for CommandMode {
+    fn action(&self, _app: &mut App, key: KeyEvent) -> Option<Action> {
+        match key.code {
+            KeyCode::Enter => Some(Action::SubmitTask),
+            KeyCode::Esc => Some(Action::ExitEditMode),
+            KeyCode::Up => Some(Action::HistoryPrev),
+            KeyCode::Down => Some(Action::HistoryNext),
+            KeyCode::Tab => Some(Action::CompleteCommand),
+            KeyCode::Char(c) => Some(Action::InputChar(c)),
+            KeyCode::Backspace => Some(Action::InputBackspace),
+            _ => None,
+        }
+    }
+}
+
+pub struct SearchMode;
+
+impl ModeHandler for SearchMode {
+    fn action(&self, _app: &mut App, key: KeyEvent) -> Option<Action> {
+        match key.code {
+            KeyCode::Enter => Some(Action::SubmitTask),
+            KeyCode::Esc => Some(Action::ExitEditMode),
+            KeyCode::Char(c) => Some(Action::InputChar(c)),
+            KeyCode::Backspace => Some(Action::InputBackspace),
+            _ => None,
+        }
+    }
+}
+
+pub struct ChecklistMode;
+
+impl ModeHandler for ChecklistMode {
+    fn action(&self, _app: &mut App, key: KeyEvent) -> Option<Action> {
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => Some(Action::MoveUp),
+            KeyCode::Down | KeyCode::Char('j') => Some(Action::MoveDown),
+            KeyCode::Char(' ') => Some(Action::ToggleTodo),
+            KeyCode::Enter => Some(Action::DrillDown),
+            KeyCode::Esc | KeyCode::Char('q') => Some(Action::ToggleChecklist),
+            _ => None,
+        }
+    }
+}
+
+pub struct ResultsMode;
+
+impl ModeHandler for ResultsMode {
+    fn action(&self, _app: &mut App, key: KeyEvent) -> Option<Action> {
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => Some(Action::MoveUp),
+            KeyCode::Down | KeyCode::Char('j') => Some(Action::MoveDown),
+            KeyCode::Enter => Some(Action::DrillDown),
+            KeyCode::Esc | KeyCode::Char('q') => Some(Action::CloseResults),
+            _ => None,
+        }
+    }
+}
+
+pub struct ArchiveMode;
+
+impl ModeHandler for ArchiveMode {
+    fn action(&self, _app: &mut App, key: KeyEvent) -> Option<Action> {
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => Some(Action::MoveUp),
+            KeyCode::Down | KeyCode::Char('j') => Some(Action::MoveDown),
+            KeyCode::Enter | KeyCode::Char('r') => Some(Action::DrillDown),
+            KeyCode::Esc | KeyCode::Char('q') => Some(Action::ToggleArchive),
+            _ => None,
+        }
+    }
+}
+
+pub struct TrashMode;
+
+impl ModeHandler for TrashMode {
+    fn action(&self, _app: &mut App, key: KeyEvent) -> Option<Action> {
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => Some(Action::MoveUp),
+            KeyCode::Down | KeyCode::Char('j') => Some(Action::MoveDown),
+            KeyCode::Enter | KeyCode::Char('r') => Some(Action::DrillDown),
+            KeyCode::Esc | KeyCode::Char('q') => Some(Action::ToggleTrash),
+            _ => None,
+        }
+    }
+}
+
+pub struct WorkspacesMode;
+
+impl ModeHandler for WorkspacesMode {
+    fn action(&self, _app: &mut App, key: KeyEvent) -> Option<Action> {
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => Some(Action::MoveUp),
+            KeyCode::Down | KeyCode::Char('j') => Some(Action::MoveDown),
+            KeyCode::Enter => Some(Action::DrillDown),
+            KeyCode::Esc | KeyCode::Char('q') => Some(Action::ToggleWorkspaces),
+            _ => None,
+        }
+    }
+}
+
+pub struct AgendaMode;
+
+impl ModeHandler for AgendaMode {
+    fn action(&self, _app: &mut App, key: KeyEvent) -> Option<Action> {
+        match key.code {
+            KeyCode::Left | KeyCode::Char('h') => Some(Action::MoveLeft),
+            KeyCode::Right | KeyCode::Char('l') => Some(Action::MoveRight),
+            KeyCode::Enter => Some(Action::DrillDown),
+            KeyCode::Esc | KeyCode::Char('q') => Some(Action::ToggleAgendaFocus),
+            _ => None,
+        }
+    }
+}
+
+pub struct JumpMode;
+
+impl ModeHandler for JumpMode {
+    fn action(&self, _app: &mut App, key: KeyEvent) -> Option<Action> {
+        match key.code {
+            KeyCode::Char(c) if c.is_ascii_lowercase() => Some(Action::JumpKey(c)),
+            _ => Some(Action::ToggleJump),
+        }
+    }
+}
+
+pub struct BadgesMode;
+
+impl ModeHandler for BadgesMode {
+    fn action(&self, _app: &mut App, key: KeyEvent) -> Option<Action> {
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => Some(Action::MoveUp),
+            KeyCode::Down | KeyCode::Char('j') => Some(Action::MoveDown),
+            KeyCode::Char('K') => Some(Action::MoveBadge(-1)),
+            KeyCode::Char('J') => Some(Action::MoveBadge(1)),
+            KeyCode::Char(' ') => Some(Action::ToggleTodo),
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter => Some(Action::ToggleBadgeMenu),
+            _ => None,
+        }
+    }
+}
+
+pub struct ReplaceMode;
+
+impl ModeHandler for ReplaceMode {
+    fn action(&self, _app: &mut App, key: KeyEvent) -> Option<Action> {
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => Some(Action::Replace(replace::Answer::Yes)),
+            KeyCode::Char('n') | KeyCode::Char('N') => Some(Action::Replace(replace::Answer::No)),
+            KeyCode::Char('a') | KeyCode::Char('A') => Some(Action::Replace(replace::Answer::All)),
+            KeyCode::Esc | KeyCode::Char('q') => Some(Action::Replace(replace::Answer::Stop)),
+            _ => None,
+        }
+    }
+}
+
+pub struct ConfirmMode;
+
+impl ModeHandler for ConfirmMode {
+    fn action(&self, app: &mut App, key: KeyEvent) -> Option<Action> {
+        // A column with tasks asks what to do with them instead of yes/no
+        if app.column_delete_neighbor().is_some() {
+            return match key.code {
+                KeyCode::Char('m') => Some(Action::ConfirmDeleteColumn(ColumnTasks::Move)),
+                KeyCode::Char('d') => Some(Action::ConfirmDeleteColumn(ColumnTasks::Drop)),
+                KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => Some(Action::ConfirmNo),
+                _ => None,
+            };
+        }
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => Some(Action::ConfirmYes),
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => Some(Action::ConfirmNo),
+            _ => None,
+        }
+    }
+}
+
+pub struct SelectTypeMode;
+
+impl ModeHandler for SelectTypeMode {
+    fn action(&self, _app: &mut App, key: KeyEvent) -> Option<Action> {
+        match key.code {
+            KeyCode::Char('b') => Some(Action::SelectBoard),
+            KeyCode::Char('t') => Some(Action::SelectTodo),
+            KeyCode::Char('n') => Some(Action::SelectText),
+            KeyCode::Esc => Some(Action::GoBack),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use crate::autocomplete;
+use crate::collate;
+use crate::model::Task;
+use serde::{Deserialize, Serialize};
+
+// Swimlanes split a board into rows that run across every column, one per
+// person, epic or whatever the board is keyed on, so it reads as a grid of
+// lanes by columns. Tasks with nothing to key on share a last row.
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum Lanes {
+    Assignee,            // The first @name in the title
+    Tag(Option<String>), // The first tag, or the first starting with this prefix ("epic-")
+    Field,               // The task's own `lane`, set with `:lane`
+}
+
+impl Lanes {
+    /// Reads "assignee", "tag" with an optional prefix, or "field"; "off" is None.
+    pub fn parse(s: &str) -> Result<Option<Self>, String> {
+        let mut words = s.split_whitespace();
+        let lanes = match words.next().map(str::to_lowercase).as_deref() {
+            Some("off") => None,
+            Some("assignee" | "who") => Some(Lanes::Assignee),
+            Some("tag") => Some(Lanes::Tag(words.next().map(|p| p.trim_start_matches('#').to_string()))),
+            Some("field" | "lane") => Some(Lanes::Field),
+            _ => return Err(format!("'{}' is not assignee, tag [prefix], field or off", s.trim())),
+        };
+        match words.next() {
+            Some(extra) => Err(format!("unexpected '{}'", extra)),
+            None => Ok(lanes),
+        }
+    }
+
+    /// The lane `task` goes in, as its row is headed; None for the last row.
+    pub fn of(&self, task: &Task) -> Option<String> {
+        match self {
+            Lanes::Assignee => task.title.split_whitespace().find(|w| w.len() > 1 && w.starts_with('@')).map(str::to_string),
+            Lanes::Tag(prefix) => {
+                let prefix = prefix.as_deref().map(collate::fold).unwrap_or_default();
+                autocomplete::tags_in(&task.title).find(|t| collate::fold(t).starts_with(&prefix)).map(|t| format!("#{}", t))
+            }
+            Lanes::Field => task.lane.clone(),
+        }
+    }
+}
+
+impl std::fmt::Display for Lanes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Lanes::Assignee => write!(f, "assignee"),
+            Lanes::Tag(None) => write!(f, "tag"),
+            Lanes::Tag(Some(prefix)) => write!(f, "tag {}", prefix),
+            Lanes::Field => write!(f, "field"),
+        }
+    }
+}
+
+/// The lanes `tasks` fall into, in the order the rows show: by name, and the
+/// row without a lane last. Names that differ only in case or accents are one lane.
+pub fn order<'a>(lanes: &Lanes, tasks: impl Iterator<Item = &'a Task>) -> Vec<Option<String>> {
+    let mut found: Vec<Option<String>> = Vec::new();
+    for lane in tasks.map(|t| lanes.of(t)) {
+        if position(&found, &lane).is_none() {
+            found.push(lane);
+        }
+    }
+    found.sort_by(|a, b| match (a, b) {
+        (Some(a), Some(b)) => collate::cmp(a, b),
+        (a, b) => a.is_none().cmp(&b.is_none()),
+    });
+    found
+}
+
+/// Where `lane` is in `order`.
+pub fn position(order: &[Option<String>], lane: &Option<String>) -> Option<usize> {
+    order.iter().position(|l| match (l, lane) {
+        (Some(a), Some(b)) => collate::eq(a, b),
+        (a, b) => a.is_none() && b.is_none(),
+    })
+}
@@ -0,0 +1,62 @@
+//! Hierarchical Kanban boards: tasks can hold boards of their own, todo lists or notes.
+//!
+//! The terminal UI ([`tui`], [`ui`]) is one frontend over this crate; another can reuse
+//! everything but the drawing:
+//!
+//! - [`app::App`] holds the open workspace and where the user is, and changes only
+//!   through [`app::App::update`] with an [`app::Action`]. Saving, undo, sorting and
+//!   the like happen there, whatever produced the action.
+//! - [`view`] turns the app into what to show: breadcrumbs, columns and cards with
+//!   their badges, already filtered.
+//! - [`keymap::Keymap`] maps terminal keys to actions; frontends with their own
+//!   input build `Action`s directly.
+//! - [`model`] is the data itself, and [`storage`] reads and writes `kanban.db`.
+//!
+//! `examples/plain.rs` is a line-based frontend built this way.
+
+mod advisor;
+pub mod agenda;
+pub mod app;
+mod autocomplete;
+mod backup;
+mod batch;
+mod bulk;
+pub mod cli;
+pub mod collate;
+mod command;
+pub mod config;
+mod date;
+mod defaults;
+pub mod editor;
+pub mod demo;
+mod export;
+mod github;
+mod ics;
+mod import;
+mod jira;
+pub mod keymap;
+mod lanes;
+mod link;
+mod lint;
+mod mapping;
+mod markdown;
+pub mod model;
+mod planner;
+mod reminder;
+mod replace;
+mod reset;
+pub mod search;
+mod session;
+mod sort;
+pub mod stats;
+pub mod storage;
+mod stress;
+mod template;
+pub mod theme;
+mod todotxt;
+pub mod tui;
+mod undo;
+pub mod ui;
+mod validate;
+pub mod view;
+pub mod zone;
@@ -0,0 +1,94 @@
+use crate::collate;
+use crate::model::Board;
+use crate::search;
+use anyhow::{bail, Result};
+use uuid::Uuid;
+
+// `kanban://<board>/<sub-board>/.../<column>/<short id>` addresses a task from
+// outside the app (notes, exports). The short id decides; board and column
+// names only break ties between tasks sharing an id prefix.
+
+pub const SCHEME: &str = "kanban://";
+const SHORT_ID_LEN: usize = 8;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Link {
+    pub boards: Vec<String>, // Breadcrumbs, starting at the main board
+    pub column: String,
+    pub short_id: String,
+}
+
+pub fn format(crumbs: &[String], column: &str, id: Uuid) -> String {
+    let mut link = SCHEME.to_string();
+    for segment in crumbs.iter().map(String::as_str).chain([column]) {
+        link.push_str(&encode(segment));
+        link.push('/');
+    }
+    link.push_str(&id.to_string()[..SHORT_ID_LEN]);
+    link
+}
+
+pub fn parse(s: &str) -> Option<Link> {
+    let rest = s.trim().strip_prefix(SCHEME)?;
+    let mut segments: Vec<String> = rest.trim_end_matches('/').split('/').map(decode).collect::<Option<_>>()?;
+    let short_id = segments.pop().filter(|id| !id.is_empty())?.to_lowercase();
+    let column = segments.pop()?;
+    if segments.is_empty() {
+        return None;
+    }
+    Some(Link { boards: segments, column, short_id })
+}
+
+/// Path to the linked task.
+pub fn resolve(root: &Board, link: &Link) -> Result<Vec<(usize, usize)>> {
+    let mut found = Vec::new();
+    search::walk_tasks(root, &mut |crumbs, location, column, task| {
+        if task.id.to_string().starts_with(&link.short_id) {
+            let exact = crumbs == link.boards.as_slice() && collate::eq(&column.title, &link.column);
+            found.push((location.to_vec(), exact));
+        }
+    });
+    if found.len() > 1 {
+        found.retain(|(_, exact)| *exact);
+    }
+    match found.len() {
+        0 => bail!("no task matches {}{}", SCHEME, link.short_id),
+        1 => Ok(found.remove(0).0),
+        n => bail!("link matches {} tasks", n),
+    }
+}
+
+// Only what would break the path or the surrounding text is escaped
+fn encode(segment: &str) -> String {
+    let mut out = String::with_capacity(segment.len());
+    for ch in segment.chars() {
+        match ch {
+            '%' | '/' | '?' | '#' | '(' | ')' | '<' | '>' | '[' | ']' => out.push_str(&format!("%{:02X}", ch as u32)),
+            c if c.is_whitespace() || c.is_control() => {
+                let mut buf = [0; 4];
+                for b in c.encode_utf8(&mut buf).bytes() {
+                    out.push_str(&format!("%{:02X}", b));
+                }
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn decode(segment: &str) -> Option<String> {
+    let bytes = segment.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = segment.get(i + 1..i + 3)?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
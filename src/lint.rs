@@ -0,0 +1,94 @@
+use crate::autocomplete;
+use crate::collate;
+use crate::config::{LintConfig, MustHave};
+use crate::model::{Board, Task, TaskContent};
+use crate::zone;
+use chrono::{DateTime, Utc};
+
+// Policy checks for `kanban lint`, for teams that keep their board in git and
+// want CI to hold it to the rules in `[lint]`. Unlike the advisor's nudges,
+// every offending task is reported on its own.
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Problem {
+    pub breadcrumbs: Vec<String>, // From the main board down to the board with the problem
+    pub column: String,
+    pub task: Option<String>, // None when the column itself breaks a rule
+    pub what: String,
+}
+
+impl std::fmt::Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} > [{}]", self.breadcrumbs.join(" > "), self.column)?;
+        if let Some(task) = &self.task {
+            write!(f, " {}", task)?;
+        }
+        write!(f, ": {}", self.what)
+    }
+}
+
+/// Everything in the tree under `root` that breaks a rule of `config`, board by board.
+pub fn check(root: &Board, config: &LintConfig, now: DateTime<Utc>) -> Vec<Problem> {
+    let mut found = Vec::new();
+    check_board(root, config, now, &mut vec![root.title.clone()], &mut found);
+    found
+}
+
+fn check_board(board: &Board, config: &LintConfig, now: DateTime<Utc>, crumbs: &mut Vec<String>, found: &mut Vec<Problem>) {
+    let today = zone::local(now).date();
+    let last = board.columns.len().saturating_sub(1);
+    for (c, column) in board.columns.iter().enumerate() {
+        let mut report = |task: Option<&Task>, what: String| found.push(Problem {
+            breadcrumbs: crumbs.clone(),
+            column: column.title.clone(),
+            task: task.map(|t| format!("{} ({})", t.title, &t.id.to_string()[..8])),
+            what,
+        });
+        if config.wip && let Some(limit) = column.wip_limit.filter(|_| column.over_wip_limit()) {
+            report(None, format!("{} tasks, over its WIP limit of {}", column.tasks.len(), limit));
+        }
+        let requirements: Vec<&MustHave> = config.require.iter()
+            .filter(|r| collate::eq(&r.column, &column.title))
+            .map(|r| &r.has)
+            .collect();
+        for task in &column.tasks {
+            // Parked and finished tasks may sit as long as they like, and so may snoozed ones
+            let days = task.days_in_column(now);
+            if config.max_days > 0 && c != last && !column.icebox && !task.is_snoozed(today) && days > config.max_days {
+                report(Some(task), format!("{} days in the column, {} at most", days, config.max_days));
+            }
+            for has in &requirements {
+                if let Some(missing) = missing(task, has) {
+                    report(Some(task), missing);
+                }
+            }
+        }
+        for task in &column.tasks {
+            if let Some(TaskContent::Board(sub)) = &task.content {
+                crumbs.push(task.title.clone());
+                check_board(sub, config, now, crumbs, found);
+                crumbs.pop();
+            }
+        }
+    }
+}
+
+// What `task` lacks of `has`, if anything
+fn missing(task: &Task, has: &MustHave) -> Option<String> {
+    let ok = match has {
+        MustHave::Assignee => task.title.split_whitespace().any(|w| w.len() > 1 && w.starts_with('@')),
+        MustHave::Due => task.due.is_some(),
+        MustHave::Description => !task.description.trim().is_empty(),
+        MustHave::Tag(None) => autocomplete::tags_in(&task.title).next().is_some(),
+        MustHave::Tag(Some(tag)) => autocomplete::tags_in(&task.title).any(|t| collate::eq(t, tag)),
+        MustHave::Completed => task.entered_column_at > task.created_at,
+    };
+    (!ok).then(|| match has {
+        MustHave::Assignee => "no assignee (an @name in the title)".to_string(),
+        MustHave::Due => "no due date".to_string(),
+        MustHave::Description => "no description".to_string(),
+        MustHave::Tag(None) => "no tag".to_string(),
+        MustHave::Tag(Some(tag)) => format!("not tagged #{}", tag),
+        MustHave::Completed => "no completion date (created here rather than moved in)".to_string(),
+    })
+}
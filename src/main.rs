@@ -1,116 +1,44 @@
-use std::io;
-use anyhow::Result;
-use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, KeyModifiers},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
-use ratatui::{backend::CrosstermBackend, Terminal};
+use anyhow::{Context, Result};
+use clap::Parser;
+use kanban_cli::app::App;
+use kanban_cli::{cli, config, demo, model, theme, tui, zone};
 
-mod app;
-mod model;
-mod ui;
-
-use app::{App, Action, InputMode};
+// Set to a number to make generated ids reproducible (tests, golden exports)
+const SEED_ENV: &str = "KANBAN_SEED";
 
 fn main() -> Result<()> {
-    // Setup terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
-
-    // Create app
-    let mut app = App::new()?; 
-    let res = run_app(&mut terminal, &mut app);
-
-    // Restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
-
-    if let Err(err) = res {
-        println!("{:?}", err);
+    if let Ok(seed) = std::env::var(SEED_ENV) {
+        let seed = seed.parse().with_context(|| format!("{} must be an unsigned integer", SEED_ENV))?;
+        model::seed_ids(seed);
     }
 
-    Ok(())
-}
-
-fn run_app<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()> {
-    loop {
-        terminal.draw(|f| ui::draw(f, app))?;
-
-        if let Event::Key(key) = event::read()? {
-            if key.kind == KeyEventKind::Press {
-                if key.code == KeyCode::Char('?') && app.input_mode != InputMode::Editing {
-                    app.update(Action::ToggleHelp)?;
-                    continue;
-                }
-                
-                if app.show_help {
-                     app.update(Action::ToggleHelp)?;
-                     continue;
-                }
-
-                let action = match app.input_mode {
-                    InputMode::Normal => {
-                        // Check for Shift modifier FIRST
-                        if key.modifiers.contains(KeyModifiers::SHIFT) {
-                            match key.code {
-                                KeyCode::Left | KeyCode::Char('H') => Some(Action::MoveTaskLeft),
-                                KeyCode::Right | KeyCode::Char('L') => Some(Action::MoveTaskRight),
-                                _ => None,
-                            }
-                        } else {
-                            match key.code {
-                                KeyCode::Char('q') => Some(Action::Quit),
-                                KeyCode::Left | KeyCode::Char('h') => Some(Action::MoveLeft),
-                                KeyCode::Right | KeyCode::Char('l') => Some(Action::MoveRight),
-                                KeyCode::Up | KeyCode::Char('k') => Some(Action::MoveUp),
-                                KeyCode::Down | KeyCode::Char('j') => Some(Action::MoveDown),
-                                KeyCode::Enter => Some(Action::DrillDown),
-                                KeyCode::Backspace | KeyCode::Esc => Some(Action::GoBack),
-                                KeyCode::Char('a') => Some(Action::EnterEditMode),
-                                KeyCode::Char('c') => Some(Action::EnterAddColumnMode),
-                                KeyCode::Char('d') => Some(Action::DeleteTask),
-                                KeyCode::Char(' ') => Some(Action::ToggleTodo),
-                                
-                                // Alternative shift bindings if terminal swallows modifiers for arrows (sometimes tricky)
-                                KeyCode::Char('H') => Some(Action::MoveTaskLeft), // Shift+h
-                                KeyCode::Char('L') => Some(Action::MoveTaskRight), // Shift+l
-                                _ => None,
-                            }
-                        }
-                    },
-                    InputMode::Editing | InputMode::EditingColumn => match key.code {
-                        KeyCode::Enter => Some(Action::SubmitTask),
-                        KeyCode::Esc => Some(Action::ExitEditMode),
-                        KeyCode::Char(c) => Some(Action::InputChar(c)),
-                        KeyCode::Backspace => Some(Action::InputBackspace),
-                        _ => None,
-                    },
-                    InputMode::SelectType => match key.code {
-                        KeyCode::Char('b') => Some(Action::SelectBoard),
-                        KeyCode::Char('t') => Some(Action::SelectTodo),
-                        KeyCode::Char('n') => Some(Action::SelectText),
-                        KeyCode::Esc => Some(Action::GoBack),
-                        _ => None,
-                    },
-                };
-
-                if let Some(action) = action {
-                    app.update(action)?;
-                }
-            }
-        }
+    let args = cli::Cli::parse();
+    // Subcommands and the TUI alike count days in the configured zone and
+    // start new boards with the configured columns
+    let config = config::Config::load()?;
+    zone::init(config.time.zone.as_deref())?;
+    model::set_default_columns(config.board.columns.clone());
+    let mut ui = config.ui.clone();
+    ui.monochrome |= args.no_color;
+    theme::init(ui);
+    let focus = match args.command {
+        Some(cli::CliCommand::Open { link, id }) => link.or(id),
+        Some(cmd) => return cli::run(cmd, &config),
+        None => None,
+    };
+
+    // Create app before touching the terminal so load errors print normally
+    let (mut app, script) = if args.demo { (App::demo(), demo::script()) } else { (App::new()?, Vec::new()) };
+    if let Some(id) = focus {
+        app.reveal_task(&id)?;
+    }
+    if let Some(file) = args.frame {
+        return tui::render_frame(&mut app, script, args.size, &file);
+    }
 
-        if app.should_quit {
-            return Ok(());
-        }
+    if let Err(err) = tui::run(&mut app, script) {
+        println!("{:?}", err);
+        return Ok(());
     }
+    app.finish_session()
 }
@@ -0,0 +1,128 @@
+use crate::batch::Operation;
+use crate::cli;
+use crate::collate;
+use crate::model::Board;
+use crate::storage;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+// Lists/statuses of imported data that don't match a local column are mapped
+// once, by asking, and the answer is kept per source for the next import.
+
+const MAPPINGS_FILE: &str = "mappings.json"; // Beside the board file, see `storage::sibling`
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct Mappings {
+    sources: BTreeMap<String, BTreeMap<String, String>>, // Source -> list name -> local column
+    #[serde(skip)]
+    changed: bool,
+}
+
+/// Where tasks of an unknown list should go.
+pub enum Choice {
+    Column(String), // An existing column, by title
+    Create,         // A new column named like the list
+}
+
+/// Asked for each unknown list: (source, list, board title, its columns).
+pub type Ask<'a> = dyn FnMut(&str, &str, &str, &[String]) -> Result<Choice> + 'a;
+
+impl Mappings {
+    /// The mappings kept beside board file `file`.
+    pub fn load(file: &Path) -> Result<Self> {
+        let path = storage::sibling(file, MAPPINGS_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        serde_json::from_str(&fs::read_to_string(&path)?).with_context(|| format!("invalid {}", path.display()))
+    }
+
+    /// Writes the mappings back beside `file` if any changed since `load`.
+    pub fn save(&self, file: &Path) -> Result<()> {
+        if !self.changed {
+            return Ok(());
+        }
+        fs::write(storage::sibling(file, MAPPINGS_FILE), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    fn get(&self, source: &str, list: &str) -> Option<&str> {
+        self.sources.get(source)?.get(&list.to_lowercase()).map(String::as_str)
+    }
+
+    fn set(&mut self, source: &str, list: &str, column: &str) {
+        let old = self.sources.entry(source.to_string()).or_default().insert(list.to_lowercase(), column.to_string());
+        self.changed |= old.as_deref() != Some(column);
+    }
+}
+
+/// Rewrites the columns of `add`/`move` operations that name no column of their
+/// board (or several) to the mapped one, asking when there is no usable mapping.
+/// New columns are created by `column` operations inserted before first use.
+/// `source` is used for operations that don't name their own.
+pub fn map_columns(root: &Board, ops: Vec<Operation>, source: &str, mappings: &mut Mappings, ask: &mut Ask) -> Result<Vec<Operation>> {
+    let mut mapped = Vec::with_capacity(ops.len());
+    let mut created = HashSet::new(); // (board path, lowercase title) of columns added here
+
+    for mut op in ops {
+        let (column, board_name, op_source) = match &mut op {
+            Operation::Add { column: Some(column), board, source: op_source, .. } => (column, board.clone(), op_source.as_ref().map(|s| s.system.clone())),
+            // Tasks created earlier in the same batch can't be looked up yet; they are left as written
+            Operation::Move { task, column, .. } => match cli::resolve_task(root, task) {
+                Ok(path) => {
+                    let parent = &path[..path.len() - 1];
+                    let board = (!parent.is_empty()).then(|| root.task_at(parent).map(|t| t.id.to_string())).flatten();
+                    (column, board, None)
+                }
+                Err(_) => {
+                    mapped.push(op);
+                    continue;
+                }
+            },
+            _ => {
+                mapped.push(op);
+                continue;
+            }
+        };
+        let source = op_source.as_deref().unwrap_or(source);
+        let path = match &board_name {
+            Some(name) => cli::resolve_board(root, name)?,
+            None => Vec::new(),
+        };
+        let Some(board) = root.board_at(&path) else {
+            mapped.push(op);
+            continue;
+        };
+        let known = |name: &str| board.match_column(name).is_ok() || created.contains(&(path.clone(), collate::fold(name)));
+        if known(column) {
+            mapped.push(op);
+            continue;
+        }
+
+        let choice = match mappings.get(source, column) {
+            Some(target) if known(target) => Choice::Column(target.to_string()),
+            // Mapped to a new column that is missing here, e.g. after a --dry-run
+            Some(target) if collate::eq(target, column) => Choice::Create,
+            // No mapping yet, or the column it pointed to is gone
+            _ => {
+                let titles: Vec<String> = board.columns.iter().map(|c| c.title.clone()).collect();
+                ask(source, column, &board.title, &titles)?
+            }
+        };
+        let target = match choice {
+            Choice::Column(title) => title,
+            Choice::Create => {
+                created.insert((path.clone(), collate::fold(column)));
+                mapped.push(Operation::Column { title: column.clone(), board: board_name.clone() });
+                column.clone()
+            }
+        };
+        mappings.set(source, column, &target);
+        *column = target;
+        mapped.push(op);
+    }
+    Ok(mapped)
+}
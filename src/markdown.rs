@@ -0,0 +1,134 @@
+use crate::theme::{self, colors};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+
+// Notes drawn as styled Markdown: headings, lists, quotes, code and the common
+// inline marks. Only the look changes; markers that carry meaning (list
+// bullets, checkboxes) stay, the rest are dropped.
+
+/// `text` as lines for a view `width` cells wide, which rules span.
+pub fn lines(text: &str, width: u16) -> Vec<Line<'static>> {
+    let mut out = Vec::new();
+    let mut in_code = false;
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            out.push(Line::styled(format!("  {}", line), Style::default().fg(colors().accent)));
+            continue;
+        }
+        out.push(block(line, width));
+    }
+    out
+}
+
+fn block(line: &str, width: u16) -> Line<'static> {
+    let indent = &line[..line.len() - line.trim_start().len()];
+    let trimmed = line.trim();
+    if let Some((level, title)) = heading(trimmed) {
+        let mut style = Style::default().fg(colors().heading).add_modifier(Modifier::BOLD);
+        if level == 1 {
+            style = style.add_modifier(Modifier::UNDERLINED);
+        }
+        return Line::from(inline(title, style));
+    }
+    if is_rule(trimmed) {
+        return Line::styled("─".repeat(width.into()), Style::default().fg(colors().dim));
+    }
+    if let Some(quote) = trimmed.strip_prefix('>') {
+        let mut spans = vec![Span::styled(format!("{}│ ", indent), Style::default().fg(colors().dim))];
+        spans.extend(inline(quote.trim_start(), Style::default().fg(colors().muted).add_modifier(Modifier::ITALIC)));
+        return Line::from(spans);
+    }
+    if let Some((marker, rest)) = list_marker(trimmed) {
+        let mut spans = vec![Span::styled(format!("{}{} ", indent, marker), Style::default().fg(colors().accent))];
+        spans.extend(inline(rest, Style::default()));
+        return Line::from(spans);
+    }
+    let mut spans = vec![Span::raw(indent.to_string())];
+    spans.extend(inline(trimmed, Style::default()));
+    Line::from(spans)
+}
+
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    let title = line[level..].strip_prefix(' ')?;
+    (1..=6).contains(&level).then(|| (level, title.trim_end_matches('#').trim()))
+}
+
+fn is_rule(line: &str) -> bool {
+    let Some(first) = line.chars().next() else { return false };
+    matches!(first, '-' | '*' | '_') && line.chars().filter(|&c| c != ' ').all(|c| c == first) && line.matches(first).count() >= 3
+}
+
+// The bullet, number or checkbox to show for a list item, and the item's text
+fn list_marker(line: &str) -> Option<(String, &str)> {
+    if let Some(rest) = ["- ", "* ", "+ "].iter().find_map(|b| line.strip_prefix(b)) {
+        return Some(match rest.get(..4) {
+            Some("[ ] ") => (theme::icon("☐", "[ ]").to_string(), &rest[4..]),
+            Some("[x] " | "[X] ") => (theme::icon("☑", "[x]").to_string(), &rest[4..]),
+            _ => (theme::icon("•", "-").to_string(), rest),
+        });
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    let rest = line[digits..].strip_prefix(". ").or_else(|| line[digits..].strip_prefix(") "))?;
+    (digits > 0).then(|| (line[..digits + 1].to_string(), rest))
+}
+
+// Code spans, bold, italics, strikethrough and links within one line, on top of `base`
+fn inline(text: &str, base: Style) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let after_word = plain.chars().last().is_some_and(char::is_alphanumeric);
+        let styled = match c {
+            '`' => closed(rest, "`").map(|(code, len)| (vec![Span::styled(code.to_string(), base.fg(colors().accent))], len)),
+            '*' | '_' if rest.starts_with("**") || rest.starts_with("__") => closed(rest, &rest[..2])
+                .map(|(inner, len)| (inline(inner, base.add_modifier(Modifier::BOLD)), len)),
+            '~' if rest.starts_with("~~") => closed(rest, "~~")
+                .map(|(inner, len)| (inline(inner, base.add_modifier(Modifier::CROSSED_OUT)), len)),
+            // `_` inside a word is part of it, as in snake_case
+            '*' | '_' if !(c == '_' && after_word) => closed(rest, &rest[..1])
+                .filter(|(inner, _)| !inner.starts_with(' '))
+                .map(|(inner, len)| (inline(inner, base.add_modifier(Modifier::ITALIC)), len)),
+            '[' => link(rest).map(|(label, len)| (inline(label, base.add_modifier(Modifier::UNDERLINED)), len)),
+            _ => None,
+        };
+        match styled {
+            Some((inner, len)) => {
+                if !plain.is_empty() {
+                    spans.push(Span::styled(std::mem::take(&mut plain), base));
+                }
+                spans.extend(inner);
+                rest = &rest[len..];
+            }
+            None => {
+                plain.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    if !plain.is_empty() {
+        spans.push(Span::styled(plain, base));
+    }
+    spans
+}
+
+// The text between `mark` at the start of `text` and its next occurrence, and
+// how many bytes that took, marks included
+fn closed<'a>(text: &'a str, mark: &str) -> Option<(&'a str, usize)> {
+    let body = &text[mark.len()..];
+    let end = body.find(mark).filter(|&end| end > 0)?;
+    Some((&body[..end], mark.len() * 2 + end))
+}
+
+// `[label](url)`: the label, and the bytes it all took
+fn link(text: &str) -> Option<(&str, usize)> {
+    let close = text.find("](")?;
+    let end = text[close..].find(')')? + close;
+    Some((&text[1..close], end + 1))
+}
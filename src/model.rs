@@ -1,29 +1,349 @@
+use crate::collate;
+use crate::defaults::TaskDefaults;
+use crate::lanes::Lanes;
+use crate::reset::ColumnReset;
+use crate::sort::ColumnSort;
+use crate::zone;
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::{Mutex, OnceLock};
+use uuid::{Builder, Uuid};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct Board {
     pub title: String,
     pub columns: Vec<Column>,
+    pub description: String, // Goals, links, working agreements; shown above the columns
+    pub frozen: bool, // Read-only until unfrozen, see `frozen_change`
+    pub wide_cards: bool, // Cards wrap long titles onto a second line instead of cutting them off
+    pub archive: Vec<Archived>, // Tasks taken off the board to keep, oldest first
+    pub trash: Vec<Trashed>, // Deleted tasks and columns, oldest first, until restored or purged
+    pub lanes: Option<Lanes>, // Swimlanes the cards are grouped into across the columns
+}
+
+// A task out of view but kept, with the column it left so it can go back there
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Archived {
+    pub task: Task,
+    pub column: String,
+    pub archived_at: DateTime<Utc>,
+}
+
+// A deleted task or column, with where it was so it can be put back there
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Trashed {
+    pub item: TrashedItem,
+    pub column: String, // The task's column, or the column itself
+    pub position: usize, // The task's row in its column, or the column's place on the board
+    pub deleted_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum TrashedItem {
+    Task(Box<Task>), // Boxed, as tasks outweigh columns
+    Column(Column),
+}
+
+// Columns of new boards, `[board] columns` from the config
+static DEFAULT_COLUMNS: OnceLock<Vec<String>> = OnceLock::new();
+
+/// Gives boards made from now on these columns instead of To Do, In Progress and Done.
+/// An empty list is ignored, as a board needs a column.
+pub fn set_default_columns(columns: Vec<String>) {
+    if !columns.is_empty() {
+        let _ = DEFAULT_COLUMNS.set(columns);
+    }
 }
 
 impl Default for Board {
     fn default() -> Self {
-        Self {
-            title: "Main Board".to_string(),
-            columns: vec![
+        let columns = match DEFAULT_COLUMNS.get() {
+            Some(titles) => titles.iter().map(|t| Column::new(t)).collect(),
+            None => vec![
                 Column::new("To Do"),
                 Column::new("In Progress"),
                 Column::new("Done"),
             ],
+        };
+        Self {
+            title: "Main Board".to_string(),
+            columns,
+            description: String::new(),
+            frozen: false,
+            wide_cards: false,
+            archive: Vec::new(),
+            trash: Vec::new(),
+            lanes: None,
         }
     }
 }
 
+impl Board {
+    /// Resolves a user-typed column name: exact (ignoring case and accents) first, then
+    /// word prefix, substring and finally subsequence ("prog" -> "In Progress").
+    /// The first tier with matches wins; more than one match there is ambiguous.
+    pub fn match_column(&self, query: &str) -> Result<usize, ColumnMatchError> {
+        let q = collate::fold(query.trim());
+        let titles: Vec<String> = self.columns.iter().map(|c| collate::fold(&c.title)).collect();
+
+        let tiers: [&dyn Fn(&str) -> bool; 4] = [
+            &|t| t == q,
+            &|t| t.split_whitespace().any(|w| w.starts_with(&q)) || t.starts_with(&q),
+            &|t| t.contains(&q),
+            &|t| is_subsequence(&q, t),
+        ];
+
+        for tier in tiers {
+            let hits: Vec<usize> = (0..titles.len()).filter(|&i| tier(&titles[i])).collect();
+            match hits.len() {
+                0 => continue,
+                1 => return Ok(hits[0]),
+                _ => {
+                    return Err(ColumnMatchError::Ambiguous {
+                        query: query.to_string(),
+                        candidates: hits.iter().map(|&i| self.columns[i].title.clone()).collect(),
+                    })
+                }
+            }
+        }
+
+        Err(ColumnMatchError::NotFound {
+            query: query.to_string(),
+            candidates: self.columns.iter().map(|c| c.title.clone()).collect(),
+        })
+    }
+
+    /// Paths (as `(col, task)` pairs from this board) to every task matching `pred`,
+    /// searching nested boards too.
+    pub fn find_tasks(&self, pred: &dyn Fn(&Task) -> bool) -> Vec<Vec<(usize, usize)>> {
+        let mut found = Vec::new();
+        let mut prefix = Vec::new();
+        self.collect_tasks(pred, &mut prefix, &mut found);
+        found
+    }
+
+    fn collect_tasks(&self, pred: &dyn Fn(&Task) -> bool, prefix: &mut Vec<(usize, usize)>, found: &mut Vec<Vec<(usize, usize)>>) {
+        for (c, col) in self.columns.iter().enumerate() {
+            for (r, task) in col.tasks.iter().enumerate() {
+                prefix.push((c, r));
+                if pred(task) {
+                    found.push(prefix.clone());
+                }
+                if let Some(TaskContent::Board(ref sub)) = task.content {
+                    sub.collect_tasks(pred, prefix, found);
+                }
+                prefix.pop();
+            }
+        }
+    }
+
+    /// Follows `path` down through nested boards; `None` if it leaves the tree
+    /// or passes through a task that is not a board.
+    pub fn board_at_mut(&mut self, path: &[(usize, usize)]) -> Option<&mut Board> {
+        let Some((&(c, r), rest)) = path.split_first() else { return Some(self) };
+        match self.columns.get_mut(c)?.tasks.get_mut(r)?.content {
+            Some(TaskContent::Board(ref mut sub)) => sub.board_at_mut(rest),
+            _ => None,
+        }
+    }
+
+    /// Deep copy with fresh task ids. `reset_done` unchecks todo items,
+    /// `strip_tasks` keeps only the column layout.
+    pub fn duplicate(&self, reset_done: bool, strip_tasks: bool) -> Board {
+        let mut copy = self.clone();
+        copy.title = format!("{} (copy)", self.title);
+        copy.refresh_copy(reset_done, strip_tasks);
+        copy
+    }
+
+    fn refresh_copy(&mut self, reset_done: bool, strip_tasks: bool) {
+        self.archive.clear(); // The original's history, and its ids
+        self.trash.clear();
+        for col in &mut self.columns {
+            if strip_tasks {
+                col.tasks.clear();
+            }
+            col.subscription = None; // The copied events are ordinary tasks
+            for task in &mut col.tasks {
+                task.id = new_id();
+                task.enter_column();
+                task.source = None; // A copy is not the imported item
+                task.reminders.clear(); // Nor should it remind twice
+                match task.content {
+                    Some(TaskContent::Board(ref mut sub)) => sub.refresh_copy(reset_done, strip_tasks),
+                    Some(TaskContent::Todo(ref mut items)) if reset_done => {
+                        items.iter_mut().for_each(|i| i.done = false);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Moves the task at `(c, r)` into the archive.
+    pub fn archive_task(&mut self, c: usize, r: usize) -> Option<&Archived> {
+        let column = self.columns.get_mut(c).filter(|col| r < col.tasks.len())?;
+        let task = column.tasks.remove(r);
+        self.archive.push(Archived { task, column: column.title.clone(), archived_at: Utc::now() });
+        self.archive.last()
+    }
+
+    /// Puts archived task `i` back at the bottom of the column it left, or of
+    /// the last column if that one is gone; returns the column's index.
+    pub fn restore_archived(&mut self, i: usize) -> Option<usize> {
+        if i >= self.archive.len() {
+            return None;
+        }
+        let archived = self.archive.remove(i);
+        let c = self.columns.iter().position(|col| col.title == archived.column).unwrap_or(self.columns.len() - 1);
+        self.columns[c].tasks.push(archived.task);
+        Some(c)
+    }
+
+    /// Moves the task at `(c, r)` into the trash.
+    pub fn trash_task(&mut self, c: usize, r: usize) -> Option<&Trashed> {
+        let column = self.columns.get_mut(c).filter(|col| r < col.tasks.len())?;
+        let task = column.tasks.remove(r);
+        self.trash.push(Trashed { item: TrashedItem::Task(Box::new(task)), column: column.title.clone(), position: r, deleted_at: Utc::now() });
+        self.trash.last()
+    }
+
+    /// Moves column `c` into the trash with whatever tasks are still in it.
+    /// A board keeps at least one column.
+    pub fn trash_column(&mut self, c: usize) -> Option<&Trashed> {
+        if c >= self.columns.len() || self.columns.len() == 1 {
+            return None;
+        }
+        let column = self.columns.remove(c);
+        self.trash.push(Trashed { column: column.title.clone(), item: TrashedItem::Column(column), position: c, deleted_at: Utc::now() });
+        self.trash.last()
+    }
+
+    /// Puts trashed item `i` back where it was: a task into its column (the
+    /// last one if that is gone), a column in its old place. Returns the
+    /// `(col, task)` it landed at; the task is None for a column.
+    pub fn restore_trashed(&mut self, i: usize) -> Option<(usize, Option<usize>)> {
+        if i >= self.trash.len() {
+            return None;
+        }
+        let trashed = self.trash.remove(i);
+        Some(match trashed.item {
+            TrashedItem::Task(task) => {
+                let c = self.columns.iter().position(|col| col.title == trashed.column).unwrap_or(self.columns.len() - 1);
+                let r = trashed.position.min(self.columns[c].tasks.len());
+                self.columns[c].tasks.insert(r, *task);
+                (c, Some(r))
+            }
+            TrashedItem::Column(column) => {
+                let c = trashed.position.min(self.columns.len());
+                self.columns.insert(c, column);
+                (c, None)
+            }
+        })
+    }
+
+    /// Empties the trash of everything deleted before `cutoff`, here and on the
+    /// boards inside; returns how many items went.
+    pub fn purge_trash(&mut self, cutoff: DateTime<Utc>) -> usize {
+        let before = self.trash.len();
+        self.trash.retain(|t| t.deleted_at >= cutoff);
+        let mut purged = before - self.trash.len();
+        for task in self.columns.iter_mut().flat_map(|c| c.tasks.iter_mut()) {
+            if let Some(TaskContent::Board(sub)) = &mut task.content {
+                purged += sub.purge_trash(cutoff);
+            }
+        }
+        purged
+    }
+
+    /// Where the task with `id` is, as `(col, task)` steps from this board.
+    pub fn path_to(&self, id: Uuid) -> Option<Vec<(usize, usize)>> {
+        self.find_tasks(&|t| t.id == id).pop()
+    }
+
+    /// The board at the end of `path`, if the path is valid.
+    pub fn board_at(&self, path: &[(usize, usize)]) -> Option<&Board> {
+        let Some((&(c, r), rest)) = path.split_first() else { return Some(self) };
+        match self.columns.get(c)?.tasks.get(r)?.content {
+            Some(TaskContent::Board(ref sub)) => sub.board_at(rest),
+            _ => None,
+        }
+    }
+
+    /// Breadcrumbs of the board at `path` as `search` names them: this board's
+    /// title, then the title of each task opened on the way.
+    pub fn breadcrumbs(&self, path: &[(usize, usize)]) -> Vec<String> {
+        let titles = (1..=path.len()).map_while(|n| self.task_at(&path[..n]).map(|t| t.title.clone()));
+        std::iter::once(self.title.clone()).chain(titles).collect()
+    }
+
+    /// Tasks still in play: everything outside the last column (taken as done) and the icebox.
+    pub fn open_tasks(&self) -> usize {
+        let last = self.columns.len().saturating_sub(1);
+        self.columns.iter().enumerate()
+            .filter(|&(i, c)| (i < last || last == 0) && !c.icebox)
+            .map(|(_, c)| c.tasks.len())
+            .sum()
+    }
+
+    pub fn task_at(&self, path: &[(usize, usize)]) -> Option<&Task> {
+        let (&(c, r), parent) = path.split_last()?;
+        self.board_at(parent)?.columns.get(c)?.tasks.get(r)
+    }
+
+    pub fn task_at_mut(&mut self, path: &[(usize, usize)]) -> Option<&mut Task> {
+        let (&(c, r), parent) = path.split_last()?;
+        self.board_at_mut(parent)?.columns.get_mut(c)?.tasks.get_mut(r)
+    }
+}
+
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut chars = haystack.chars();
+    needle.chars().all(|n| chars.any(|h| h == n))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnMatchError {
+    NotFound { query: String, candidates: Vec<String> },
+    Ambiguous { query: String, candidates: Vec<String> },
+}
+
+impl fmt::Display for ColumnMatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColumnMatchError::NotFound { query, candidates } => {
+                write!(f, "no column matches '{}' (columns: {})", query, candidates.join(", "))
+            }
+            ColumnMatchError::Ambiguous { query, candidates } => {
+                write!(f, "'{}' is ambiguous: {}", query, candidates.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for ColumnMatchError {}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct Column {
     pub title: String,
     pub tasks: Vec<Task>,
+    pub wip_limit: Option<usize>, // Max tasks before the column is flagged as over its limit
+    pub reset: Option<ColumnReset>, // Recurring clear/move, see reset.rs
+    pub requires_reason: bool, // Tasks moved in must say why (a "Blocked" column)
+    pub icebox: bool, // Parked "someday" ideas; at most one per board, left out of working views
+    pub subscription: Option<Subscription>, // Feed whose events this column mirrors
+    pub sort: Option<ColumnSort>, // Kept in this order after every change; None is manual order
+    pub frozen: bool, // Tasks can't be added, changed, moved or removed until unfrozen
+    pub defaults: Option<TaskDefaults>, // Tags and due date given to tasks created here, see defaults.rs
+}
+
+// An iCalendar feed mirrored into a column as read-only tasks, see ics.rs
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Subscription {
+    pub url: String,
+    pub refreshed_at: Option<DateTime<Utc>>,
 }
 
 impl Column {
@@ -31,8 +351,20 @@ impl Column {
         Self {
             title: title.to_string(),
             tasks: Vec::new(),
+            wip_limit: None,
+            reset: None,
+            requires_reason: false,
+            icebox: false,
+            subscription: None,
+            sort: None,
+            frozen: false,
+            defaults: None,
         }
     }
+
+    pub fn over_wip_limit(&self) -> bool {
+        self.wip_limit.is_some_and(|limit| self.tasks.len() > limit)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -41,6 +373,32 @@ pub struct Task {
     pub title: String,
     pub description: String,
     pub content: Option<TaskContent>,
+    pub created_at: DateTime<Utc>,
+    pub entered_column_at: DateTime<Utc>, // When the task last changed column (or was created)
+    pub blocked_reason: Option<String>, // Given when entering a `requires_reason` column
+    pub todo_layout: TodoLayout, // How the todo view arranges this task's items
+    pub snoozed_until: Option<NaiveDate>, // Hidden from the board before this day
+    pub waiting_on: Option<WaitingOn>,
+    pub due: Option<DateTime<Utc>>, // See `zone` for dues without a time of day
+    pub reminders: Vec<DateTime<Utc>>, // Fired by the TUI or `kanban notify`, see reminder.rs
+    pub source: Option<Source>, // Set on tasks created from another tool's data
+    pub lane: Option<String>, // Its swimlane on boards with `Lanes::Field`
+}
+
+// Where an imported task came from, so it can be traced back and matched on the next import
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Source {
+    pub system: String, // "trello", "github", ...
+    pub id: String,     // Id in that system, e.g. "acme/web#123"
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+// "Waiting on <who> since <date>", for tasks blocked on someone else
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct WaitingOn {
+    pub who: String,
+    pub since: NaiveDate,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -56,15 +414,328 @@ pub struct TodoItem {
     pub done: bool,
 }
 
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct TodoLayout {
+    pub pending_percent: u16, // Height share of the pending pane when both panes show
+    pub merged: bool,         // One list with done items at the bottom instead of two panes
+}
+
+/// Display order of a todo list: pending items, then done ones, each in stored
+/// order. The todo view's cursor is a position in this order.
+pub fn todo_order(items: &[TodoItem]) -> Vec<usize> {
+    let (pending, done): (Vec<usize>, Vec<usize>) = (0..items.len()).partition(|&i| !items[i].done);
+    pending.into_iter().chain(done).collect()
+}
+
+impl Default for TodoLayout {
+    fn default() -> Self {
+        Self { pending_percent: 50, merged: false }
+    }
+}
+
 impl Task {
     pub fn new(title: &str, description: &str) -> Self {
+        // The same instant, so a task that never moved can be told from one that did
+        let now = Utc::now();
         Self {
-            id: Uuid::new_v4(),
+            id: new_id(),
             title: title.to_string(),
             description: description.to_string(),
             content: None,
+            created_at: now,
+            entered_column_at: now,
+            blocked_reason: None,
+            todo_layout: TodoLayout::default(),
+            snoozed_until: None,
+            waiting_on: None,
+            due: None,
+            reminders: Vec::new(),
+            source: None,
+            lane: None,
         }
     }
 
+    /// Call whenever the task lands in a different column. Leaving a column
+    /// also drops the reason it was blocked for.
+    pub fn enter_column(&mut self) {
+        self.entered_column_at = Utc::now();
+        self.blocked_reason = None;
+    }
+
+    pub fn is_snoozed(&self, today: NaiveDate) -> bool {
+        self.snoozed_until.is_some_and(|until| until > today)
+    }
+
+    /// Days without news on a waiting task: since it started waiting, or since
+    /// it last moved, whichever is later.
+    pub fn days_waiting(&self, today: NaiveDate) -> Option<i64> {
+        let waiting = self.waiting_on.as_ref()?;
+        let last_change = waiting.since.max(zone::local(self.entered_column_at).date());
+        Some((today - last_change).num_days())
+    }
+
+    /// Whole days spent in the current column as of `now`.
+    pub fn days_in_column(&self, now: DateTime<Utc>) -> i64 {
+        (now - self.entered_column_at).num_days()
+    }
+
+    /// Past its time, or for a due without one, the day is over.
+    pub fn is_overdue(&self, now: DateTime<Utc>) -> bool {
+        self.due.is_some_and(|due| if zone::has_time(due) { due < now } else { zone::due_date(due) < zone::local(now).date() })
+    }
+
+    /// Overdue, blocked, or waiting long past its follow-up (the red waiting badge);
+    /// snoozed tasks are left alone until they come back.
+    pub fn needs_attention(&self, now: DateTime<Utc>, follow_up_days: i64) -> bool {
+        let today = zone::local(now).date();
+        !self.is_snoozed(today)
+            && (self.is_overdue(now)
+                || self.blocked_reason.is_some()
+                || self.days_waiting(today).is_some_and(|d| d >= 2 * follow_up_days))
+    }
+}
+
+// --- Id generation ---
+// Ids are random (v4) by default. Once seeded, they come from a deterministic
+// generator instead, so fixtures and exported examples are stable across runs.
 
+static ID_STATE: Mutex<Option<u64>> = Mutex::new(None);
+
+pub fn seed_ids(seed: u64) {
+    *ID_STATE.lock().unwrap_or_else(|e| e.into_inner()) = Some(seed);
+}
+
+pub fn new_id() -> Uuid {
+    let mut state = ID_STATE.lock().unwrap_or_else(|e| e.into_inner());
+    match state.as_mut() {
+        Some(s) => {
+            let bits = (u128::from(splitmix64(s)) << 64) | u128::from(splitmix64(s));
+            Builder::from_random_bytes(bits.to_be_bytes()).into_uuid()
+        }
+        None => Uuid::new_v4(),
+    }
+}
+
+pub fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+// --- Freezing ---
+// Frozen columns and boards hold reference material ("Definition of Done") that
+// should not change by accident. Rather than checking in every operation, the
+// tree before a change is compared with the tree after it, and changes touching
+// a frozen section are refused as a whole.
+
+/// The first frozen column or board in `before` that `after` changed, named for
+/// an error message. Unfreezing it is the one change let through.
+pub fn frozen_change(before: &Board, after: &Board) -> Option<String> {
+    changed_frozen(before, Some(after), after)
+}
+
+// `after` is `before` as found in `after_root`, or None if it is gone
+fn changed_frozen(before: &Board, after: Option<&Board>, after_root: &Board) -> Option<String> {
+    if before.frozen {
+        let kept = after.is_some_and(|a| a.title == before.title && a.description == before.description && a.columns == before.columns);
+        return (!kept).then(|| format!("board '{}'", before.title));
+    }
+    for column in before.columns.iter().filter(|c| c.frozen) {
+        let kept = after
+            .and_then(|a| a.columns.iter().find(|c| c.title == column.title))
+            .is_some_and(|c| c.tasks == column.tasks);
+        if !kept {
+            return Some(format!("column '{}'", column.title));
+        }
+    }
+    // Sub-boards are followed by their task's id, wherever it moved
+    for task in before.columns.iter().flat_map(|c| c.tasks.iter()) {
+        let Some(TaskContent::Board(ref sub)) = task.content else { continue };
+        let moved = after_root.path_to(task.id).and_then(|p| after_root.task_at(&p));
+        let sub_after = match moved.and_then(|t| t.content.as_ref()) {
+            Some(TaskContent::Board(b)) => Some(b),
+            _ => None,
+        };
+        if let Some(what) = changed_frozen(sub, sub_after, after_root) {
+            return Some(what);
+        }
+    }
+    None
+}
+
+// --- Validation ---
+// Invariants every board tree must satisfy after it is loaded or imported.
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationError {
+    /// A board (root or nested) has no columns, so nothing can be added to it.
+    NoColumns { board: String },
+    /// The same task id appears more than once in the tree.
+    DuplicateId { id: Uuid, title: String },
+    /// Two task ids start alike, so their 8-character short ids can't tell them apart.
+    AmbiguousShortId { short: String, ids: (Uuid, Uuid) },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::NoColumns { board } => write!(f, "board '{}' has no columns", board),
+            ValidationError::DuplicateId { id, title } => write!(f, "duplicate task id {} ('{}')", id, title),
+            ValidationError::AmbiguousShortId { short, ids } => write!(f, "task ids {} and {} share the short id {}", ids.0, ids.1, short),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Checks the whole tree under `board` and returns every violation found.
+pub fn validate(board: &Board) -> Result<(), Vec<ValidationError>> {
+    let mut errors = Vec::new();
+    let mut seen = HashSet::new();
+    validate_board(board, &mut seen, &mut errors);
+    let mut ids: Vec<Uuid> = seen.into_iter().collect();
+    ids.sort();
+    for pair in ids.windows(2) {
+        let short = short_id(pair[0]);
+        if short == short_id(pair[1]) {
+            errors.push(ValidationError::AmbiguousShortId { short, ids: (pair[0], pair[1]) });
+        }
+    }
+    if errors.is_empty() { Ok(()) } else { Err(errors) }
+}
+
+// The first 8 characters of `id`, the short id a task goes by
+fn short_id(id: Uuid) -> String {
+    id.to_string()[..8].to_string()
+}
+
+/// Validates `board` against ids already present elsewhere (`seen`), e.g. when
+/// grafting an imported board into an existing tree. New ids are added to `seen`.
+pub fn validate_board(board: &Board, seen: &mut HashSet<Uuid>, errors: &mut Vec<ValidationError>) {
+    if board.columns.is_empty() {
+        errors.push(ValidationError::NoColumns { board: board.title.clone() });
+    }
+    // Archived and trashed tasks keep their ids to be put back with them
+    let archived = board.archive.iter().map(|a| &a.task);
+    let trashed = board.trash.iter().flat_map(|t| match &t.item {
+        TrashedItem::Task(task) => std::slice::from_ref(task.as_ref()),
+        TrashedItem::Column(column) => column.tasks.as_slice(),
+    });
+    for task in board.columns.iter().flat_map(|c| c.tasks.iter()).chain(archived).chain(trashed) {
+        if !seen.insert(task.id) {
+            errors.push(ValidationError::DuplicateId { id: task.id, title: task.title.clone() });
+        }
+        if let Some(TaskContent::Board(ref sub)) = task.content {
+            validate_board(sub, seen, errors);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board(columns: &[&str]) -> Board {
+        Board { columns: columns.iter().map(|t| Column::new(t)).collect(), ..Default::default() }
+    }
+
+    // A board with one sub-board card in its first column
+    fn nested() -> Board {
+        let mut root = board(&["To Do", "Done"]);
+        let mut card = Task::new("Project", "");
+        let mut sub = board(&["Now", "Later"]);
+        sub.columns[0].tasks.push(Task::new("Step", ""));
+        card.content = Some(TaskContent::Board(sub));
+        root.columns[0].tasks.push(card);
+        root
+    }
+
+    #[test]
+    fn match_column_prefers_closer_matches() {
+        let b = board(&["Backlog", "In Progress", "Review", "Done", "Progress Notes"]);
+        assert_eq!(b.match_column("review"), Ok(2));
+        assert_eq!(b.match_column(" DONE "), Ok(3));
+        assert_eq!(b.match_column("back"), Ok(0), "word prefix");
+        assert_eq!(b.match_column("view"), Ok(2), "substring");
+        assert_eq!(b.match_column("bklg"), Ok(0), "subsequence");
+        assert!(matches!(b.match_column("prog"), Err(ColumnMatchError::Ambiguous { .. })));
+        assert!(matches!(b.match_column("zzz"), Err(ColumnMatchError::NotFound { .. })));
+    }
+
+    #[test]
+    fn match_column_ignores_accents() {
+        let b = board(&["À faire", "Terminé"]);
+        assert_eq!(b.match_column("a faire"), Ok(0));
+        assert_eq!(b.match_column("TERMINE"), Ok(1));
+    }
+
+    #[test]
+    fn frozen_columns_refuse_changes() {
+        let mut before = nested();
+        before.columns[1].frozen = true;
+        before.columns[1].tasks.push(Task::new("Reference", ""));
+
+        let mut added = before.clone();
+        added.columns[0].tasks.push(Task::new("New", ""));
+        assert_eq!(frozen_change(&before, &added), None, "other columns are free");
+
+        let mut renamed = before.clone();
+        renamed.columns[1].tasks[0].title = "Changed".to_string();
+        assert_eq!(frozen_change(&before, &renamed), Some("column 'Done'".to_string()));
+
+        let mut unfrozen = before.clone();
+        unfrozen.columns[1].frozen = false;
+        assert_eq!(frozen_change(&before, &unfrozen), None, "unfreezing is let through");
+    }
+
+    #[test]
+    fn frozen_boards_are_followed_when_moved() {
+        let mut before = nested();
+        let Some(TaskContent::Board(sub)) = &mut before.columns[0].tasks[0].content else { unreachable!() };
+        sub.frozen = true;
+
+        let mut moved = before.clone();
+        let card = moved.columns[0].tasks.remove(0);
+        moved.columns[1].tasks.push(card);
+        assert_eq!(frozen_change(&before, &moved), None);
+
+        let Some(TaskContent::Board(sub)) = &mut moved.columns[1].tasks[0].content else { unreachable!() };
+        sub.columns[0].tasks.clear();
+        assert_eq!(frozen_change(&before, &moved), Some("board 'Main Board'".to_string()));
+    }
+
+    #[test]
+    fn validate_finds_every_violation() {
+        assert_eq!(validate(&nested()), Ok(()));
+
+        let mut root = nested();
+        let step = root.task_at(&[(0, 0), (0, 0)]).unwrap().clone();
+        root.columns[1].tasks.push(step.clone());
+        root.columns.push(Column::new("Empty"));
+        let mut empty = Task::new("Empty board", "");
+        empty.content = Some(TaskContent::Board(board(&[])));
+        root.columns[0].tasks.push(empty);
+        root.archive.push(Archived { task: root.columns[0].tasks[0].clone(), column: "To Do".to_string(), archived_at: Utc::now() });
+
+        let errors = validate(&root).unwrap_err();
+        // The archived copy of Project brings its sub-board, and Step with it
+        assert_eq!(errors.len(), 4, "{:?}", errors);
+        assert!(errors.contains(&ValidationError::DuplicateId { id: step.id, title: step.title.clone() }));
+        assert!(errors.contains(&ValidationError::NoColumns { board: "Main Board".to_string() }));
+        assert!(errors.iter().any(|e| matches!(e, ValidationError::DuplicateId { title, .. } if title == "Project")), "archived ids count");
+    }
+
+    #[test]
+    fn validate_refuses_ambiguous_short_ids() {
+        let mut root = board(&["To Do"]);
+        for id in [0x1234_5678_0000_0000_0000_0000_0000_0001, 0x1234_5678_ffff_0000_0000_0000_0000_0002, 0x1234_5679_0000_0000_0000_0000_0000_0000u128] {
+            let mut task = Task::new("Task", "");
+            task.id = Uuid::from_u128(id);
+            root.columns[0].tasks.push(task);
+        }
+        let errors = validate(&root).unwrap_err();
+        assert!(matches!(errors.as_slice(), [ValidationError::AmbiguousShortId { short, .. }] if short == "12345678"), "{:?}", errors);
+    }
 }
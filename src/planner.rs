@@ -0,0 +1,89 @@
+use crate::agenda;
+use crate::model::{Board, Task};
+use crate::zone;
+use chrono::{Days, NaiveDate};
+use std::fmt::Write;
+
+// A week on one page, to print or paste into a notes app: what was left over,
+// then a section per day with the open tasks due that day and the ones pinned
+// to it by a reminder or a snooze ending. Markdown that reads as plain text.
+
+struct Entry {
+    title: String,
+    when: Option<String>, // Time of day, for sorting and in front of the title
+    note: Option<&'static str>,
+    place: String, // Board and column
+}
+
+/// The week starting on `monday` for the open tasks of `board`, whose
+/// breadcrumbs from the main board are `crumbs`.
+pub fn render(board: &Board, crumbs: &[String], monday: NaiveDate) -> String {
+    let days: Vec<NaiveDate> = (0..7).filter_map(|n| monday.checked_add_days(Days::new(n))).collect();
+    let mut overdue = Vec::new();
+    let mut per_day: Vec<Vec<Entry>> = days.iter().map(|_| Vec::new()).collect();
+    agenda::walk_open(board, &mut |inner, _, column, task| {
+        let place = crumbs.iter().chain(&inner[1..]).cloned().chain([column.title.clone()]).collect::<Vec<_>>().join(" > ");
+        let entry = |when: Option<String>, note| Entry { title: task.title.clone(), when, note, place: place.clone() };
+        if let Some(due) = task.due {
+            let day = zone::due_date(due);
+            if day < monday {
+                overdue.push((day, entry(None, None)));
+            } else if let Some(i) = days.iter().position(|&d| d == day) {
+                per_day[i].push(entry(time(task), None));
+            }
+        }
+        for at in &task.reminders {
+            let at = zone::local(*at);
+            if let Some(i) = days.iter().position(|&d| d == at.date()) {
+                per_day[i].push(entry(Some(at.format("%H:%M").to_string()), Some("reminder")));
+            }
+        }
+        if let Some(i) = task.snoozed_until.and_then(|until| days.iter().position(|&d| d == until)) {
+            per_day[i].push(entry(None, Some("back from snooze")));
+        }
+    });
+
+    let mut out = String::new();
+    let sunday = days.last().copied().unwrap_or(monday);
+    let _ = writeln!(out, "# Week of {} – {}\n", monday.format("%b %-d"), sunday.format("%b %-d, %Y"));
+    if !overdue.is_empty() {
+        overdue.sort_by_key(|(day, _)| *day);
+        out.push_str("## Carried over\n\n");
+        for (day, entry) in &overdue {
+            line(&mut out, entry, Some(&format!("was due {}", day.format("%b %-d"))));
+        }
+        out.push('\n');
+    }
+    for (day, mut entries) in days.iter().zip(per_day) {
+        let _ = writeln!(out, "## {}\n", day.format("%A, %b %-d"));
+        if entries.is_empty() {
+            out.push_str("- [ ]\n\n");
+            continue;
+        }
+        // Timed entries in order, the rest of the day after them
+        entries.sort_by(|a, b| (a.when.is_none(), &a.when).cmp(&(b.when.is_none(), &b.when)));
+        for entry in &entries {
+            line(&mut out, entry, None);
+        }
+        out.push('\n');
+    }
+    out.push_str("## Notes\n\n\n");
+    out
+}
+
+fn line(out: &mut String, entry: &Entry, note: Option<&str>) {
+    out.push_str("- [ ] ");
+    if let Some(when) = &entry.when {
+        let _ = write!(out, "{} ", when);
+    }
+    out.push_str(&entry.title);
+    if let Some(note) = note.or(entry.note) {
+        let _ = write!(out, " ({})", note);
+    }
+    let _ = writeln!(out, " · {}", entry.place);
+}
+
+// The due time, for tasks due at one
+fn time(task: &Task) -> Option<String> {
+    task.due.filter(|&due| zone::has_time(due)).map(|due| zone::local(due).format("%H:%M").to_string())
+}
@@ -0,0 +1,81 @@
+use crate::date;
+use crate::model::Board;
+use crate::search;
+use crate::storage;
+use crate::zone;
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use uuid::Uuid;
+
+// Reminders are times set on a task, fired by the TUI while it is open and by
+// `kanban notify` (e.g. from cron). Each firing is appended to the activity log
+// rather than saved in the board file, so the two can run at the same time and
+// neither fires a reminder the other already did.
+
+const ACTIVITY_FILE: &str = "activity.log"; // Beside the board file, see `storage::sibling`
+const EVENT: &str = "reminder";
+const DEFAULT_TIME: (u32, u32) = (9, 0); // For reminders given as a day alone
+
+pub struct Reminder {
+    pub task: Uuid,
+    pub at: DateTime<Utc>,
+    pub title: String,
+    pub board: String, // Breadcrumbs, starting with the workspace
+}
+
+/// A time as `date::parse_due` reads it; a day alone means 9:00 that day.
+pub fn parse(s: &str, today: NaiveDate) -> Option<DateTime<Utc>> {
+    let at = date::parse_due(s, today)?;
+    if zone::has_time(at) {
+        return Some(at);
+    }
+    let (h, m) = DEFAULT_TIME;
+    Some(zone::utc(zone::due_date(at).and_time(NaiveTime::from_hms_opt(h, m, 0)?)))
+}
+
+/// Reminders in `workspaces` that are due by `now` and not in the activity log
+/// of board file `file` yet.
+pub fn due(file: &Path, workspaces: &[&Board], now: DateTime<Utc>) -> Vec<Reminder> {
+    let fired = fired(file);
+    let mut due = Vec::new();
+    for root in workspaces {
+        search::walk_tasks(root, &mut |crumbs, _, _, task| {
+            for &at in task.reminders.iter().filter(|&&at| at <= now && !fired.contains(&(task.id, at))) {
+                due.push(Reminder { task: task.id, at, title: task.title.clone(), board: crumbs.join(" > ") });
+            }
+        });
+    }
+    due.sort_by_key(|r| r.at);
+    due
+}
+
+/// Appends `reminders` to the activity log of board file `file` so they don't fire again.
+pub fn record(file: &Path, reminders: &[Reminder]) -> Result<()> {
+    if reminders.is_empty() {
+        return Ok(());
+    }
+    let mut log = OpenOptions::new().create(true).append(true).open(storage::sibling(file, ACTIVITY_FILE))?;
+    let now = Utc::now().to_rfc3339();
+    for r in reminders {
+        // Tabs and newlines in titles would split the line
+        let title = r.title.replace(['\t', '\n'], " ");
+        writeln!(log, "{}\t{}\t{}\t{}\t{}", now, EVENT, r.task, r.at.to_rfc3339(), title)?;
+    }
+    Ok(())
+}
+
+// (task, reminder time) of every reminder in the log
+fn fired(file: &Path) -> HashSet<(Uuid, DateTime<Utc>)> {
+    let Ok(text) = fs::read_to_string(storage::sibling(file, ACTIVITY_FILE)) else { return HashSet::new() };
+    text.lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('\t').collect();
+            let [_, EVENT, task, at, ..] = fields.as_slice() else { return None };
+            Some((task.parse().ok()?, DateTime::parse_from_rfc3339(at).ok()?.to_utc()))
+        })
+        .collect()
+}
@@ -0,0 +1,175 @@
+use crate::model::{Board, Task, TaskContent};
+use crate::search;
+
+// Interactive find/replace over task titles, descriptions and notes. Targets are
+// collected up front; occurrences are found one at a time so earlier replacements
+// shift the text of later ones correctly.
+
+const CONTEXT_CHARS: usize = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Field {
+    Title,
+    Description,
+    Note,
+}
+
+impl Field {
+    fn name(self) -> &'static str {
+        match self {
+            Field::Title => "title",
+            Field::Description => "description",
+            Field::Note => "note",
+        }
+    }
+
+    fn text_mut(self, task: &mut Task) -> Option<&mut String> {
+        match self {
+            Field::Title => Some(&mut task.title),
+            Field::Description => Some(&mut task.description),
+            Field::Note => match task.content {
+                Some(TaskContent::Text(ref mut text)) => Some(text),
+                _ => None,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Answer {
+    Yes,
+    No,
+    All,  // This and every remaining occurrence
+    Stop, // Leave the rest alone
+}
+
+pub struct ReplaceSession {
+    pub find: String,
+    pub replace: String,
+    targets: Vec<(Vec<(usize, usize)>, Field)>, // Task paths from the root
+    target: usize,
+    offset: usize,          // Byte offset in the current target to search from
+    current: Option<usize>, // Offset of the occurrence awaiting an answer
+    pub replaced: usize,
+}
+
+/// `s/old/new/` with any delimiter; a trailing `t` also searches nested boards.
+pub fn parse(arg: &str) -> Result<(String, String, bool), String> {
+    let mut chars = arg.chars();
+    let delim = chars.next().ok_or("usage: replace /old/new/[t]")?;
+    let parts: Vec<&str> = chars.as_str().split(delim).collect();
+    let (find, replace, tree) = match parts.as_slice() {
+        [find, replace] | [find, replace, ""] => (find, replace, false),
+        [find, replace, "t"] => (find, replace, true),
+        [_, _, flags] => return Err(format!("unknown flags '{}' (t: whole tree)", flags)),
+        _ => return Err("usage: replace /old/new/[t]".to_string()),
+    };
+    if find.is_empty() {
+        return Err("nothing to find".to_string());
+    }
+    Ok((find.to_string(), replace.to_string(), tree))
+}
+
+impl ReplaceSession {
+    /// Searches the tasks of the board at `path`, and with `tree` the boards nested in it.
+    /// Returns None when there is nothing to replace.
+    pub fn start(root: &mut Board, path: &[(usize, usize)], find: &str, replace: &str, tree: bool) -> Option<Self> {
+        let board = root.board_at(path)?;
+        let mut targets = Vec::new();
+        search::walk_tasks(board, &mut |_, location, _, task| {
+            if !tree && location.len() > 1 {
+                return;
+            }
+            let full: Vec<(usize, usize)> = path.iter().chain(location).copied().collect();
+            targets.push((full.clone(), Field::Title));
+            targets.push((full.clone(), Field::Description));
+            if let Some(TaskContent::Text(_)) = task.content {
+                targets.push((full, Field::Note));
+            }
+        });
+
+        let mut session = Self {
+            find: find.to_string(),
+            replace: replace.to_string(),
+            targets,
+            target: 0,
+            offset: 0,
+            current: None,
+            replaced: 0,
+        };
+        session.advance(root);
+        session.current.map(|_| session)
+    }
+
+    fn text_mut<'a>(&self, root: &'a mut Board, target: usize) -> Option<&'a mut String> {
+        let (path, field) = self.targets.get(target)?;
+        field.text_mut(root.task_at_mut(path)?)
+    }
+
+    // Moves `current` to the next occurrence, or None when there are no more
+    fn advance(&mut self, root: &mut Board) {
+        while self.target < self.targets.len() {
+            let found = self.text_mut(root, self.target)
+                .and_then(|text| text.get(self.offset..)?.find(&self.find).map(|i| i + self.offset));
+            if let Some(at) = found {
+                self.current = Some(at);
+                return;
+            }
+            self.target += 1;
+            self.offset = 0;
+        }
+        self.current = None;
+    }
+
+    /// Applies the answer for the current occurrence; false once the session is over.
+    pub fn answer(&mut self, root: &mut Board, answer: Answer) -> bool {
+        let Some(at) = self.current else { return false };
+        match answer {
+            Answer::Stop => {
+                self.current = None;
+                return false;
+            }
+            Answer::No => self.offset = at + self.find.len(),
+            Answer::Yes => self.replace_current(root, at),
+            Answer::All => {
+                while let Some(at) = self.current {
+                    self.replace_current(root, at);
+                    self.advance(root);
+                }
+                return false;
+            }
+        }
+        self.advance(root);
+        self.current.is_some()
+    }
+
+    fn replace_current(&mut self, root: &mut Board, at: usize) {
+        let (find, replace) = (self.find.clone(), self.replace.clone());
+        if let Some(text) = self.text_mut(root, self.target) {
+            text.replace_range(at..at + find.len(), &replace);
+            self.replaced += 1;
+        }
+        self.offset = at + replace.len();
+    }
+
+    /// Where the current occurrence is and the text around it, split as
+    /// (heading, before, match, after) for highlighting.
+    pub fn preview(&self, root: &Board) -> Option<(String, String, String, String)> {
+        let at = self.current?;
+        let (path, field) = self.targets.get(self.target)?;
+        let task = root.task_at(path)?;
+        let text = match field {
+            Field::Title => &task.title,
+            Field::Description => &task.description,
+            Field::Note => match task.content {
+                Some(TaskContent::Text(ref text)) => text,
+                _ => return None,
+            },
+        };
+        let end = at + self.find.len();
+        let before: String = text[..at].chars().rev().take(CONTEXT_CHARS).collect::<Vec<_>>().into_iter().rev().collect();
+        let after: String = text[end..].chars().take(CONTEXT_CHARS).collect();
+        let heading = format!("{} of '{}'", field.name(), task.title);
+        Some((heading, before, text[at..end].to_string(), after))
+    }
+}
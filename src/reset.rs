@@ -0,0 +1,109 @@
+use crate::model::{Archived, Board, TaskContent};
+use chrono::{Datelike, Days, NaiveDate, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+
+// Recurring column resets ("weekly board"): when a column's period rolls over,
+// its tasks are moved into another column, or archived when no target is set.
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ColumnReset {
+    pub period: ResetPeriod,
+    pub into: Option<String>, // Target column title; None archives the column's tasks
+    pub last_run: NaiveDate,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum ResetPeriod {
+    Daily,
+    Weekly(Weekday),
+}
+
+impl ResetPeriod {
+    /// Accepts "daily" or a weekday name ("mon", "Monday", ...).
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("daily") {
+            return Some(ResetPeriod::Daily);
+        }
+        s.parse::<Weekday>().ok().map(ResetPeriod::Weekly)
+    }
+
+    /// The latest day on or before `today` on which a reset is scheduled.
+    fn last_boundary(self, today: NaiveDate) -> NaiveDate {
+        match self {
+            ResetPeriod::Daily => today,
+            ResetPeriod::Weekly(day) => {
+                let back = (7 + today.weekday().num_days_from_monday() - day.num_days_from_monday()) % 7;
+                today - Days::new(back.into())
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for ResetPeriod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResetPeriod::Daily => write!(f, "daily"),
+            ResetPeriod::Weekly(day) => write!(f, "every {}", day),
+        }
+    }
+}
+
+impl ColumnReset {
+    /// A new rule does not fire until its next period boundary.
+    pub fn new(period: ResetPeriod, into: Option<String>, today: NaiveDate) -> Self {
+        Self { period, into, last_run: today }
+    }
+
+    fn is_due(&self, today: NaiveDate) -> bool {
+        self.last_run < self.period.last_boundary(today)
+    }
+}
+
+/// Runs every due reset in the tree and returns how many tasks were moved or archived.
+pub fn run_due(board: &mut Board, today: NaiveDate) -> usize {
+    if board.frozen {
+        return 0;
+    }
+    let mut affected = 0;
+
+    for c in 0..board.columns.len() {
+        let Some(rule) = board.columns[c].reset.clone() else { continue };
+        // A frozen column keeps its tasks; the reset runs once it is unfrozen
+        if !rule.is_due(today) || board.columns[c].frozen {
+            continue;
+        }
+
+        let target = match &rule.into {
+            Some(name) => match board.match_column(name) {
+                Ok(t) if t != c && !board.columns[t].frozen => Some(t),
+                // Missing, frozen or self-referencing target: leave the column alone until fixed
+                _ => continue,
+            },
+            None => None,
+        };
+
+        let tasks: Vec<_> = board.columns[c].tasks.drain(..).collect();
+        affected += tasks.len();
+        for mut task in tasks {
+            match target {
+                Some(t) => {
+                    task.enter_column();
+                    board.columns[t].tasks.push(task);
+                }
+                // Kept where `:archive` puts tasks, so a reset never loses one
+                None => board.archive.push(Archived { task, column: board.columns[c].title.clone(), archived_at: Utc::now() }),
+            }
+        }
+        if let Some(rule) = board.columns[c].reset.as_mut() {
+            rule.last_run = today;
+        }
+    }
+
+    for task in board.columns.iter_mut().flat_map(|c| c.tasks.iter_mut()) {
+        if let Some(TaskContent::Board(ref mut sub)) = task.content {
+            affected += run_due(sub, today);
+        }
+    }
+    affected
+}
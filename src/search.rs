@@ -0,0 +1,91 @@
+use crate::collate;
+use crate::link;
+use crate::model::{Board, Column, Source, Task, TaskContent};
+use serde::Serialize;
+use uuid::Uuid;
+
+// Substring search over the whole board tree, ignoring case and accents (see collate).
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Match {
+    pub breadcrumbs: Vec<String>,    // Boards from the root down to the one holding the task
+    pub location: Vec<(usize, usize)>, // (col, task) path to the task, as used by `App.path`
+    pub column: String,
+    pub id: Uuid,
+    pub title: String,
+    pub link: String, // kanban:// address, see `link`
+    pub field: &'static str, // Where the query was found: title, description, note, todo or source
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<Source>,
+}
+
+pub fn search(root: &Board, query: &str) -> Vec<Match> {
+    let mut matches = Vec::new();
+    let needle = collate::fold(query);
+    if needle.is_empty() {
+        return matches;
+    }
+
+    walk_tasks(root, &mut |crumbs, location, column, task| {
+        if let Some(field) = matched_field(task, &needle) {
+            matches.push(Match::new(crumbs, location, column, task, field));
+        }
+    });
+    matches
+}
+
+impl Match {
+    /// `task` as `walk_tasks` visits it, found by its `field`.
+    pub fn new(crumbs: &[String], location: &[(usize, usize)], column: &Column, task: &Task, field: &'static str) -> Self {
+        Self {
+            breadcrumbs: crumbs.to_vec(),
+            location: location.to_vec(),
+            column: column.title.clone(),
+            id: task.id,
+            title: task.title.clone(),
+            link: link::format(crumbs, &column.title, task.id),
+            field,
+            source: task.source.clone(),
+        }
+    }
+}
+
+/// Where a `collate::fold`ed `needle` occurs in `task` itself (not its sub-board), if anywhere.
+pub fn matched_field(task: &Task, needle: &str) -> Option<&'static str> {
+    let hit = |s: &str| collate::fold(s).contains(needle);
+    if hit(&task.title) {
+        Some("title")
+    } else if hit(&task.description) {
+        Some("description")
+    } else {
+        match &task.content {
+            Some(TaskContent::Text(text)) if hit(text) => Some("note"),
+            Some(TaskContent::Todo(items)) if items.iter().any(|i| hit(&i.text)) => Some("todo"),
+            _ => task.source.as_ref().filter(|s| hit(&s.id) || s.url.as_deref().is_some_and(hit)).map(|_| "source"),
+        }
+    }
+}
+
+// Board breadcrumbs, path to the task, its column, task
+pub type Visitor<'a> = dyn FnMut(&[String], &[(usize, usize)], &Column, &Task) + 'a;
+
+/// Visits every task in the tree, parents before the tasks of their sub-boards.
+pub fn walk_tasks(root: &Board, visit: &mut Visitor) {
+    walk(root, visit, &mut vec![root.title.clone()], &mut Vec::new());
+}
+
+fn walk(board: &Board, visit: &mut Visitor, crumbs: &mut Vec<String>, location: &mut Vec<(usize, usize)>) {
+    for (c, col) in board.columns.iter().enumerate() {
+        for (r, task) in col.tasks.iter().enumerate() {
+            location.push((c, r));
+            visit(crumbs, location, col, task);
+
+            if let Some(TaskContent::Board(ref sub)) = task.content {
+                crumbs.push(task.title.clone());
+                walk(sub, visit, crumbs, location);
+                crumbs.pop();
+            }
+            location.pop();
+        }
+    }
+}
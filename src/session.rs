@@ -0,0 +1,99 @@
+use crate::model::{Board, TaskContent};
+use crate::search;
+use chrono::{DateTime, Local};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+// Work log of a TUI session: where every task was when it opened, compared
+// with where it is on quit. Nothing is tracked per action, so changes that
+// were undone, or made and reverted, don't show up.
+
+#[derive(Clone, PartialEq)]
+struct Place {
+    title: String,
+    board: String,  // Breadcrumbs, starting with the workspace
+    column: String,
+    done: bool,     // In the last column of its board
+}
+
+pub struct Session {
+    started: DateTime<Local>,
+    places: HashMap<Uuid, Place>,
+}
+
+impl Session {
+    pub fn start(workspaces: &[&Board]) -> Self {
+        Self { started: Local::now(), places: places(workspaces) }
+    }
+
+    /// Markdown lines describing what changed, or None when nothing did.
+    pub fn summary(&self, workspaces: &[&Board]) -> Option<String> {
+        let now = places(workspaces);
+        let (mut added, mut completed, mut moved, mut archived, mut deleted) = (Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        let mut in_archive = HashSet::new();
+        workspaces.iter().for_each(|root| archived_ids(root, &mut in_archive));
+        for (id, place) in &now {
+            match self.places.get(id) {
+                None => added.push(format!("{} ({} > {})", place.title, place.board, place.column)),
+                Some(before) if place.done && !before.done => completed.push(place.title.clone()),
+                Some(before) if before.board != place.board || before.column != place.column => {
+                    moved.push(format!("{}: {} → {}", place.title, before.column, place.column));
+                }
+                Some(_) => {}
+            }
+        }
+        for (id, place) in &self.places {
+            if in_archive.contains(id) {
+                archived.push(place.title.clone());
+            } else if !now.contains_key(id) {
+                deleted.push(place.title.clone());
+            }
+        }
+        if added.len() + completed.len() + moved.len() + archived.len() + deleted.len() == 0 {
+            return None;
+        }
+
+        let ended = Local::now();
+        let minutes = (ended - self.started).num_minutes();
+        let mut lines = vec![format!(
+            "## Session {} – {} ({}h{:02}m)",
+            self.started.format("%Y-%m-%d %H:%M"), ended.format("%H:%M"), minutes / 60, minutes % 60,
+        )];
+        for (heading, mut items) in [("Added", added), ("Completed", completed), ("Moved", moved), ("Archived", archived), ("Deleted", deleted)] {
+            if items.is_empty() {
+                continue;
+            }
+            items.sort();
+            lines.push(format!("- {} ({}):", heading, items.len()));
+            lines.extend(items.iter().map(|i| format!("  - {}", i)));
+        }
+        Some(lines.join("\n"))
+    }
+}
+
+// Tasks in the archives of `board` and the boards inside it
+fn archived_ids(board: &Board, ids: &mut HashSet<Uuid>) {
+    ids.extend(board.archive.iter().map(|a| a.task.id));
+    for task in board.columns.iter().flat_map(|c| &c.tasks) {
+        if let Some(TaskContent::Board(sub)) = &task.content {
+            archived_ids(sub, ids);
+        }
+    }
+}
+
+fn places(workspaces: &[&Board]) -> HashMap<Uuid, Place> {
+    let mut places = HashMap::new();
+    for root in workspaces {
+        search::walk_tasks(root, &mut |crumbs, location, column, task| {
+            let (c, _) = location[location.len() - 1];
+            let last = root.board_at(&location[..location.len() - 1]).map_or(0, |b| b.columns.len().saturating_sub(1));
+            places.insert(task.id, Place {
+                title: task.title.clone(),
+                board: crumbs.join(" > "),
+                column: column.title.clone(),
+                done: c == last,
+            });
+        });
+    }
+    places
+}
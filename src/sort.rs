@@ -0,0 +1,82 @@
+use crate::collate;
+use crate::model::{Board, Task, TaskContent};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+// Auto-sorted columns keep their tasks ordered by a key after every change, e.g.
+// Done by when tasks arrived, newest first. Columns without one keep the order
+// tasks were added or moved in.
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct ColumnSort {
+    pub key: SortKey,
+    pub descending: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum SortKey {
+    Title,
+    Due,     // Tasks without a due date go last either way
+    Entered, // When the task entered the column; for Done, when it was finished
+}
+
+impl ColumnSort {
+    /// Accepts a key ("title", "due", "entered") optionally followed by "asc" or "desc".
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut words = s.split_whitespace();
+        let key = match words.next()?.to_lowercase().as_str() {
+            "title" | "name" => SortKey::Title,
+            "due" => SortKey::Due,
+            "entered" | "age" => SortKey::Entered,
+            _ => return None,
+        };
+        let descending = match words.next().map(str::to_lowercase).as_deref() {
+            None | Some("asc") => false,
+            Some("desc") => true,
+            Some(_) => return None,
+        };
+        words.next().is_none().then_some(Self { key, descending })
+    }
+
+    fn compare(self, a: &Task, b: &Task) -> Ordering {
+        let order = match self.key {
+            SortKey::Title => collate::cmp(&a.title, &b.title),
+            SortKey::Due => match (a.due, b.due) {
+                (Some(x), Some(y)) => x.cmp(&y),
+                (x, y) => return x.is_none().cmp(&y.is_none()),
+            },
+            SortKey::Entered => a.entered_column_at.cmp(&b.entered_column_at),
+        };
+        if self.descending { order.reverse() } else { order }
+    }
+}
+
+impl std::fmt::Display for ColumnSort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let key = match self.key {
+            SortKey::Title => "title",
+            SortKey::Due => "due",
+            SortKey::Entered => "entered",
+        };
+        write!(f, "{}{}", key, if self.descending { " desc" } else { "" })
+    }
+}
+
+/// Sorts every auto-sorted column in the tree; true if any task changed place.
+/// The sort is stable, so tasks with equal keys keep their relative order.
+pub fn apply(board: &mut Board) -> bool {
+    let mut moved = false;
+    for column in &mut board.columns {
+        if let Some(sort) = column.sort
+            && !column.tasks.is_sorted_by(|a, b| sort.compare(a, b) != Ordering::Greater) {
+                column.tasks.sort_by(|a, b| sort.compare(a, b));
+                moved = true;
+            }
+        for task in &mut column.tasks {
+            if let Some(TaskContent::Board(sub)) = &mut task.content {
+                moved |= apply(sub);
+            }
+        }
+    }
+    moved
+}
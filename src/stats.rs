@@ -0,0 +1,171 @@
+use crate::export::csv_field;
+use crate::model::{Board, TaskContent};
+use crate::zone;
+use chrono::{DateTime, Datelike, Days, NaiveDate, Utc};
+use serde::Serialize;
+use std::fmt::Write;
+
+// Figures for a whole workspace, board by board: what is open, what got done
+// and where things moved since a given time. "Open" is as in `Board::open_tasks`,
+// and a task counts as done once it sits in its board's last column.
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ColumnStats {
+    pub title: String,
+    pub tasks: usize,
+    pub overdue: usize, // Never counted in the last column, where tasks are done
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct BoardStats {
+    pub breadcrumbs: Vec<String>, // From the main board down to this one
+    pub open: usize,
+    pub done: usize, // Entered the last column since `WorkspaceStats::since`
+    pub moved: usize, // Changed column (or were added) since then
+    pub overdue: usize,
+    pub cycle_days: CycleTime, // Of the tasks in `done`
+    pub columns: Vec<ColumnStats>,
+    #[serde(skip)]
+    samples: Vec<f64>, // Days from creation to done, for totals over boards
+}
+
+// Days from a task's creation to reaching the last column
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct CycleTime {
+    pub average: Option<f64>,
+    pub median: Option<f64>,
+}
+
+impl CycleTime {
+    fn of(samples: &[f64]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let mid = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) { (sorted[mid - 1] + sorted[mid]) / 2.0 } else { sorted[mid] };
+        Self { average: Some(sorted.iter().sum::<f64>() / sorted.len() as f64), median: Some(median) }
+    }
+}
+
+impl BoardStats {
+    pub fn title(&self) -> &str {
+        self.breadcrumbs.last().map_or("", String::as_str)
+    }
+
+    /// 0 for the main board.
+    pub fn depth(&self) -> usize {
+        self.breadcrumbs.len().saturating_sub(1)
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct WorkspaceStats {
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    pub boards: Vec<BoardStats>, // The main board, then sub-boards depth first
+}
+
+impl WorkspaceStats {
+    pub fn open(&self) -> usize {
+        self.boards.iter().map(|b| b.open).sum()
+    }
+
+    pub fn done(&self) -> usize {
+        self.boards.iter().map(|b| b.done).sum()
+    }
+
+    pub fn overdue(&self) -> usize {
+        self.boards.iter().map(|b| b.overdue).sum()
+    }
+
+    pub fn cycle_days(&self) -> CycleTime {
+        CycleTime::of(&self.boards.iter().flat_map(|b| b.samples.iter().copied()).collect::<Vec<_>>())
+    }
+
+    /// Up to `n` boards that had anything move, busiest first.
+    pub fn most_active(&self, n: usize) -> Vec<&BoardStats> {
+        let mut active: Vec<&BoardStats> = self.boards.iter().filter(|b| b.moved > 0).collect();
+        active.sort_by_key(|b| std::cmp::Reverse(b.moved));
+        active.truncate(n);
+        active
+    }
+
+    /// Totals first, then each board with its columns.
+    pub fn json(&self) -> serde_json::Result<String> {
+        #[derive(Serialize)]
+        struct Totals<'a> {
+            #[serde(flatten)]
+            stats: &'a WorkspaceStats,
+            open: usize,
+            done: usize,
+            overdue: usize,
+            cycle_days: CycleTime,
+        }
+        serde_json::to_string_pretty(&Totals { stats: self, open: self.open(), done: self.done(), overdue: self.overdue(), cycle_days: self.cycle_days() })
+    }
+
+    /// A row per column. Done tasks and their cycle time go on the row of the
+    /// last column, where they are, and stay empty on the others.
+    pub fn csv(&self) -> String {
+        let mut out = "board,column,tasks,overdue,done,cycle_days_average,cycle_days_median\n".to_string();
+        let days = |d: Option<f64>| d.map(|d| format!("{:.1}", d)).unwrap_or_default();
+        for board in &self.boards {
+            let last = board.columns.len().saturating_sub(1);
+            for (i, column) in board.columns.iter().enumerate() {
+                let done = [board.done.to_string(), days(board.cycle_days.average), days(board.cycle_days.median)];
+                let done = if i == last && last > 0 { done } else { Default::default() };
+                let _ = writeln!(out, "{},{},{},{},{}", csv_field(&board.breadcrumbs.join(" > ")), csv_field(&column.title), column.tasks, column.overdue, done.join(","));
+            }
+        }
+        out
+    }
+}
+
+/// Monday 00:00 of the week `today` is in, in the configured zone.
+pub fn week_start(today: NaiveDate) -> DateTime<Utc> {
+    let monday = today.checked_sub_days(Days::new(today.weekday().num_days_from_monday().into())).unwrap_or(today);
+    zone::start_of_day(monday)
+}
+
+/// Stats for `root` and every board inside it, counting what happened from `since` to `until`.
+pub fn workspace(root: &Board, since: DateTime<Utc>, until: DateTime<Utc>) -> WorkspaceStats {
+    let mut boards = Vec::new();
+    collect(root, &mut vec![root.title.clone()], since, until, &mut boards);
+    WorkspaceStats { since, until, boards }
+}
+
+fn collect(board: &Board, crumbs: &mut Vec<String>, since: DateTime<Utc>, now: DateTime<Utc>, out: &mut Vec<BoardStats>) {
+    let last = board.columns.len().checked_sub(1).filter(|&l| l > 0);
+    let columns: Vec<ColumnStats> = board.columns.iter().enumerate().map(|(i, c)| ColumnStats {
+        title: c.title.clone(),
+        tasks: c.tasks.len(),
+        overdue: if Some(i) == last || c.icebox { 0 } else { c.tasks.iter().filter(|t| t.is_overdue(now)).count() },
+    }).collect();
+    let tasks = || board.columns.iter().flat_map(|c| c.tasks.iter());
+    // Finished tasks archived since still count as done
+    let samples: Vec<f64> = last.map_or(Vec::new(), |l| board.columns[l].tasks.iter()
+        .chain(board.archive.iter().filter(|a| a.column == board.columns[l].title).map(|a| &a.task))
+        .filter(|t| t.entered_column_at >= since)
+        .map(|t| (t.entered_column_at - t.created_at).num_seconds().max(0) as f64 / 86_400.0)
+        .collect());
+    out.push(BoardStats {
+        breadcrumbs: crumbs.clone(),
+        open: board.open_tasks(),
+        done: samples.len(),
+        // Icebox tasks are parked, not in flight, as in `Board::open_tasks`
+        moved: board.columns.iter().filter(|c| !c.icebox).flat_map(|c| c.tasks.iter()).filter(|t| t.entered_column_at >= since).count(),
+        overdue: columns.iter().map(|c| c.overdue).sum(),
+        cycle_days: CycleTime::of(&samples),
+        columns,
+        samples,
+    });
+    for task in tasks() {
+        if let Some(TaskContent::Board(sub)) = &task.content {
+            crumbs.push(task.title.clone());
+            collect(sub, crumbs, since, now, out);
+            crumbs.pop();
+        }
+    }
+}
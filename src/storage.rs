@@ -0,0 +1,298 @@
+use crate::model::{self, Board};
+use anyhow::{anyhow, bail, Result};
+use bincode::config;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+pub const DB_FILE: &str = "kanban.db";
+
+// Files start with this tag followed by the bincode-encoded `Workspaces`.
+// bincode data doesn't describe itself, so any change to what is serialized
+// needs a new tag and a decoder for the layout it replaces. Files without a
+// tag predate the header and use the original layout (see `legacy`).
+const MAGIC: &[u8; 4] = b"KBN2";
+
+/// Every top-level board in a file ("Work", "Personal", ...). Each is a root
+/// board titled with the workspace name; `active` is the one last opened.
+#[derive(Serialize, Deserialize)]
+pub struct Workspaces {
+    pub active: usize,
+    pub boards: Vec<Board>,
+}
+
+// Same encoding as `Workspaces`, for saving boards that aren't in one vector
+#[derive(Serialize)]
+struct WorkspacesRef<'a> {
+    active: usize,
+    boards: Vec<&'a Board>,
+}
+
+/// Loads every workspace from `path`, or a single default board when the file
+/// is missing. Each one is checked with `model::validate`.
+pub fn load_workspaces(path: &Path) -> Result<Workspaces> {
+    let workspaces = if path.exists() {
+        decode(&fs::read(path)?).ok_or_else(|| {
+            anyhow!("{} could not be decoded; refusing to overwrite it", path.display())
+        })?
+    } else {
+        Workspaces { active: 0, boards: vec![Board::default()] }
+    };
+    if workspaces.active >= workspaces.boards.len() {
+        bail!("{} has no workspace {}", path.display(), workspaces.active);
+    }
+
+    // Refuse to open (and later autosave over) a tree that breaks model invariants
+    for root in &workspaces.boards {
+        if let Err(errors) = model::validate(root) {
+            let details: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            bail!("{} failed validation in '{}': {}", path.display(), root.title, details.join("; "));
+        }
+    }
+    Ok(workspaces)
+}
+
+/// Loads the active workspace's board tree from `path`.
+pub fn load(path: &Path) -> Result<Board> {
+    let mut workspaces = load_workspaces(path)?;
+    Ok(workspaces.boards.swap_remove(workspaces.active))
+}
+
+/// Writes a new file holding `board` as its only workspace.
+pub fn save(path: &Path, board: &Board) -> Result<()> {
+    save_workspaces(path, 0, vec![board])
+}
+
+/// Writes `board` back as the active workspace of `path`, keeping the others.
+pub fn save_active(path: &Path, board: &Board) -> Result<()> {
+    if !path.exists() {
+        return save(path, board);
+    }
+    let workspaces = load_workspaces(path)?;
+    let boards = workspaces.boards.iter().enumerate()
+        .map(|(i, b)| if i == workspaces.active { board } else { b })
+        .collect();
+    save_workspaces(path, workspaces.active, boards)
+}
+
+/// Writes every workspace to `path`. The bytes go to a temporary file that then
+/// replaces `path`, so a reader never sees half a file.
+pub fn save_workspaces(path: &Path, active: usize, boards: Vec<&Board>) -> Result<()> {
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend(bincode::serde::encode_to_vec(WorkspacesRef { active, boards }, config::standard())?);
+    let tmp = sibling(path, "tmp");
+    fs::write(&tmp, bytes)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// When `path` was last written, to notice saves by another process; `None` if it is missing.
+pub fn stamp(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+// How long `lock` waits for another process to finish with the file
+const LOCK_WAIT: Duration = Duration::from_secs(3);
+
+/// Held while a process reads, changes and writes back `path`, so the TUI and
+/// headless commands (`kanban add` from a capture shortcut, say) take turns.
+/// The lock is a `<file>.lock` file next to it, removed on drop.
+pub struct Lock(PathBuf);
+
+pub fn lock(path: &Path) -> Result<Lock> {
+    let file = sibling(path, "lock");
+    let started = Instant::now();
+    loop {
+        match OpenOptions::new().write(true).create_new(true).open(&file) {
+            Ok(_) => return Ok(Lock(file)),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists && started.elapsed() < LOCK_WAIT => {
+                thread::sleep(Duration::from_millis(50));
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => bail!(
+                "{} is locked by another kanban process (remove {} if none is running)",
+                path.display(),
+                file.display()
+            ),
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+/// `kanban.db` -> `kanban.db.<ext>`, for files kept beside the board file.
+pub fn sibling(path: &Path, ext: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(ext);
+    PathBuf::from(name)
+}
+
+fn decode(data: &[u8]) -> Option<Workspaces> {
+    if let Some(body) = data.strip_prefix(MAGIC) {
+        return bincode::serde::decode_from_slice(body, config::standard()).ok().map(|(w, _)| w);
+    }
+    // The old layout holds one board, which becomes the only workspace
+    let board: Board = bincode::serde::decode_from_slice::<legacy::Board, _>(data, config::standard()).ok()?.0.into();
+    Some(Workspaces { active: 0, boards: vec![board] })
+}
+
+// The pre-header layout, kept only to upgrade old files on load.
+mod legacy {
+    use crate::model;
+    use serde::Deserialize;
+    use uuid::Uuid;
+
+    #[derive(Deserialize)]
+    pub struct Board {
+        title: String,
+        columns: Vec<Column>,
+    }
+
+    #[derive(Deserialize)]
+    struct Column {
+        title: String,
+        tasks: Vec<Task>,
+    }
+
+    #[derive(Deserialize)]
+    struct Task {
+        id: Uuid,
+        title: String,
+        description: String,
+        content: Option<TaskContent>,
+    }
+
+    #[derive(Deserialize)]
+    enum TaskContent {
+        Board(Board),
+        Todo(Vec<TodoItem>),
+        Text(String),
+    }
+
+    #[derive(Deserialize)]
+    struct TodoItem {
+        text: String,
+        done: bool,
+    }
+
+    impl From<Board> for model::Board {
+        fn from(b: Board) -> Self {
+            let mut board = model::Board { title: b.title, ..Default::default() };
+            board.columns = b.columns.into_iter().map(|c| {
+                let mut column = model::Column::new(&c.title);
+                column.tasks = c.tasks.into_iter().map(Into::into).collect();
+                column
+            }).collect();
+            board
+        }
+    }
+
+    impl From<Task> for model::Task {
+        fn from(t: Task) -> Self {
+            let mut task = model::Task::new(&t.title, &t.description);
+            task.id = t.id;
+            task.content = t.content.map(|c| match c {
+                TaskContent::Board(b) => model::TaskContent::Board(b.into()),
+                TaskContent::Todo(items) => model::TaskContent::Todo(
+                    items.into_iter().map(|i| model::TodoItem { text: i.text, done: i.done }).collect(),
+                ),
+                TaskContent::Text(s) => model::TaskContent::Text(s),
+            });
+            task
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Column, Task, TaskContent, TodoItem};
+
+    fn tree() -> Board {
+        let mut root = Board::default();
+        let mut sub = Board { title: "Launch".to_string(), columns: vec![Column::new("Now")], ..Default::default() };
+        sub.columns[0].tasks.push(Task::new("Ship it", ""));
+        let mut card = Task::new("Launch", "");
+        card.content = Some(TaskContent::Board(sub));
+        root.columns[0].tasks.push(card);
+        let mut list = Task::new("Groceries", "milk");
+        list.content = Some(TaskContent::Todo(vec![TodoItem { text: "Eggs".to_string(), done: true }]));
+        root.columns[1].tasks.push(list);
+        root
+    }
+
+    #[test]
+    fn workspaces_round_trip() {
+        let dir = std::env::temp_dir().join(format!("kanban-storage-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(DB_FILE);
+        let work = tree();
+        let personal = Board { title: "Personal".to_string(), ..Default::default() };
+        save_workspaces(&path, 1, vec![&work, &personal]).unwrap();
+
+        let data = fs::read(&path).unwrap();
+        assert!(data.starts_with(MAGIC));
+        let workspaces = decode(&data).unwrap();
+        assert_eq!(workspaces.active, 1);
+        assert_eq!(workspaces.boards, [work, personal]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // The pre-header layout, written field by field as it was
+    #[derive(Serialize)]
+    struct OldBoard {
+        title: String,
+        columns: Vec<(String, Vec<OldTask>)>,
+    }
+
+    #[derive(Serialize)]
+    struct OldTask {
+        id: uuid::Uuid,
+        title: String,
+        description: String,
+        content: Option<OldContent>,
+    }
+
+    #[derive(Serialize)]
+    enum OldContent {
+        #[allow(dead_code)]
+        Board(OldBoard),
+        Todo(Vec<(String, bool)>),
+    }
+
+    #[test]
+    fn headerless_files_load_as_one_workspace() {
+        let id = uuid::Uuid::from_u128(42);
+        let old = OldBoard {
+            title: "Main Board".to_string(),
+            columns: vec![("To Do".to_string(), vec![OldTask {
+                id,
+                title: "Groceries".to_string(),
+                description: "milk".to_string(),
+                content: Some(OldContent::Todo(vec![("Eggs".to_string(), true)])),
+            }])],
+        };
+        let data = bincode::serde::encode_to_vec(&old, config::standard()).unwrap();
+        let workspaces = decode(&data).unwrap();
+        assert_eq!(workspaces.active, 0);
+        let [board] = workspaces.boards.as_slice() else { panic!("one workspace") };
+        assert_eq!(board.columns[0].title, "To Do");
+        let task = &board.columns[0].tasks[0];
+        assert_eq!((task.id, task.title.as_str(), task.description.as_str()), (id, "Groceries", "milk"));
+        assert_eq!(task.content, Some(TaskContent::Todo(vec![TodoItem { text: "Eggs".to_string(), done: true }])));
+    }
+
+    #[test]
+    fn garbage_is_not_decoded() {
+        assert!(decode(b"").is_none());
+        assert!(decode(b"KBN2\xff\xff\xff").is_none());
+        assert!(decode(b"not a board at all").is_none());
+    }
+}
@@ -0,0 +1,121 @@
+use crate::model::{self, Board, Column, Task, TaskContent, TodoItem, WaitingOn};
+use crate::zone;
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+
+// Synthetic boards for stress testing (`kanban gen`). The same seed always builds
+// the same tree, ids included (dates are relative to today), so timings can be
+// compared across changes.
+
+const COLUMNS: &[&str] = &["Backlog", "In Progress", "Review", "Done"];
+const SUB_COLUMNS: &[&str] = &["Todo", "Doing", "Done"];
+const WORDS: &[&str] = &["Fix", "Add", "Refactor", "Document", "Review", "Migrate", "Test", "Profile", "Design", "Release"];
+const OBJECTS: &[&str] = &["login", "parser", "cache", "exporter", "sidebar", "billing", "search", "sync", "onboarding", "reports"];
+const TAGS: &[&str] = &["#bug", "#ui", "#perf", "#docs", "#infra"];
+const PEOPLE: &[&str] = &["alice", "bob", "design team", "vendor"];
+const MAX_SUB_BOARDS: usize = 8; // Per board
+const MIN_SPLIT: usize = 20; // Boards with fewer tasks than this hold them all directly
+
+pub struct Stats {
+    pub tasks: usize,
+    pub boards: usize, // Sub-boards, not counting the root
+    pub todo_items: usize,
+}
+
+struct Generator {
+    state: u64,
+    today: NaiveDate,
+    midnight: DateTime<Utc>, // Start of today, so timestamps don't depend on the time of day
+    stats: Stats,
+}
+
+/// A board tree with exactly `tasks` tasks (sub-board cards included) nested
+/// at most `depth` boards deep; depth 1 is a single flat board.
+pub fn generate(tasks: usize, depth: usize, seed: u64) -> (Board, Stats) {
+    model::seed_ids(seed);
+    let today = zone::today();
+    let mut generator = Generator {
+        state: seed,
+        today,
+        midnight: today.and_time(NaiveTime::MIN).and_utc(),
+        stats: Stats { tasks: 0, boards: 0, todo_items: 0 },
+    };
+    let mut root = Board { title: "Generated".to_string(), columns: COLUMNS.iter().map(|t| Column::new(t)).collect(), ..Default::default() };
+    generator.fill(&mut root, tasks, depth.max(1));
+    (root, generator.stats)
+}
+
+impl Generator {
+    fn below(&mut self, n: usize) -> usize {
+        (model::splitmix64(&mut self.state) % n as u64) as usize
+    }
+
+    fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[self.below(items.len())]
+    }
+
+    // A fifth of the budget stays on this board, the rest is split between its sub-boards
+    fn fill(&mut self, board: &mut Board, budget: usize, depth: usize) {
+        let subs = if depth > 1 && budget >= MIN_SPLIT { (budget / MIN_SPLIT).min(MAX_SUB_BOARDS) } else { 0 };
+        let nested = (budget - subs) * 4 / 5 * usize::from(subs > 0);
+        let local = budget - subs - nested;
+
+        for i in 0..local {
+            let task = self.task();
+            // One per column first, so no column stays empty on small boards
+            let column = if i < board.columns.len() { i } else { self.below(board.columns.len()) };
+            board.columns[column].tasks.push(task);
+        }
+        for i in 0..subs {
+            let share = nested / subs + usize::from(i < nested % subs);
+            let mut sub = Board { title: format!("Project {}", self.stats.boards + 1), columns: SUB_COLUMNS.iter().map(|t| Column::new(t)).collect(), ..Default::default() };
+            self.stats.boards += 1;
+            self.fill(&mut sub, share, depth - 1);
+
+            let mut card = Task::new(&sub.title, "");
+            card.entered_column_at = self.midnight;
+            card.created_at = self.midnight;
+            card.content = Some(TaskContent::Board(sub));
+            self.stats.tasks += 1;
+            let column = self.below(board.columns.len() - 1); // Anywhere but the last column
+            board.columns[column].tasks.push(card);
+        }
+    }
+
+    // A leaf task with a mix of content and badges
+    fn task(&mut self) -> Task {
+        self.stats.tasks += 1;
+        let title = format!("{} {} {}", self.pick(WORDS), self.pick(OBJECTS), self.stats.tasks);
+        let mut task = Task::new(&title, "");
+        if self.below(4) == 0 {
+            let tag = self.pick(TAGS);
+            task.title = format!("{} {}", task.title, tag);
+        }
+        if self.below(3) == 0 {
+            task.description = format!("Generated task {} for load testing.", self.stats.tasks);
+        }
+        task.content = match self.below(6) {
+            0 | 1 => {
+                let items: Vec<TodoItem> = (0..self.below(9))
+                    .map(|i| TodoItem { text: format!("Step {}", i + 1), done: self.below(2) == 0 })
+                    .collect();
+                self.stats.todo_items += items.len();
+                Some(TaskContent::Todo(items))
+            }
+            2 => Some(TaskContent::Text(format!("Notes for {}", title))),
+            _ => None,
+        };
+        task.entered_column_at = self.midnight - Duration::days(self.below(60) as i64);
+        task.created_at = task.entered_column_at;
+        if self.below(6) == 0 {
+            task.due = Some(zone::start_of_day(self.today + Duration::days(self.below(40) as i64 - 10)));
+        }
+        if self.below(12) == 0 {
+            let who = self.pick(PEOPLE).to_string();
+            task.waiting_on = Some(WaitingOn { who, since: self.today - Duration::days(self.below(10) as i64) });
+        }
+        if self.below(20) == 0 {
+            task.snoozed_until = Some(self.today + Duration::days(self.below(14) as i64 + 1));
+        }
+        task
+    }
+}
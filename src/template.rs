@@ -0,0 +1,152 @@
+use crate::defaults::TaskDefaults;
+use crate::model::{self, Board, Column, Task, TaskContent, TodoItem};
+use crate::reset::{ColumnReset, ResetPeriod};
+use crate::sort::ColumnSort;
+use crate::zone;
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+// Declarative board definition for `init --template`, e.g.
+//
+//   title: Sprint 12
+//   description: Ship the importer
+//   columns:
+//     - title: Backlog
+//       tasks:
+//         - title: Release checklist
+//           todo: [Tag version, Publish notes]
+//         - title: Design
+//           board:
+//             title: Design
+//             columns: [{ title: Ideas }, { title: Approved }]
+//     - title: This Week
+//       reset: monday
+//       reset_into: Backlog
+//     - title: In Progress
+//       wip: 3
+//     - title: Blocked
+//       requires_reason: true
+//     - title: Bugs
+//       defaults: "#bug pri:A"
+//     - title: Someday
+//       icebox: true
+//     - title: Definition of Done
+//       frozen: true
+//     - title: Done
+//       sort: entered desc
+#[derive(Deserialize, Debug)]
+pub struct BoardTemplate {
+    title: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    columns: Vec<ColumnTemplate>,
+    #[serde(default)]
+    frozen: bool,
+}
+
+#[derive(Deserialize, Debug)]
+struct ColumnTemplate {
+    title: String,
+    #[serde(default)]
+    wip: Option<usize>,
+    #[serde(default)]
+    reset: Option<String>, // "daily" or a weekday
+    #[serde(default)]
+    reset_into: Option<String>,
+    #[serde(default)]
+    requires_reason: bool,
+    #[serde(default)]
+    icebox: bool,
+    #[serde(default)]
+    frozen: bool,
+    #[serde(default)]
+    sort: Option<String>, // As for `:sort`, e.g. "due" or "entered desc"
+    #[serde(default)]
+    defaults: Option<String>, // As for `:defaults`, e.g. "#bug due:3d"
+    #[serde(default)]
+    tasks: Vec<TaskTemplate>,
+}
+
+// A task may carry at most one kind of content
+#[derive(Deserialize, Debug)]
+struct TaskTemplate {
+    title: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    board: Option<BoardTemplate>,
+    #[serde(default)]
+    todo: Option<Vec<String>>,
+    #[serde(default)]
+    note: Option<String>,
+}
+
+/// Reads a template, picking TOML for `.toml` files and YAML otherwise.
+pub fn load(path: &Path) -> Result<BoardTemplate> {
+    let text = fs::read_to_string(path).with_context(|| format!("cannot read {}", path.display()))?;
+    let parsed = if path.extension().is_some_and(|e| e == "toml") {
+        toml::from_str(&text).map_err(anyhow::Error::from)
+    } else {
+        serde_yaml::from_str(&text).map_err(anyhow::Error::from)
+    };
+    parsed.with_context(|| format!("invalid template {}", path.display()))
+}
+
+impl BoardTemplate {
+    pub fn build(self) -> Result<Board> {
+        let board = self.into_board()?;
+        if let Err(errors) = model::validate(&board) {
+            let details: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            bail!("template failed validation: {}", details.join("; "));
+        }
+        Ok(board)
+    }
+
+    fn into_board(self) -> Result<Board> {
+        let mut columns = Vec::new();
+        for c in self.columns {
+            let mut column = Column::new(&c.title);
+            column.wip_limit = c.wip;
+            column.requires_reason = c.requires_reason;
+            column.icebox = c.icebox;
+            column.frozen = c.frozen;
+            if let Some(sort) = c.sort {
+                column.sort = Some(ColumnSort::parse(&sort)
+                    .ok_or_else(|| anyhow!("column '{}': sort must be title, due or entered, optionally followed by asc or desc", c.title))?);
+            }
+            if let Some(defaults) = c.defaults {
+                column.defaults = TaskDefaults::parse(&defaults, zone::today())
+                    .map_err(|e| anyhow!("column '{}': defaults: {}", c.title, e))?;
+            }
+            if let Some(period) = c.reset {
+                let period = ResetPeriod::parse(&period)
+                    .ok_or_else(|| anyhow!("column '{}': reset must be 'daily' or a weekday", c.title))?;
+                column.reset = Some(ColumnReset::new(period, c.reset_into, zone::today()));
+            }
+            for t in c.tasks {
+                column.tasks.push(t.into_task()?);
+            }
+            columns.push(column);
+        }
+        Ok(Board { title: self.title, columns, description: self.description, frozen: self.frozen, wide_cards: false, archive: Vec::new(), trash: Vec::new(), lanes: None })
+    }
+}
+
+impl TaskTemplate {
+    fn into_task(self) -> Result<Task> {
+        let mut task = Task::new(&self.title, &self.description);
+        task.content = match (self.board, self.todo, self.note) {
+            (None, None, None) => None,
+            (Some(board), None, None) => Some(TaskContent::Board(board.into_board()?)),
+            (None, Some(items), None) => Some(TaskContent::Todo(
+                items.into_iter().map(|text| TodoItem { text, done: false }).collect(),
+            )),
+            (None, None, Some(note)) => Some(TaskContent::Text(note)),
+            _ => bail!("task '{}' has more than one of board/todo/note", self.title),
+        };
+        Ok(task)
+    }
+}
@@ -0,0 +1,299 @@
+use crate::config::{ColorDepth, Colors, IconStyle, ThemeName, UiConfig};
+use ratatui::style::{Color, Modifier, Style};
+use std::sync::OnceLock;
+
+// How the terminal UI looks: `[ui]` from the config, set once at startup like
+// the time zone, so drawing code and view models read it without an `App`.
+
+static UI: OnceLock<UiConfig> = OnceLock::new();
+static THEME: OnceLock<Theme> = OnceLock::new();
+static DEPTH: OnceLock<ColorDepth> = OnceLock::new();
+
+/// Uses `ui` from now on; without a call the defaults apply.
+pub fn init(ui: UiConfig) {
+    let _ = UI.set(ui);
+}
+
+fn ui() -> &'static UiConfig {
+    UI.get_or_init(UiConfig::default)
+}
+
+/// The `[ui] theme` with the `[ui.colors]` overrides applied and brought
+/// down to what the terminal shows, or no color at all when `monochrome`.
+pub fn colors() -> &'static Theme {
+    THEME.get_or_init(|| if monochrome() { Theme::mono() } else { Theme::named(ui().theme).with(&ui().colors).within(depth()) })
+}
+
+/// `[ui] monochrome`, `--no-color` or no color support: state shows through
+/// markers, bold, underline and reverse video instead of color.
+pub fn monochrome() -> bool {
+    ui().monochrome || depth() == ColorDepth::None
+}
+
+/// `[ui] color_depth`, or when that is `auto`, what the environment says.
+pub fn depth() -> ColorDepth {
+    *DEPTH.get_or_init(|| match ui().color_depth {
+        ColorDepth::Auto => detect_depth(|name| std::env::var(name).ok()),
+        depth => depth,
+    })
+}
+
+// NO_COLOR (https://no-color.org) wins, then COLORTERM, then TERM's name;
+// terminals that say nothing get the 16 colors all of them have
+fn detect_depth(var: impl Fn(&str) -> Option<String>) -> ColorDepth {
+    if var("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+        return ColorDepth::None;
+    }
+    // Windows Terminal sets no TERM, but has all the colors
+    if var("COLORTERM").is_some_and(|v| v == "truecolor" || v == "24bit") || var("WT_SESSION").is_some() {
+        return ColorDepth::TrueColor;
+    }
+    match var("TERM").unwrap_or_default().as_str() {
+        "dumb" => ColorDepth::None,
+        term if term.contains("truecolor") || term.contains("direct") => ColorDepth::TrueColor,
+        term if term.contains("256") => ColorDepth::Ansi256,
+        _ => ColorDepth::Ansi16,
+    }
+}
+
+// xterm's levels for each channel of its 6x6x6 color cube (indices 16-231)
+const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+// The 16 named colors, as xterm draws them
+const ANSI: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// `color` if the terminal has it, else the nearest color it does have.
+pub fn downgrade(color: Color, depth: ColorDepth) -> Color {
+    match (color, depth) {
+        (Color::Rgb(r, g, b), ColorDepth::Ansi256) => Color::Indexed(nearest_indexed((r, g, b))),
+        (Color::Rgb(r, g, b), ColorDepth::Ansi16) => nearest_ansi((r, g, b)),
+        (Color::Indexed(i), ColorDepth::Ansi16) if i >= 16 => nearest_ansi(indexed_rgb(i)),
+        (Color::Indexed(i), ColorDepth::Ansi16) => ANSI[i as usize].0,
+        _ => color,
+    }
+}
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+    d(r1, r2) + d(g1, g2) + d(b1, b2)
+}
+
+// Of the cube and the gray ramp (232-255); the first 16 vary between terminals
+fn nearest_indexed(rgb: (u8, u8, u8)) -> u8 {
+    (16..=255).min_by_key(|&i| distance(rgb, indexed_rgb(i))).expect("240 colors")
+}
+
+fn nearest_ansi(rgb: (u8, u8, u8)) -> Color {
+    ANSI.iter().min_by_key(|(_, ansi)| distance(rgb, *ansi)).expect("16 colors").0
+}
+
+// What a palette index shows on xterm
+fn indexed_rgb(i: u8) -> (u8, u8, u8) {
+    match i {
+        0..=15 => ANSI[i as usize].1,
+        16..=231 => {
+            let i = i - 16;
+            (CUBE[(i / 36) as usize], CUBE[(i / 6 % 6) as usize], CUBE[(i % 6) as usize])
+        }
+        _ => {
+            let v = 8 + 10 * (i - 232);
+            (v, v, v)
+        }
+    }
+}
+
+/// The card, row or day under the cursor.
+pub fn selected() -> Style {
+    let style = Style::default().fg(colors().selected_fg).bg(colors().selected_bg);
+    if monochrome() { style.add_modifier(Modifier::REVERSED) } else { style }
+}
+
+/// Search hits and jump labels.
+pub fn highlighted() -> Style {
+    let style = Style::default().fg(colors().search_hit_fg).bg(colors().search_hit_bg);
+    if monochrome() { style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED) } else { style }
+}
+
+/// `emoji`, or `ascii` with `icons = "ascii"`.
+pub fn icon(emoji: &'static str, ascii: &'static str) -> &'static str {
+    match ui().icons {
+        IconStyle::Emoji => emoji,
+        IconStyle::Ascii => ascii,
+    }
+}
+
+// Every color the UI draws with, by what it is used for
+#[derive(Debug, Clone)]
+pub struct Theme {
+    pub border_active: Color, // Focused column and popups
+    pub border: Color,
+    pub selected_bg: Color,
+    pub selected_fg: Color,
+    pub search_hit_bg: Color, // Cards matching the `/` search, jump labels
+    pub search_hit_fg: Color,
+    pub board: Color, // Sub-board icons and badges
+    pub todo: Color, // Todo list icons and progress
+    pub text: Color, // Note icons
+    pub alert: Color, // Over WIP limits, overdue, blocked, error messages
+    pub foreground: Color, // Card titles and other plain text
+    pub dim: Color, // Key hints, separators, empty cards and the like
+    pub muted: Color, // Done todo items, board notes
+    pub accent: Color, // Focused column title, typed input, today on the agenda
+    pub heading: Color, // Current breadcrumb, footer hints, popup headings
+    pub warning: Color, // Due soon, unsaved changes, advisor nudges
+    pub success: Color, // Replacement text
+    pub popup_border: Color, // Menus, help and the replace prompt
+    pub input_border: Color, // Text entry popups
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            border_active: Color::Green,
+            border: Color::DarkGray,
+            selected_bg: Color::Blue,
+            selected_fg: Color::White,
+            search_hit_bg: Color::Yellow,
+            search_hit_fg: Color::Black,
+            board: Color::Yellow,
+            todo: Color::Cyan,
+            text: Color::Magenta,
+            alert: Color::Red,
+            foreground: Color::White,
+            dim: Color::DarkGray,
+            muted: Color::Gray,
+            accent: Color::Yellow,
+            heading: Color::Cyan,
+            warning: Color::Yellow,
+            success: Color::Green,
+            popup_border: Color::Yellow,
+            input_border: Color::Blue,
+        }
+    }
+
+    // Yellow and cyan wash out on white, so they give way to darker shades
+    pub fn light() -> Self {
+        Self {
+            border: Color::Gray,
+            selected_bg: Color::Blue,
+            selected_fg: Color::White,
+            board: Color::Rgb(0xaf, 0x87, 0x00),
+            todo: Color::Rgb(0x00, 0x87, 0x87),
+            foreground: Color::Black,
+            dim: Color::Gray,
+            muted: Color::DarkGray,
+            accent: Color::Rgb(0xaf, 0x5f, 0x00),
+            heading: Color::Blue,
+            warning: Color::Rgb(0xaf, 0x5f, 0x00),
+            success: Color::Rgb(0x00, 0x87, 0x00),
+            popup_border: Color::Blue,
+            ..Self::dark()
+        }
+    }
+
+    // Ethan Schoonover's palette, on its dark background
+    pub fn solarized() -> Self {
+        let (base02, base01, base00, base0, base3) = (Color::Rgb(0x07, 0x36, 0x42), Color::Rgb(0x58, 0x6e, 0x75), Color::Rgb(0x65, 0x7b, 0x83), Color::Rgb(0x83, 0x94, 0x96), Color::Rgb(0xfd, 0xf6, 0xe3));
+        let (yellow, orange, red, magenta, violet, blue, cyan, green) = (
+            Color::Rgb(0xb5, 0x89, 0x00),
+            Color::Rgb(0xcb, 0x4b, 0x16),
+            Color::Rgb(0xdc, 0x32, 0x2f),
+            Color::Rgb(0xd3, 0x36, 0x82),
+            Color::Rgb(0x6c, 0x71, 0xc4),
+            Color::Rgb(0x26, 0x8b, 0xd2),
+            Color::Rgb(0x2a, 0xa1, 0x98),
+            Color::Rgb(0x85, 0x99, 0x00),
+        );
+        Self {
+            border_active: green,
+            border: base01,
+            selected_bg: blue,
+            selected_fg: base3,
+            search_hit_bg: yellow,
+            search_hit_fg: base02,
+            board: yellow,
+            todo: cyan,
+            text: magenta,
+            alert: red,
+            foreground: base0,
+            dim: base01,
+            muted: base00,
+            accent: yellow,
+            heading: cyan,
+            warning: orange,
+            success: green,
+            popup_border: violet,
+            input_border: blue,
+        }
+    }
+
+    // The terminal's own colors everywhere
+    pub fn mono() -> Self {
+        Self {
+            border_active: Color::Reset,
+            border: Color::Reset,
+            selected_bg: Color::Reset,
+            selected_fg: Color::Reset,
+            search_hit_bg: Color::Reset,
+            search_hit_fg: Color::Reset,
+            board: Color::Reset,
+            todo: Color::Reset,
+            text: Color::Reset,
+            alert: Color::Reset,
+            foreground: Color::Reset,
+            dim: Color::Reset,
+            muted: Color::Reset,
+            accent: Color::Reset,
+            heading: Color::Reset,
+            warning: Color::Reset,
+            success: Color::Reset,
+            popup_border: Color::Reset,
+            input_border: Color::Reset,
+        }
+    }
+
+    pub fn named(name: ThemeName) -> Self {
+        match name {
+            ThemeName::Dark => Self::dark(),
+            ThemeName::Light => Self::light(),
+            ThemeName::Solarized => Self::solarized(),
+        }
+    }
+
+    /// This theme with the colors `colors` sets replaced.
+    pub fn with(mut self, colors: &Colors) -> Self {
+        macro_rules! overrides {
+            ($($field:ident),*) => { $(if let Some(c) = colors.$field { self.$field = c; })* };
+        }
+        overrides!(border_active, border, selected_bg, selected_fg, search_hit_bg, search_hit_fg, board, todo, text, alert,
+            foreground, dim, muted, accent, heading, warning, success, popup_border, input_border);
+        self
+    }
+
+    /// This theme with every color `depth` can't show swapped for its nearest.
+    pub fn within(mut self, depth: ColorDepth) -> Self {
+        macro_rules! downgrade {
+            ($($field:ident),*) => { $(self.$field = downgrade(self.$field, depth);)* };
+        }
+        downgrade!(border_active, border, selected_bg, selected_fg, search_hit_bg, search_hit_fg, board, todo, text, alert,
+            foreground, dim, muted, accent, heading, warning, success, popup_border, input_border);
+        self
+    }
+}
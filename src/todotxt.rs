@@ -0,0 +1,162 @@
+use crate::model::{Board, Column, Task, TodoItem};
+use crate::zone;
+use anyhow::{bail, Result};
+use chrono::NaiveDate;
+
+// The todo.txt format (one task per line, `x ` when done) for todo lists and
+// flat boards. Its tokens become tags and back again:
+//   (A) Call Mom +family @phone due:2026-11-03
+//   Call Mom #family #@phone #pri-A                 (due 2026-11-03 on board tasks)
+// Board tasks in the last column are done; everything else is open. Creation
+// and completion dates are not kept on import.
+
+const TODO_COLUMN: &str = "To Do";
+const DONE_COLUMN: &str = "Done";
+const PRIORITY_TAG: &str = "pri-"; // `#pri-A` for `(A)`
+
+struct Entry {
+    done: bool,
+    title: String,
+    due: Option<NaiveDate>,
+}
+
+/// A flat board titled `title`: open lines in To Do, done ones in Done.
+pub fn board(text: &str, title: &str) -> Result<Board> {
+    let (mut todo, mut done) = (Column::new(TODO_COLUMN), Column::new(DONE_COLUMN));
+    for entry in text.lines().filter_map(|line| entry(line, true)) {
+        let mut task = Task::new(&entry.title, "");
+        task.due = entry.due.map(zone::start_of_day);
+        if entry.done { &mut done } else { &mut todo }.tasks.push(task);
+    }
+    if todo.tasks.is_empty() && done.tasks.is_empty() {
+        bail!("no tasks in the todo.txt file");
+    }
+    Ok(Board { title: title.to_string(), columns: vec![todo, done], description: String::new(), frozen: false, wide_cards: false, archive: Vec::new(), trash: Vec::new(), lanes: None })
+}
+
+/// Todo items, one per line; `due:` stays in the item text.
+pub fn items(text: &str) -> Result<Vec<TodoItem>> {
+    let items: Vec<TodoItem> = text.lines().filter_map(|line| entry(line, false)).map(|e| TodoItem { text: e.title, done: e.done }).collect();
+    if items.is_empty() {
+        bail!("no tasks in the todo.txt file");
+    }
+    Ok(items)
+}
+
+/// Every task of `board`'s columns, sub-boards left closed.
+pub fn render_board(board: &Board) -> String {
+    let last = board.columns.len().saturating_sub(1);
+    let mut out = String::new();
+    for (c, column) in board.columns.iter().enumerate() {
+        let done = c == last && last > 0;
+        for task in &column.tasks {
+            let completed = done.then(|| zone::local(task.entered_column_at).date());
+            out.push_str(&line(&task.title, done, completed, task.due.map(zone::due_date)));
+        }
+    }
+    out
+}
+
+pub fn render_items(items: &[TodoItem]) -> String {
+    items.iter().map(|item| line(&item.text, item.done, None, None)).collect()
+}
+
+fn entry(line: &str, take_due: bool) -> Option<Entry> {
+    let mut words = line.split_whitespace().peekable();
+    let done = words.next_if_eq(&"x").is_some();
+    let mut level = if done { None } else { words.next_if(|w| priority(w).is_some()).and_then(priority) };
+    // Completion and creation dates
+    while words.next_if(|w| NaiveDate::parse_from_str(w, "%Y-%m-%d").is_ok()).is_some() {}
+
+    let (mut title, mut due) = (Vec::new(), None);
+    for word in words {
+        if let Some(date) = word.strip_prefix("due:").and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok()).filter(|_| take_due) {
+            due = Some(date);
+        } else if let Some(p) = word.strip_prefix("pri:").and_then(|p| priority(&format!("({})", p))) {
+            // Where done tasks keep their priority
+            level = Some(p);
+        } else if let Some(tag) = word.strip_prefix('+').filter(|t| !t.is_empty()) {
+            title.push(format!("#{}", tag));
+        } else if word.len() > 1 && word.starts_with('@') {
+            title.push(format!("#{}", word));
+        } else {
+            title.push(word.to_string());
+        }
+    }
+    if let Some(p) = level {
+        title.push(format!("#{}{}", PRIORITY_TAG, p));
+    }
+    (!title.is_empty()).then(|| Entry { done, title: title.join(" "), due })
+}
+
+fn line(title: &str, done: bool, completed: Option<NaiveDate>, due: Option<NaiveDate>) -> String {
+    let mut level = None;
+    let words: Vec<String> = title.split_whitespace().filter_map(|word| match word.strip_prefix('#') {
+        Some(tag) if let Some(p) = tag.strip_prefix(PRIORITY_TAG).and_then(|p| priority(&format!("({})", p))) => {
+            level = Some(p);
+            None
+        }
+        Some(context) if context.len() > 1 && context.starts_with('@') => Some(context.to_string()),
+        Some(project) if !project.is_empty() => Some(format!("+{}", project)),
+        _ => Some(word.to_string()),
+    }).collect();
+
+    let mut out = String::new();
+    match (done, level) {
+        (true, _) => out.push_str("x "),
+        (false, Some(p)) => out.push_str(&format!("({}) ", p)),
+        (false, None) => {}
+    }
+    if let Some(day) = completed {
+        out.push_str(&format!("{} ", day));
+    }
+    out.push_str(&words.join(" "));
+    if let (true, Some(p)) = (done, level) {
+        out.push_str(&format!(" pri:{}", p));
+    }
+    if let Some(day) = due {
+        out.push_str(&format!(" due:{}", day));
+    }
+    out.push('\n');
+    out
+}
+
+// "(A)" -> 'A'
+fn priority(word: &str) -> Option<char> {
+    match word.as_bytes() {
+        [b'(', p @ b'A'..=b'Z', b')'] => Some(char::from(*p)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_become_tags() {
+        let home = board("(A) Call Mom +family @phone due:2026-11-03\nx 2026-10-01 2026-09-30 Pay rent pri:B\n\n", "Home").unwrap();
+        let [todo, done] = home.columns.as_slice() else { panic!("two columns") };
+        assert_eq!(todo.tasks[0].title, "Call Mom #family #@phone #pri-A");
+        assert_eq!(todo.tasks[0].due.map(zone::due_date), NaiveDate::from_ymd_opt(2026, 11, 3));
+        assert_eq!(done.tasks[0].title, "Pay rent #pri-B");
+        assert!(board("\n  \n", "Empty").is_err());
+    }
+
+    #[test]
+    fn items_keep_due_in_the_text() {
+        let list = items("Milk due:2026-11-03\nx Eggs\n").unwrap();
+        assert_eq!(list, vec![
+            TodoItem { text: "Milk due:2026-11-03".to_string(), done: false },
+            TodoItem { text: "Eggs".to_string(), done: true },
+        ]);
+    }
+
+    #[test]
+    fn round_trips_through_a_board() {
+        let text = "(A) Call Mom +family @phone due:2026-11-03\nWater plants\n";
+        assert_eq!(render_board(&board(text, "Home").unwrap()), text);
+        let items = [TodoItem { text: "Eggs #pri-C".to_string(), done: true }];
+        assert_eq!(render_items(&items), "x Eggs pri:C\n");
+    }
+}
@@ -0,0 +1,201 @@
+use crate::app::{Action, App, ExternalEdit, InputMode};
+use crate::demo::Step;
+use crate::keymap::Keymap;
+use crate::ui;
+use anyhow::{bail, Context, Result};
+use crossterm::{
+    event::{self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture, Event, KeyEventKind, MouseButton, MouseEvent, MouseEventKind},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{backend::{CrosstermBackend, TestBackend}, layout::Size, Terminal};
+use std::collections::VecDeque;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use unicode_width::UnicodeWidthStr;
+use uuid::Uuid;
+
+// The terminal frontend: raw mode, the event loop, and rendering a frame to text.
+
+// Shortest time between two frames while input keeps arriving (key repeat, paste)
+const FRAME: Duration = Duration::from_millis(16);
+// Longest time between the clicks of a double click
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+/// Runs `app` in the terminal until the user quits. `script` plays first, one
+/// step after another; a key press stops it and hands over.
+pub fn run(app: &mut App, script: Vec<Step>) -> Result<()> {
+    // Before raw mode, so a bad `[keys]` entry prints normally
+    let keymap = Keymap::with_keys(&app.config.keys)?;
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture, EnableBracketedPaste)?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    let res = run_app(&mut terminal, &keymap, app, script.into());
+
+    // Restore terminal
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        DisableBracketedPaste
+    )?;
+    terminal.show_cursor()?;
+    res
+}
+
+/// Plays `script` without pausing and writes the screen it ends on to `file`
+/// (`-` for stdout) as plain text, `size` cells large.
+pub fn render_frame(app: &mut App, script: Vec<Step>, size: Size, file: &Path) -> Result<()> {
+    for (_, action) in script {
+        app.update(action)?;
+    }
+    let mut terminal = Terminal::new(TestBackend::new(size.width, size.height))?;
+    let frame = terminal.draw(|f| ui::draw(f, app))?;
+    let mut text = String::new();
+    for row in frame.buffer.content.chunks(size.width.into()) {
+        let mut line = String::new();
+        let mut hidden = 0; // Cells covered by the wide symbol before them
+        for cell in row {
+            if hidden > 0 {
+                hidden -= 1;
+                continue;
+            }
+            line.push_str(cell.symbol());
+            hidden = cell.symbol().width().saturating_sub(1);
+        }
+        text.push_str(line.trim_end());
+        text.push('\n');
+    }
+    if file == Path::new("-") {
+        print!("{}", text);
+    } else {
+        fs::write(file, text)?;
+    }
+    Ok(())
+}
+
+fn run_app<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>, keymap: &Keymap, app: &mut App, mut script: VecDeque<Step>) -> Result<()> {
+    let mut last_frame: Option<Instant> = None;
+    let mut last_click: Option<(Instant, Uuid)> = None; // Card clicked last, for double clicks
+    let mut next_step = script.front().map(|(pause, _)| Instant::now() + *pause);
+    loop {
+        if let Some(edit) = app.external_edit.take() {
+            match edit_externally(&edit.text) {
+                Ok(text) => app.update(Action::ExternalEdited(ExternalEdit { text, ..edit }))?,
+                Err(err) => app.message = Some(format!("Editor: {:#}", err)),
+            }
+            terminal.clear()?;
+            app.redraw = true;
+        }
+
+        // Draw only when something changed, and hold off while more input is already queued
+        let busy = last_frame.is_some_and(|t| t.elapsed() < FRAME) && event::poll(Duration::ZERO)?;
+        if app.redraw && !busy {
+            terminal.draw(|f| ui::draw(f, app))?;
+            app.redraw = false;
+            last_frame = Some(Instant::now());
+        }
+
+        if let Some(at) = next_step
+            && at <= Instant::now() {
+                let (_, action) = script.pop_front().expect("next_step is set while steps are left");
+                app.update(action)?;
+                next_step = script.front().map(|(pause, _)| Instant::now() + *pause);
+                continue;
+            }
+
+        // Only wake up without input when a debounced save or a script step is pending
+        let wait = [app.next_tick(), next_step.map(|at| at.saturating_duration_since(Instant::now()))].into_iter().flatten().min();
+        let event = match wait {
+            Some(timeout) if !event::poll(timeout)? => {
+                app.tick();
+                continue;
+            }
+            _ => event::read()?,
+        };
+
+        if let Event::Resize(..) = event {
+            app.redraw = true;
+        }
+
+        if let Event::Mouse(mouse) = event
+            && let Some(action) = mouse_action(app, mouse, terminal.size()?, &mut last_click) {
+                app.update(action)?;
+            }
+
+        // Arrives whole, so a line break in it doesn't submit the popup
+        if let Event::Paste(text) = &event {
+            app.update(Action::InputPaste(text.clone()))?;
+        }
+
+        if let Event::Key(key) = event
+            && key.kind == KeyEventKind::Press {
+                if next_step.take().is_some() {
+                    script.clear();
+                    continue;
+                }
+                if let Some(action) = keymap.action(app, key) {
+                    app.update(action)?;
+                }
+            }
+
+        if app.should_quit {
+            return Ok(());
+        }
+    }
+}
+
+// Leaves the TUI for `$VISUAL` or `$EDITOR` (vi, or notepad on Windows) on a
+// temporary copy of `text`, and returns the copy as saved
+fn edit_externally(text: &str) -> Result<String> {
+    let editor = std::env::var("VISUAL").or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| if cfg!(windows) { "notepad" } else { "vi" }.to_string());
+    // `code --wait` and the like come with arguments
+    let mut words = editor.split_whitespace();
+    let program = words.next().context("$EDITOR is empty")?;
+    let file = std::env::temp_dir().join(format!("kanban-{}.md", Uuid::new_v4()));
+    fs::write(&file, text)?;
+
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture, DisableBracketedPaste)?;
+    let status = std::process::Command::new(program).args(words).arg(&file).status();
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture, EnableBracketedPaste)?;
+
+    let edited = fs::read_to_string(&file);
+    let _ = fs::remove_file(&file);
+    let status = status.with_context(|| format!("couldn't start {}", program))?;
+    if !status.success() {
+        bail!("{} exited with {}, nothing changed", program, status);
+    }
+    Ok(edited?)
+}
+
+// A click selects the card or column under it, and a second one on the same
+// card soon after opens it
+fn mouse_action(app: &App, mouse: MouseEvent, size: Size, last_click: &mut Option<(Instant, Uuid)>) -> Option<Action> {
+    if mouse.kind != MouseEventKind::Down(MouseButton::Left) || !matches!(app.input_mode, InputMode::Normal | InputMode::Agenda) || app.show_help {
+        return None;
+    }
+    // Breadcrumbs sit on the first line inside the header border
+    if mouse.row == 1 {
+        return ui::crumb_at(app, size.width, mouse.column).map(Action::JumpToLevel);
+    }
+    if app.input_mode == InputMode::Normal
+        && let Some((column, card)) = ui::board_hit(app, size, mouse.column, mouse.row) {
+            let Some(id) = card else { return Some(Action::SelectColumn(column)) };
+            let previous = last_click.replace((Instant::now(), id));
+            if previous.is_some_and(|(at, last)| last == id && at.elapsed() < DOUBLE_CLICK) {
+                *last_click = None;
+                return Some(Action::DrillDown);
+            }
+            return Some(Action::SelectTask(id));
+        }
+    ui::agenda_slot_at(app, size, mouse.column, mouse.row).map(Action::OpenAgendaSlot)
+}
@@ -1,38 +1,58 @@
 use ratatui::{
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Position, Rect, Size},
     style::{Color, Modifier, Style},
-    text::{Line, Span},
-    widgets::{Block, Borders, BorderType, List, ListItem, Paragraph, Clear, Wrap, Table, Row},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, BorderType, List, ListItem, ListState, Paragraph, Clear, Wrap, Table, Row},
     Frame,
 };
-use crate::app::{App, InputMode, ActiveContentRef};
-use crate::model::TaskContent;
+use crate::app::{App, InputMode, ActiveContentRef, RenameTarget, SaveFailure};
+use crate::command::COMMANDS;
+use crate::editor::Editor;
+use crate::keymap::CHORDS;
+use crate::markdown;
+use crate::model::{TaskContent, TrashedItem};
+use crate::replace::ReplaceSession;
+use crate::theme::{self, colors};
+use crate::agenda::{self, Slot};
+use crate::view::{self, CardKind, CardView, ColumnView, LaneView, Tone};
+use crate::zone;
+use chrono::{Datelike, Utc, Weekday};
+use std::rc::Rc;
+use unicode_width::UnicodeWidthChar;
+use uuid::Uuid;
 
-// Theme Constants
-
-const COLOR_BORDER_ACTIVE: Color = Color::Green;
-const COLOR_BORDER_INACTIVE: Color = Color::DarkGray;
-const COLOR_SELECTED_BG: Color = Color::Blue;
-const COLOR_SELECTED_FG: Color = Color::White;
-const COLOR_BOARD_ICON: Color = Color::Yellow;
-const COLOR_TODO_ICON: Color = Color::Cyan;
-const COLOR_TEXT_ICON: Color = Color::Magenta;
+const AGENDA_LATE_WIDTH: u16 = 8; // " late 12"
+const WIDE_CARD_LINES: usize = 2; // Lines a card's title may wrap over with `:wide`
 
 pub fn draw(f: &mut Frame, app: &App) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3), // Header
-            Constraint::Min(0),    // Main Content
-            Constraint::Length(3), // Footer / Help
-        ])
-        .split(f.area());
+    let outer = screen_layout(app, f.area());
+    let chunks = [outer[0], outer[2], outer[3]];
 
     draw_header(f, app, chunks[0]);
+    if let Some(failure) = &app.save_failure {
+        draw_save_error_banner(f, failure, outer[1]);
+    }
     
     // Determine what to draw based on active content
     match app.get_active_content() {
-        ActiveContentRef::Board(board) => draw_board(f, app, board, chunks[1]),
+        _ if app.input_mode == InputMode::Checklist => draw_checklist(f, app, chunks[1]),
+        _ if app.input_mode == InputMode::Results => draw_results(f, app, chunks[1]),
+        _ if app.input_mode == InputMode::Archive => draw_archive(f, app, chunks[1]),
+        _ if app.input_mode == InputMode::Trash => draw_trash(f, app, chunks[1]),
+        _ if app.input_mode == InputMode::Workspaces => draw_workspaces(f, app, chunks[1]),
+        ActiveContentRef::Board(board) => {
+            let layout = board_layout(app, board, chunks[1]);
+            if let Some(area) = layout.agenda {
+                draw_agenda(f, app, board, area);
+            }
+            if let Some(area) = layout.notes {
+                draw_board_notes(f, &board.description, area);
+            }
+            if let Some(area) = layout.details {
+                draw_details(f, app, board, area);
+            }
+            draw_board(f, app, board, &layout)
+        },
         ActiveContentRef::Todo(items) => draw_todo(f, app, items, chunks[1]),
         ActiveContentRef::Text(text) => draw_text_view(f, app, text, chunks[1]),
         ActiveContentRef::None => draw_empty_selection(f, chunks[1]), 
@@ -40,119 +60,630 @@ pub fn draw(f: &mut Frame, app: &App) {
 
     draw_footer(f, app, chunks[2]);
 
-    if app.input_mode == InputMode::Editing || app.input_mode == InputMode::EditingColumn {
+    if matches!(app.input_mode, InputMode::Editing | InputMode::EditingColumn | InputMode::EditingBoardNotes | InputMode::EditingBlockReason | InputMode::EditingDescription | InputMode::Renaming | InputMode::EditingColumnDefaults) {
         draw_input_popup(f, app);
     } else if app.input_mode == InputMode::SelectType {
         draw_type_selection_popup(f);
+    } else if app.input_mode == InputMode::Badges {
+        draw_badge_menu(f, app);
+    } else if let Some(session) = &app.replace {
+        draw_replace_popup(f, app, session);
+    } else if let Some((_, prompt)) = &app.pending_confirm {
+        draw_confirm_popup(f, app, prompt);
     }
+    draw_key_hints(f, app, chunks[2]);
     
     if app.show_help {
         draw_help_popup(f);
     }
 }
 
+// Header, save error banner, main content, footer
+fn screen_layout(app: &App, area: Rect) -> Rc<[Rect]> {
+    let banner_height = if app.save_failure.is_some() { 1 } else { 0 };
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Header
+            Constraint::Length(banner_height), // Save error banner
+            Constraint::Min(0),    // Main Content
+            Constraint::Length(3), // Footer / Help
+        ])
+        .split(area)
+}
+
+// Where the parts of a board view go; drawing and mouse clicks both go by it
+struct BoardLayout {
+    agenda: Option<Rect>,
+    notes: Option<Rect>,
+    details: Option<Rect>,
+    board: Rect, // What the columns share, see `lane_grid`
+}
+
+fn board_layout(app: &App, board: &crate::model::Board, area: Rect) -> BoardLayout {
+    let (area, agenda) = if app.show_agenda {
+        let parts = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(1)])
+            .split(area);
+        (parts[0], Some(parts[1]))
+    } else {
+        (area, None)
+    };
+    let (area, notes) = if app.show_board_notes && !board.description.is_empty() {
+        let height = (board.description.lines().count() as u16 + 2).min(area.height / 3).max(3);
+        let parts = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(height), Constraint::Min(0)])
+            .split(area);
+        (parts[1], Some(parts[0]))
+    } else {
+        (area, None)
+    };
+    let (area, details) = if app.show_details {
+        let parts = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Percentage(35)])
+            .split(area);
+        (parts[0], Some(parts[1]))
+    } else {
+        (area, None)
+    };
+    BoardLayout { agenda, notes, details, board: area }
+}
+
+// Each lane's header line (none on a board without lanes) and its cells, one
+// per column. Every lane gets room for a card, and the rest goes to the lanes
+// by how many cards their fullest cell has.
+fn lane_grid(lanes: &[LaneView], area: Rect) -> Vec<(Option<Rect>, Rc<[Rect]>)> {
+    let col_count = lanes.first().map_or(0, |l| l.columns.len());
+    let cells = |area: Rect| Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints((0..col_count).map(|_| Constraint::Percentage(100 / col_count.max(1) as u16)))
+        .split(area);
+    if let [lane] = lanes && lane.title.is_none() {
+        return vec![(None, cells(area))];
+    }
+    const LEAST: u16 = 4; // Header, borders and a card
+    let fullest: Vec<u16> = lanes.iter().map(|l| l.columns.iter().map(|c| c.cards.len() as u16).max().unwrap_or(0)).collect();
+    let spare = area.height.saturating_sub(LEAST * lanes.len() as u16);
+    let total = fullest.iter().sum::<u16>().max(1);
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(fullest.iter().enumerate().map(|(i, &n)| {
+            // The last lane takes what rounding leaves
+            if i + 1 == lanes.len() { Constraint::Min(0) } else { Constraint::Length(LEAST + (spare as u32 * n as u32 / total as u32) as u16) }
+        }))
+        .split(area);
+    rows.iter().map(|&row| {
+        let parts = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(row);
+        (Some(parts[0]), cells(parts[1]))
+    }).collect()
+}
+
+/// What a click at (`x`, `y`) on a `size` screen hits on the board in view: a
+/// column, and the task of the card under it if there is one.
+pub fn board_hit(app: &App, size: Size, x: u16, y: u16) -> Option<(usize, Option<Uuid>)> {
+    let ActiveContentRef::Board(board) = app.get_active_content() else { return None };
+    let layout = board_layout(app, board, screen_layout(app, Rect::new(0, 0, size.width, size.height))[2]);
+    let lanes = view::lanes(app, board);
+    let grid = lane_grid(&lanes, layout.board);
+    let (l, c) = grid.iter().enumerate().find_map(|(l, (_, cells))| Some((l, cells.iter().position(|r| r.contains(Position { x, y }))?)))?;
+    let area = grid[l].1[c];
+    let column = lanes.into_iter().nth(l)?.columns.into_iter().nth(c)?;
+    let (items, offset) = column_items(app, column.cards, area, board.wide_cards);
+    // Cards stack from just inside the top border, as tall as they are drawn
+    let mut top = area.y + 1;
+    for (id, item) in items.into_iter().skip(offset) {
+        let height = item.height() as u16;
+        if (top..top + height).contains(&y) && y < area.bottom() - 1 {
+            return Some((c, Some(id)));
+        }
+        top += height;
+    }
+    Some((c, None))
+}
+
+// Which-key style list of what can follow a chord prefix or a partly typed
+// command, drawn just above the footer until the next key
+fn draw_key_hints(f: &mut Frame, app: &App, footer: Rect) {
+    let hints: Vec<(String, &str)> = match app.pending_key {
+        Some(prefix) => CHORDS.iter()
+            .filter(|(p, ..)| *p == prefix)
+            .map(|(p, k, _, what)| (format!("{} {}", p, k), *what))
+            .collect(),
+        None if app.input_mode == InputMode::Command && !app.input_buffer.contains(' ') => COMMANDS.iter()
+            .filter(|(name, _)| name.starts_with(app.input_buffer.as_str()))
+            .map(|(name, what)| (name.to_string(), *what))
+            .collect(),
+        None => return,
+    };
+    if hints.is_empty() {
+        return;
+    }
+
+    let key_width = hints.iter().map(|(k, _)| k.len()).max().unwrap_or(0);
+    let width = hints.iter().map(|(_, what)| key_width + 2 + what.len()).max().unwrap_or(0) as u16 + 2;
+    let height = (hints.len() as u16 + 2).min(footer.y.saturating_sub(1));
+    let area = Rect { x: footer.x, y: footer.y - height, width: width.min(footer.width), height };
+    f.render_widget(Clear, area);
+
+    let lines: Vec<Line> = hints.into_iter().map(|(key, what)| Line::from(vec![
+        Span::styled(format!("{:<w$}  ", key, w = key_width), Style::default().fg(colors().accent)),
+        Span::raw(what),
+    ])).collect();
+    let p = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(colors().dim)));
+    f.render_widget(p, area);
+}
+
 fn draw_header(f: &mut Frame, app: &App, area: Rect) {
     let raw_crumbs = app.get_breadcrumbs();
     let mut spans = Vec::new();
     
-    for (i, crumb) in raw_crumbs.iter().enumerate() {
+    for (i, (level, crumb)) in fit_breadcrumbs(&raw_crumbs, area.width.saturating_sub(2)).into_iter().enumerate() {
         if i > 0 {
-            spans.push(Span::styled(" > ", Style::default().fg(Color::DarkGray)));
+            spans.push(Span::styled(CRUMB_SEPARATOR, Style::default().fg(colors().dim)));
         }
-        if i == raw_crumbs.len() - 1 {
+        match level {
             // Active
-             spans.push(Span::styled(crumb, Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)));
-        } else {
-             spans.push(Span::raw(crumb));
+            Some(l) if l == raw_crumbs.len() - 1 => {
+                spans.push(Span::styled(crumb, Style::default().fg(colors().heading).add_modifier(Modifier::BOLD)));
+            }
+            Some(_) => spans.push(Span::raw(crumb)),
+            None => spans.push(Span::styled(crumb, Style::default().fg(colors().dim))),
         }
     }
+    if let ActiveContentRef::Board(board) = app.get_active_content()
+        && board.frozen {
+            spans.push(Span::styled(format!(" {} frozen", theme::icon("🔒", "#")), Style::default().fg(colors().dim)));
+        }
+    if let Some(filter) = app.quick_filter {
+        spans.push(Span::styled(format!("  only {}", filter.label()), Style::default().fg(colors().accent)));
+    }
 
     let title = Paragraph::new(Line::from(spans))
         .block(Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
-            .border_style(Style::default().fg(COLOR_BORDER_INACTIVE))
+            .border_style(Style::default().fg(colors().border))
             .title(" Kanban CLI ")
             .title_alignment(Alignment::Center));
     
     f.render_widget(title, area);
 }
 
-fn draw_board(f: &mut Frame, app: &App, board: &crate::model::Board, area: Rect) {
-    let col_count = board.columns.len();
+fn draw_save_error_banner(f: &mut Frame, failure: &SaveFailure, area: Rect) {
+    let text = format!(
+        " ⚠ Save failed ({} attempt{}): {} — retrying; Ctrl+S to retry now ",
+        failure.attempts,
+        if failure.attempts == 1 { "" } else { "s" },
+        failure.error,
+    );
+    let banner = Paragraph::new(text)
+        .style(Style::default().fg(colors().foreground).bg(colors().alert).add_modifier(Modifier::BOLD));
+    f.render_widget(banner, area);
+}
+
+// The board description, above the columns
+fn draw_board_notes(f: &mut Frame, description: &str, area: Rect) {
+    let p = Paragraph::new(description)
+        .wrap(Wrap { trim: true })
+        .style(Style::default().fg(colors().muted))
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(colors().border))
+            .title(" Notes (gd: hide, gD: edit) "));
+    f.render_widget(p, area);
+}
+
+// The selected task's details, right of the columns
+fn draw_details(f: &mut Frame, app: &App, board: &crate::model::Board, area: Rect) {
+    let label = Style::default().fg(colors().dim);
+    let field = |name: &'static str, value: String| Line::from(vec![Span::styled(format!("{:<9}", name), label), Span::raw(value)]);
+    let lines = match app.selected_task() {
+        None => vec![Line::styled("No task selected", label)],
+        Some(task) => {
+            let today = zone::today();
+            let kind = match &task.content {
+                Some(TaskContent::Board(sub)) => format!("board, {} columns", sub.columns.len()),
+                Some(TaskContent::Todo(items)) => format!("todo, {}/{} done", items.iter().filter(|i| i.done).count(), items.len()),
+                Some(TaskContent::Text(_)) => "note".to_string(),
+                None => "none yet".to_string(),
+            };
+            let mut lines = vec![
+                Line::styled(task.title.clone(), Style::default().add_modifier(Modifier::BOLD)),
+                Line::raw(""),
+                field("Column", format!("{} for {}d", board.columns[app.pos.0].title, task.days_in_column(Utc::now()))),
+                field("Content", kind),
+            ];
+            if let Some(due) = task.due {
+                lines.push(field("Due", zone::format_due(due, "%a %Y-%m-%d")));
+            }
+            let upcoming: Vec<String> = task.reminders.iter().filter(|&&at| at > Utc::now()).map(|&at| zone::format_due(at, "%a %Y-%m-%d")).collect();
+            if !upcoming.is_empty() {
+                lines.push(field("Remind", upcoming.join(", ")));
+            }
+            if let Some(until) = task.snoozed_until.filter(|_| task.is_snoozed(today)) {
+                lines.push(field("Snoozed", format!("until {}", until.format("%a %Y-%m-%d"))));
+            }
+            if let Some(waiting) = &task.waiting_on {
+                lines.push(field("Waiting", format!("on {} since {}", waiting.who, waiting.since)));
+            }
+            if let Some(reason) = &task.blocked_reason {
+                lines.push(field("Blocked", reason.clone()));
+            }
+            if let Some(source) = &task.source {
+                lines.push(field("Source", format!("{} {}", source.system, source.id)));
+                if let Some(url) = &source.url {
+                    lines.push(field("", url.clone()));
+                }
+            }
+            lines.push(field("Id", task.id.to_string()[..8].to_string()));
+            lines.push(Line::raw(""));
+            if task.description.is_empty() {
+                lines.push(Line::styled("No description (I to add one)", label));
+            } else {
+                lines.extend(task.description.lines().map(|l| Line::raw(l.to_string())));
+            }
+            lines
+        }
+    };
+
+    let p = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(colors().border))
+            .title(" Details (I: edit) "));
+    f.render_widget(p, area);
+}
+
+const CRUMB_SEPARATOR: &str = " > ";
+const CRUMB_ELLIPSIS: &str = "…";
 
-    if col_count == 0 {
+/// Picks the crumbs that fit in `width` columns. The root and the deepest levels
+/// are kept and the middle collapses into an ellipsis (level `None`).
+fn fit_breadcrumbs(crumbs: &[String], width: u16) -> Vec<(Option<usize>, &str)> {
+    let width_of = |items: &[(Option<usize>, &str)]| -> usize {
+        items.iter().map(|(_, t)| Span::raw(*t).width()).sum::<usize>()
+            + CRUMB_SEPARATOR.len() * items.len().saturating_sub(1)
+    };
+
+    let mut shown: Vec<(Option<usize>, &str)> = crumbs.iter().enumerate().map(|(i, c)| (Some(i), c.as_str())).collect();
+    while width_of(&shown) > width as usize && shown.len() > 2 {
+        // Drop the shallowest crumb after the root, folding it into the ellipsis
+        if shown[1].0.is_some() {
+            shown[1] = (None, CRUMB_ELLIPSIS);
+        } else if shown.len() > 3 {
+            shown.remove(2);
+        } else {
+            break;
+        }
+    }
+    shown
+}
+
+/// The breadcrumb level under screen column `x` of the header, for mouse clicks.
+pub fn crumb_at(app: &App, header_width: u16, x: u16) -> Option<usize> {
+    let crumbs = app.get_breadcrumbs();
+    let mut pos = 1; // Inside the left border
+    for (level, text) in fit_breadcrumbs(&crumbs, header_width.saturating_sub(2)) {
+        let end = pos + Span::raw(text).width() as u16;
+        if (pos..end).contains(&x) {
+            return level;
+        }
+        pos = end + CRUMB_SEPARATOR.len() as u16;
+    }
+    None
+}
+
+// Overdue, then the week ahead; Mondays and today carry the ISO week number
+fn draw_agenda(f: &mut Frame, app: &App, board: &crate::model::Board, area: Rect) {
+    let today = zone::today();
+    let slots = agenda::slots(today);
+    let counts = agenda::counts(board, &slots, Utc::now());
+    for (i, ((slot, count), cell)) in slots.iter().zip(counts).zip(agenda_cells(area)).enumerate() {
+        let (label, color) = match *slot {
+            Slot::Overdue => (" late".to_string(), if count > 0 { colors().alert } else { colors().dim }),
+            Slot::Day(day) => {
+                let week = if day == today || day.weekday() == Weekday::Mon { format!("W{} ", day.iso_week().week()) } else { String::new() };
+                let color = if count == 0 { colors().dim } else if day == today { colors().accent } else { Color::Reset };
+                (format!("{}{}", week, day.format("%a %d")), color)
+            }
+        };
+        // Without color today is underlined and overdue tasks bold
+        let mut style = Style::default().fg(color);
+        if theme::monochrome() {
+            match *slot {
+                Slot::Day(day) if day == today => style = style.add_modifier(Modifier::UNDERLINED),
+                Slot::Overdue if count > 0 => style = style.add_modifier(Modifier::BOLD),
+                _ => {}
+            }
+        }
+        let count = if count == 0 { "·".to_string() } else { count.to_string() };
+        if app.input_mode == InputMode::Agenda && i == app.agenda_idx {
+            style = theme::selected();
+        }
+        f.render_widget(Paragraph::new(format!("{} {}", label, count)).style(style), cell);
+    }
+}
+
+// A narrow cell for overdue tasks, then the days sharing the rest
+fn agenda_cells(area: Rect) -> Vec<Rect> {
+    let late = AGENDA_LATE_WIDTH.min(area.width);
+    let day = (area.width - late) / agenda::DAYS as u16;
+    let days = (0..agenda::DAYS as u16).map(|i| Rect { x: area.x + late + day * i, width: day, ..area });
+    std::iter::once(Rect { width: late, ..area }).chain(days).collect()
+}
+
+/// The agenda slot under a click at (`x`, `y`) on a `size` screen, when the strip is shown.
+pub fn agenda_slot_at(app: &App, size: Size, x: u16, y: u16) -> Option<usize> {
+    let ActiveContentRef::Board(board) = app.get_active_content() else { return None };
+    let strip = board_layout(app, board, screen_layout(app, Rect::new(0, 0, size.width, size.height))[2]).agenda?;
+    if y != strip.y {
+        return None;
+    }
+    agenda_cells(strip).iter().rposition(|cell| x >= cell.x)
+}
+
+fn draw_board(f: &mut Frame, app: &App, board: &crate::model::Board, layout: &BoardLayout) {
+    if board.columns.is_empty() {
         let text = Paragraph::new("No columns defined.")
             .alignment(Alignment::Center)
             .block(Block::default().borders(Borders::ALL));
-        f.render_widget(text, area);
+        f.render_widget(text, layout.board);
         return;
     }
 
-    let constraints: Vec<Constraint> = (0..col_count)
-        .map(|_| Constraint::Percentage(100 / col_count as u16))
-        .collect();
-    
-    let col_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints(constraints)
-        .split(area);
-
-    for (i, column) in board.columns.iter().enumerate() {
-        let is_selected_col = i == app.cursor.0;
-        
-        let items: Vec<ListItem> = column.tasks.iter().enumerate().map(|(j, task)| {
-            let is_selected_task = is_selected_col && j == app.cursor.1;
-            
-            let (bg, fg) = if is_selected_task {
-                (COLOR_SELECTED_BG, COLOR_SELECTED_FG)
+    let lanes = view::lanes(app, board);
+    let grid = lane_grid(&lanes, layout.board);
+    for (lane, (header, cells)) in lanes.into_iter().zip(grid) {
+        if let (Some(title), Some(area)) = (&lane.title, header) {
+            let style = if lane.selected {
+                Style::default().fg(colors().accent).add_modifier(Modifier::BOLD)
             } else {
-                (Color::Reset, Color::White)
+                Style::default().fg(colors().foreground).add_modifier(Modifier::BOLD)
             };
+            let mark = if lane.selected { theme::icon("▸", ">") } else { " " };
+            f.render_widget(Paragraph::new(Span::styled(format!("{} {} ({})", mark, title, lane.count), style)), area);
+        }
+        for (i, column) in lane.columns.into_iter().enumerate() {
+            draw_column(f, app, column, cells[i], board.wide_cards);
+        }
+    }
+}
+
+// One column of the board, or its cell in a swimlane
+fn draw_column(f: &mut Frame, app: &App, column: ColumnView, area: Rect, wide: bool) {
+    let (items, offset) = column_items(app, column.cards, area, wide);
+    let height = area.height.saturating_sub(2) as usize;
+    let shown = items[offset..].iter()
+        .scan(0, |used, (_, item)| { *used += item.height(); Some(*used) })
+        .take_while(|&used| used <= height)
+        .count();
+    let (above, below) = (offset, items.len() - offset - shown);
 
-            let (marker, marker_color) = match &task.content {
-                Some(TaskContent::Board(_)) => ("📂 ", COLOR_BOARD_ICON),
-                Some(TaskContent::Todo(_)) => ("☑️ ", COLOR_TODO_ICON),
-                Some(TaskContent::Text(_)) => ("📝 ", COLOR_TEXT_ICON),
-                None => ("📄 ", Color::DarkGray),
+    let border_style = if column.selected {
+        Style::default().fg(colors().border_active).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(colors().border)
+    };
+    
+    // Add bold to column title if active
+    let title_style = if column.selected {
+         Style::default().fg(colors().accent).add_modifier(Modifier::BOLD)
+    } else {
+         Style::default().fg(colors().foreground)
+    };
+    let title_style = if column.over_limit { title_style.fg(colors().alert) } else { title_style };
+    // Without color the focused column has a heavier border, and a full one a "!"
+    let mono = theme::monochrome();
+    let border_type = if mono && column.selected { BorderType::Thick } else { BorderType::Rounded };
+    let full = if mono && column.over_limit { "!" } else { "" };
+
+    let markers: String = column.markers.iter().map(|m| format!(" {}", m)).collect();
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(border_type)
+        .title(Span::styled(format!(" {} ({}){}{} ", column.title, column.count, full, markers), title_style))
+        .border_style(border_style);
+    // How many cards are scrolled out of view either way
+    let more = Style::default().fg(colors().dim);
+    if above > 0 {
+        block = block.title_top(Line::styled(format!(" ↑ {} ", above), more).right_aligned());
+    }
+    if below > 0 {
+        block = block.title_bottom(Line::styled(format!(" ↓ {} more ", below), more).right_aligned());
+    }
+    let list = List::new(items.into_iter().map(|(_, item)| item)).block(block);
+
+    f.render_stateful_widget(list, area, &mut ListState::default().with_offset(offset));
+}
+
+// A column's cards with their tasks, and the first one shown: the top card,
+// unless the selected one would fall below the bottom edge, then just far
+// enough down that it sits on the last line
+fn column_items(app: &App, cards: Vec<CardView>, area: Rect, wide: bool) -> (Vec<(Uuid, ListItem<'static>)>, usize) {
+    let selected = cards.iter().position(|c| c.selected);
+    let items: Vec<(Uuid, ListItem)> = cards.into_iter().map(|card| (card.id, card_item(app, card, area.width, wide))).collect();
+    let height = area.height.saturating_sub(2) as usize;
+    let Some(mut offset) = selected else { return (items, 0) };
+    let mut used = items[offset].1.height();
+    while offset > 0 && used + items[offset - 1].1.height() <= height {
+        offset -= 1;
+        used += items[offset].1.height();
+    }
+    (items, offset)
+}
+
+// One card in a column `width` cells wide, borders included
+fn card_item(app: &App, card: CardView, width: u16, wide: bool) -> ListItem<'static> {
+    let style = if card.selected {
+        theme::selected()
+    } else if card.search_hit {
+        theme::highlighted()
+    } else {
+        Style::default().fg(colors().foreground)
+    };
+
+    let marker_color = match card.kind {
+        CardKind::Board => colors().board,
+        CardKind::Todo => colors().todo,
+        CardKind::Text => colors().text,
+        CardKind::Empty => colors().dim,
+    };
+
+    let mut spans = vec![
+        Span::styled(format!("{} ", card.kind.icon()), Style::default().fg(marker_color)),
+        Span::raw(card.title),
+    ];
+    // Typed keys dim, the rest of the label stands out
+    if let Some(label) = card.jump_label {
+        let typed = app.jump_input.len().min(label.len());
+        if !label.starts_with(&app.jump_input) {
+            spans.insert(0, Span::styled(format!("{} ", " ".repeat(label.len())), Style::default()));
+        } else {
+            spans.insert(0, Span::raw(" "));
+            spans.insert(0, Span::styled(label[typed..].to_string(), theme::highlighted().add_modifier(Modifier::BOLD)));
+            spans.insert(0, Span::styled(label[..typed].to_string(), Style::default().fg(colors().dim)));
+        }
+    }
+    spans.extend(card.badges.into_iter().map(|b| {
+        // Without color, what needs attention says so
+        let mark = if b.tone == Tone::Alert && theme::monochrome() { "!" } else { "" };
+        Span::styled(format!(" {}{}", mark, b.text), tone_style(b.tone))
+    }));
+    // The item's style fills every line it has, so a wrapped card highlights whole
+    let content = if wide {
+        Text::from(wrap_spans(spans, width.saturating_sub(2).into(), WIDE_CARD_LINES))
+    } else {
+        Text::from(Line::from(spans))
+    };
+
+    ListItem::new(content).style(style)
+}
+
+// Breaks `spans` into at most `max_lines` lines of `width` cells, at a space
+// where there is one; what still doesn't fit is cut off with "…"
+fn wrap_spans(spans: Vec<Span<'static>>, width: usize, max_lines: usize) -> Vec<Line<'static>> {
+    let mut lines: Vec<Vec<(char, Style)>> = Vec::new();
+    let mut line: Vec<(char, Style)> = Vec::new();
+    let cells = spans.iter().flat_map(|s| s.content.chars().map(move |c| (c, s.style)));
+    for (c, style) in cells {
+        let used: usize = line.iter().map(|(c, _)| c.width().unwrap_or(0)).sum();
+        if used + c.width().unwrap_or(0) > width && !line.is_empty() {
+            if lines.len() + 1 == max_lines {
+                while line.iter().map(|(c, _)| c.width().unwrap_or(0)).sum::<usize>() + 1 > width && line.pop().is_some() {}
+                line.push(('…', style));
+                break;
+            }
+            // Carry the word being cut over to the next line
+            let rest = match line.iter().rposition(|(c, _)| *c == ' ').filter(|&at| at > 0) {
+                Some(at) => {
+                    let rest = line.split_off(at + 1);
+                    line.pop();
+                    rest
+                }
+                None => Vec::new(),
             };
+            lines.push(std::mem::replace(&mut line, rest));
+            if c == ' ' && line.is_empty() {
+                continue;
+            }
+        }
+        line.push((c, style));
+    }
+    lines.push(line);
 
-            let content = Line::from(vec![
-                Span::styled(marker, Style::default().fg(marker_color)),
-                Span::raw(&task.title),
-            ]);
-            
-            ListItem::new(content)
-                .style(Style::default().bg(bg).fg(fg))
-        }).collect();
+    lines.into_iter().map(|cells| {
+        let mut spans: Vec<Span> = Vec::new();
+        for (c, style) in cells {
+            match spans.last_mut() {
+                Some(last) if last.style == style => last.content.to_mut().push(c),
+                _ => spans.push(Span::styled(c.to_string(), style)),
+            }
+        }
+        Line::from(spans)
+    }).collect()
+}
+
+fn tone_style(tone: Tone) -> Style {
+    match tone {
+        Tone::Dim => Style::default().fg(colors().dim),
+        Tone::Warning if theme::monochrome() => Style::default().add_modifier(Modifier::UNDERLINED),
+        Tone::Warning => Style::default().fg(colors().warning),
+        Tone::Alert => Style::default().fg(colors().alert).add_modifier(Modifier::BOLD),
+        Tone::Todo => Style::default().fg(colors().todo),
+        Tone::Board => Style::default().fg(colors().board),
+    }
+}
+
+fn draw_badge_menu(f: &mut Frame, app: &App) {
+    let area = centered_rect(40, 40, f.area());
+    f.render_widget(Clear, area);
 
-        let border_style = if is_selected_col {
-            Style::default().fg(COLOR_BORDER_ACTIVE).add_modifier(Modifier::BOLD)
+    let items: Vec<ListItem> = app.badge_menu().into_iter().enumerate().map(|(i, (badge, shown))| {
+        let style = if i == app.badge_idx {
+            theme::selected()
+        } else if shown {
+            Style::default()
         } else {
-            Style::default().fg(COLOR_BORDER_INACTIVE)
+            Style::default().fg(colors().dim)
         };
-        
-        // Add bold to column title if active
-        let title_style = if is_selected_col {
-             Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        ListItem::new(format!("[{}] {}", if shown { "x" } else { " " }, badge.name())).style(style)
+    }).collect();
+    let list = List::new(items)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Double)
+            .border_style(Style::default().fg(colors().popup_border))
+            .title(" Card Badges "));
+    f.render_widget(list, area);
+}
+
+// Rows are numbered pending first, then done (`model::todo_order`); the cursor is a
+// row, and each pane scrolls to keep it in view
+fn draw_todo(f: &mut Frame, app: &App, items: &[crate::model::TodoItem], area: Rect) {
+    let pending_items: Vec<&crate::model::TodoItem> = items.iter().filter(|i| !i.done).collect();
+    let done_items: Vec<&crate::model::TodoItem> = items.iter().filter(|i| i.done).collect();
+    let layout = app.active_task().map(|t| t.todo_layout).unwrap_or_default();
+    let cursor = app.pos.1;
+
+    let item_style = |row: usize, done: bool| {
+        if row == cursor {
+            theme::selected()
+        } else if done && theme::monochrome() {
+            Style::default().add_modifier(Modifier::CROSSED_OUT)
+        } else if done {
+            Style::default().fg(colors().muted)
         } else {
-             Style::default().fg(Color::White)
-        };
+            Style::default()
+        }
+    };
+    // Selection within a pane whose first row is `first`
+    let pane_state = |first: usize, len: usize| {
+        ListState::default().with_selected((first..first + len).contains(&cursor).then(|| cursor - first))
+    };
 
-        let list = List::new(items)
+    if layout.merged {
+        let list_items: Vec<ListItem> = pending_items.iter().chain(done_items.iter()).enumerate().map(|(row, item)| {
+            ListItem::new(format!("[{}] {}", if item.done { "x" } else { " " }, item.text)).style(item_style(row, item.done))
+        }).collect();
+        let list = List::new(list_items)
             .block(Block::default()
                 .borders(Borders::ALL)
+                .title(format!(" Items ({}/{} done) ", done_items.len(), items.len()))
                 .border_type(BorderType::Rounded)
-                .title(Span::styled(format!(" {} ({}) ", column.title, column.tasks.len()), title_style))
-                .border_style(border_style));
-        
-        f.render_widget(list, col_chunks[i]);
+                .border_style(Style::default().fg(colors().border_active)));
+        f.render_stateful_widget(list, area, &mut pane_state(0, items.len()));
+        return;
     }
-}
-
-fn draw_todo(f: &mut Frame, app: &App, items: &[crate::model::TodoItem], area: Rect) {
-    let pending_items: Vec<(usize, &crate::model::TodoItem)> = items.iter().enumerate().filter(|(_, i)| !i.done).collect();
-    let done_items: Vec<(usize, &crate::model::TodoItem)> = items.iter().enumerate().filter(|(_, i)| i.done).collect();
     
     let constraints = if pending_items.is_empty() && done_items.is_empty() {
         vec![Constraint::Percentage(100)]
@@ -161,7 +692,7 @@ fn draw_todo(f: &mut Frame, app: &App, items: &[crate::model::TodoItem], area: R
     } else if done_items.is_empty() {
          vec![Constraint::Percentage(100), Constraint::Percentage(0)]
     } else {
-         vec![Constraint::Percentage(50), Constraint::Percentage(50)]
+         vec![Constraint::Percentage(layout.pending_percent), Constraint::Percentage(100 - layout.pending_percent)]
     };
 
     let chunks = Layout::default()
@@ -171,14 +702,8 @@ fn draw_todo(f: &mut Frame, app: &App, items: &[crate::model::TodoItem], area: R
         
     // Pending List
     if !pending_items.is_empty() || done_items.is_empty() {
-        let list_items: Vec<ListItem> = pending_items.iter().map(|&(i, item)| {
-             let is_selected = i == app.cursor.1;
-             let style = if is_selected {
-                 Style::default().fg(COLOR_SELECTED_FG).bg(COLOR_SELECTED_BG)
-             } else {
-                 Style::default()
-             };
-             ListItem::new(format!("[ ] {}", item.text)).style(style)
+        let list_items: Vec<ListItem> = pending_items.iter().enumerate().map(|(row, item)| {
+             ListItem::new(format!("[ ] {}", item.text)).style(item_style(row, false))
         }).collect();
         
         // Ensure we show title even if empty only if it's the only view? 
@@ -188,8 +713,8 @@ fn draw_todo(f: &mut Frame, app: &App, items: &[crate::model::TodoItem], area: R
                 .borders(Borders::ALL)
                 .title(" To Do ")
                 .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(COLOR_BORDER_ACTIVE)));
-        f.render_widget(list, chunks[0]);
+                .border_style(Style::default().fg(colors().border_active)));
+        f.render_stateful_widget(list, chunks[0], &mut pane_state(0, pending_items.len()));
     }
 
     // Done List
@@ -200,16 +725,11 @@ fn draw_todo(f: &mut Frame, app: &App, items: &[crate::model::TodoItem], area: R
         // Case 2 (Only Pending): [0] is Pending, [1] is size 0.
         // Case 3 (Only Done): [0] size 0, [1] is Done.
         
-        let target_chunk = if pending_items.is_empty() { chunks[1] } else { chunks[1] };
+        let target_chunk = chunks[1];
+        let first = pending_items.len();
         
-        let list_items: Vec<ListItem> = done_items.iter().map(|&(i, item)| {
-             let is_selected = i == app.cursor.1;
-             let style = if is_selected {
-                 Style::default().fg(COLOR_SELECTED_FG).bg(COLOR_SELECTED_BG)
-             } else {
-                 Style::default().fg(Color::Gray)
-             };
-             ListItem::new(format!("[x] {}", item.text)).style(style)
+        let list_items: Vec<ListItem> = done_items.iter().enumerate().map(|(k, item)| {
+             ListItem::new(format!("[x] {}", item.text)).style(item_style(first + k, true))
         }).collect();
         
         let list = List::new(list_items)
@@ -217,42 +737,300 @@ fn draw_todo(f: &mut Frame, app: &App, items: &[crate::model::TodoItem], area: R
                 .borders(Borders::ALL)
                 .title(" Done ")
                 .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(COLOR_BORDER_INACTIVE)));
-        f.render_widget(list, target_chunk);
+                .border_style(Style::default().fg(colors().border)));
+        f.render_stateful_widget(list, target_chunk, &mut pane_state(first, done_items.len()));
     }
 }
 
-fn draw_text_view(f: &mut Frame, _app: &App, text: &str, area: Rect) {
-    let p = Paragraph::new(text)
-        .wrap(Wrap { trim: true })
+// Open todo items from the whole tree, under a heading per task
+fn draw_checklist(f: &mut Frame, app: &App, area: Rect) {
+    let mut items = Vec::new();
+    let mut selected = 0;
+    for (i, entry) in app.checklist.iter().enumerate() {
+        if i == 0 || app.checklist[i - 1].group != entry.group {
+            items.push(ListItem::new(entry.group.as_str()).style(Style::default().fg(colors().todo).add_modifier(Modifier::BOLD)));
+        }
+        let style = if i == app.checklist_idx {
+            selected = items.len();
+            theme::selected()
+        } else if entry.done {
+            Style::default().fg(colors().muted)
+        } else {
+            Style::default()
+        };
+        items.push(ListItem::new(format!("  [{}] {}", if entry.done { "x" } else { " " }, entry.text)).style(style));
+    }
+
+    let open = app.checklist.iter().filter(|e| !e.done).count();
+    let list = List::new(items)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" Checklist ({} open) ", open))
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(colors().border_active)));
+    // Headings don't take the cursor, but scrolling has to count them
+    f.render_stateful_widget(list, area, &mut ListState::default().with_selected(Some(selected)));
+}
+
+fn draw_results(f: &mut Frame, app: &App, area: Rect) {
+    let mut items = Vec::new();
+    let mut selected = 0;
+    for (i, found) in app.results.iter().enumerate() {
+        if i == 0 || app.results[i - 1].breadcrumbs != found.breadcrumbs {
+            items.push(ListItem::new(found.breadcrumbs.join(" > ")).style(Style::default().fg(colors().board).add_modifier(Modifier::BOLD)));
+        }
+        let style = if i == app.results_idx {
+            selected = items.len();
+            theme::selected()
+        } else {
+            Style::default()
+        };
+        let mut spans = vec![
+            Span::styled(format!("  [{}] ", found.column), Style::default().fg(colors().dim)),
+            Span::raw(found.title.as_str()),
+        ];
+        // Agenda lists say what they hold in the title
+        if !matches!(found.field, "title" | "due") {
+            spans.push(Span::styled(format!("  (in {})", found.field), Style::default().fg(colors().dim)));
+        }
+        items.push(ListItem::new(Line::from(spans)).style(style));
+    }
+
+    let title = if app.results.first().is_some_and(|m| m.field == "due") {
+        format!(" {} tasks {} (Enter: go to, Esc: close) ", app.results.len(), app.results_query)
+    } else {
+        format!(" {} matches for '{}' (Enter: go to, Esc: close) ", app.results.len(), app.results_query)
+    };
+    let list = List::new(items)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(colors().border_active)));
+    f.render_stateful_widget(list, area, &mut ListState::default().with_selected(Some(selected)));
+}
+
+fn draw_archive(f: &mut Frame, app: &App, area: Rect) {
+    let archived = app.archived();
+    let items: Vec<ListItem> = archived.iter().enumerate().map(|(i, a)| {
+        let style = if i == app.archive_idx { theme::selected() } else { Style::default() };
+        ListItem::new(Line::from(vec![
+            Span::styled(format!("{}  ", zone::local(a.archived_at).format("%b %d")), Style::default().fg(colors().dim)),
+            Span::styled(format!("[{}] ", a.column), Style::default().fg(colors().dim)),
+            Span::raw(a.task.title.as_str()),
+        ])).style(style)
+    }).collect();
+    let list = List::new(items)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" Archive ({}) ", archived.len()))
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(colors().border_active)));
+    f.render_stateful_widget(list, area, &mut ListState::default().with_selected(Some(app.archive_idx)));
+}
+
+fn draw_trash(f: &mut Frame, app: &App, area: Rect) {
+    let trashed = app.trashed();
+    let purge = app.config.trash.purge_days;
+    let items: Vec<ListItem> = trashed.iter().enumerate().map(|(i, t)| {
+        let style = if i == app.trash_idx { theme::selected() } else { Style::default() };
+        let what = match &t.item {
+            TrashedItem::Task(task) => vec![
+                Span::styled(format!("[{}] ", t.column), Style::default().fg(colors().dim)),
+                Span::raw(task.title.as_str()),
+            ],
+            TrashedItem::Column(column) => vec![
+                Span::styled("column ", Style::default().fg(colors().dim)),
+                Span::raw(match column.tasks.len() {
+                    1 => format!("{} (1 task)", column.title),
+                    n => format!("{} ({} tasks)", column.title, n),
+                }),
+            ],
+        };
+        let mut spans = vec![Span::styled(format!("{}  ", zone::local(t.deleted_at).format("%b %d %H:%M")), Style::default().fg(colors().dim))];
+        spans.extend(what);
+        ListItem::new(Line::from(spans)).style(style)
+    }).collect();
+    let title = match purge {
+        0 => format!(" Trash ({}) ", trashed.len()),
+        days => format!(" Trash ({}, emptied after {} days) ", trashed.len(), days),
+    };
+    let list = List::new(items)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(colors().border_active)));
+    f.render_stateful_widget(list, area, &mut ListState::default().with_selected(Some(app.trash_idx)));
+}
+
+// The workspace list, and beside it how the highlighted one is doing
+fn draw_workspaces(f: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+        .split(area);
+    let items: Vec<ListItem> = app.workspace_names().into_iter().enumerate().map(|(i, name)| {
+        let style = if i == app.workspace_idx {
+            theme::selected()
+        } else {
+            Style::default()
+        };
+        let marker = if i == app.workspace { "● " } else { "  " };
+        ListItem::new(format!("{}{}", marker, name)).style(style)
+    }).collect();
+
+    let list = List::new(items)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .title(" Workspaces ")
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(colors().border_active)));
+    f.render_stateful_widget(list, chunks[0], &mut ListState::default().with_selected(Some(app.workspace_idx)));
+
+    let Some(stats) = app.workspace_stats(app.workspace_idx) else { return };
+    let label = Style::default().fg(colors().dim);
+    let heading = Style::default().fg(colors().heading).add_modifier(Modifier::BOLD);
+    let mut lines = vec![
+        Line::from(vec![Span::styled(format!("{:<15}", "Boards"), label), Span::raw(stats.boards.len().to_string())]),
+        Line::from(vec![Span::styled(format!("{:<15}", "Open tasks"), label), Span::raw(stats.open().to_string())]),
+        Line::from(vec![Span::styled(format!("{:<15}", "Done this week"), label), Span::raw(stats.done().to_string())]),
+        Line::raw(""),
+        Line::styled("Most active this week", heading),
+    ];
+    let active = stats.most_active(5);
+    if active.is_empty() {
+        lines.push(Line::styled("Nothing moved yet", label));
+    }
+    lines.extend(active.into_iter().map(|b| Line::from(vec![
+        Span::raw(b.title().to_string()),
+        Span::styled(format!("  {} moved", b.moved), label),
+    ])));
+    lines.push(Line::raw(""));
+    lines.push(Line::styled("Done this week by board", heading));
+    // Indented like the tree, leaving out boards with nothing done
+    let done: Vec<Line> = stats.boards.iter().filter(|b| b.done > 0).map(|b| Line::from(vec![
+        Span::raw(format!("{}{}", "  ".repeat(b.depth()), b.title())),
+        Span::styled(format!("  {}", b.done), Style::default().fg(colors().success)),
+    ])).collect();
+    if done.is_empty() {
+        lines.push(Line::styled("Nothing done yet", label));
+    }
+    lines.extend(done);
+
+    let p = Paragraph::new(lines)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .title(" This Week ")
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(colors().border)));
+    f.render_widget(p, chunks[1]);
+}
+
+fn draw_text_view(f: &mut Frame, app: &App, text: &str, area: Rect) {
+    if let Some(editor) = &app.note_editor {
+        draw_note_editor(f, editor, area);
+        return;
+    }
+    let p = Paragraph::new(markdown::lines(text, area.width.saturating_sub(2)))
+        .wrap(Wrap { trim: false })
         .block(Block::default()
             .borders(Borders::ALL)
             .title(" Notes ")
             .border_type(BorderType::Rounded)
-            .border_style(Style::default().fg(COLOR_BORDER_ACTIVE)));
+            .border_style(Style::default().fg(colors().border_active)));
+    f.render_widget(p, area);
+}
+
+// Unwrapped, so lines stay as typed; the view follows the cursor both ways
+fn draw_note_editor(f: &mut Frame, editor: &Editor, area: Rect) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Notes (editing) ")
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(colors().input_border));
+    let inner = block.inner(area);
+    let (top, left) = editor.scroll(inner.width.into(), inner.height.into());
+    let (row, x) = editor.cursor();
+    let lines: Vec<Line> = editor.lines().iter().map(|l| Line::raw(l.as_str())).collect();
+    let p = Paragraph::new(lines)
+        .style(Style::default().fg(colors().foreground))
+        .scroll((top as u16, left as u16))
+        .block(block);
     f.render_widget(p, area);
+    f.set_cursor_position((inner.x + (x - left) as u16, inner.y + (row - top) as u16));
 }
 
 fn draw_empty_selection(f: &mut Frame, area: Rect) {
     let p = Paragraph::new("Empty Task. Press Enter to add content.")
         .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::DarkGray)));
+        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(colors().dim)));
     f.render_widget(p, area);
 }
 
 fn draw_footer(f: &mut Frame, app: &App, area: Rect) {
+    if matches!(app.input_mode, InputMode::Command | InputMode::Searching) {
+        let prompt = if app.input_mode == InputMode::Command { ':' } else { '/' };
+        let line = Paragraph::new(format!("{}{}", prompt, app.input_buffer))
+            .style(Style::default().fg(colors().accent))
+            .block(Block::default().borders(Borders::TOP));
+        f.render_widget(line, area);
+        return;
+    }
+
+    if let Some(msg) = &app.message {
+        let line = Paragraph::new(msg.as_str())
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(colors().alert))
+            .block(Block::default().borders(Borders::TOP));
+        f.render_widget(line, area);
+        return;
+    }
+
+    if let Some(nudge) = app.nudge().filter(|_| app.input_mode == InputMode::Normal) {
+        let line = Paragraph::new(format!("💡 {}", nudge))
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(colors().warning))
+            .block(Block::default().borders(Borders::TOP));
+        f.render_widget(line, area);
+        draw_save_status(f, app, area);
+        return;
+    }
+
     let help_text = match app.get_active_content() {
-        ActiveContentRef::Board(_) => "Moves: Shift+Arrows | Enter: Open | a: Add | d: Del | ?: Help",
-        ActiveContentRef::Todo(_) => "Move: jk/Arrows | Space: Toggle | a: Add Item | d: Del | Esc: Back",
-        ActiveContentRef::Text(_) => "Enter: Edit Text | Esc: Back",
+        _ if app.input_mode == InputMode::Checklist => "Move: jk/Arrows | Space: Toggle | Enter: Open List | Esc: Close",
+        _ if app.input_mode == InputMode::Badges => "Move: jk/Arrows | Space: Show/Hide | J/K: Reorder | Esc: Close",
+        _ if app.input_mode == InputMode::Results => "Move: jk/Arrows | Enter: Go to Task | Esc: Close",
+        _ if app.input_mode == InputMode::Archive => "Move: jk/Arrows | Enter: Restore to Its Column | Esc: Close",
+        _ if app.input_mode == InputMode::Trash => "Move: jk/Arrows | Enter: Restore Where It Was | Esc: Close",
+        _ if app.input_mode == InputMode::Workspaces => "Move: jk/Arrows | Enter: Open | :workspace <name>: New | Esc: Close",
+        _ if app.input_mode == InputMode::Agenda => "Day: hl/Arrows | Enter: List Its Tasks | Esc: Back to Board",
+        _ if app.input_mode == InputMode::Jump => "Type a card's label to jump to it | Esc: Cancel",
+        _ if app.input_mode == InputMode::EditingColumnDefaults => "#tag  pri:A  due:<date>, e.g. #bug pri:A due:3d | Empty: None | Enter: Save | Esc: Cancel",
+        _ if app.input_mode == InputMode::EditingNote => "Enter: New Line | Arrows/Home/End/PgUp/PgDn: Move | Esc/Ctrl+S: Save & Close",
+        ActiveContentRef::Board(_) => "Moves: Shift+Arrows | Enter: Open | a: Add | d: Del | i: Details | ?: Help",
+        ActiveContentRef::Todo(_) => "Move: jk/Arrows | Space: Toggle | a: Add Item | d: Del | +/-: Resize | m: Merge | Esc: Back",
+        ActiveContentRef::Text(_) => "Enter: Edit Text | e: $EDITOR | Esc: Back",
         ActiveContentRef::None => "Enter: Select Content Type | Esc: Back",
     };
     
     let help = Paragraph::new(help_text)
         .alignment(Alignment::Center)
-        .style(Style::default().fg(Color::Cyan))
+        .style(Style::default().fg(colors().heading))
         .block(Block::default().borders(Borders::TOP));
     f.render_widget(help, area);
+    draw_save_status(f, app, area);
+}
+
+// Save indicator in the bottom-right corner
+fn draw_save_status(f: &mut Frame, app: &App, area: Rect) {
+    let (status, color) = match (app.dirty, app.last_saved) {
+        (true, _) => ("● unsaved".to_string(), colors().warning),
+        (false, Some(at)) => (format!("saved {}", at.format("%H:%M:%S")), colors().dim),
+        (false, None) => (String::new(), colors().dim),
+    };
+    let status_area = Rect { y: area.y + 1, height: area.height.saturating_sub(1), ..area };
+    f.render_widget(Paragraph::new(status).alignment(Alignment::Right).style(Style::default().fg(color)), status_area);
 }
 
 fn draw_input_popup(f: &mut Frame, app: &App) {
@@ -261,22 +1039,64 @@ fn draw_input_popup(f: &mut Frame, app: &App) {
 
     let title = if app.input_mode == InputMode::EditingColumn {
         " New Column "
-    } else {
-        match app.get_active_content() {
-            ActiveContentRef::Text(_) => " Edit Note ",
-            _ => " New Item ",
+    } else if app.input_mode == InputMode::EditingBoardNotes {
+        " Board Notes "
+    } else if app.input_mode == InputMode::EditingBlockReason {
+        " Why is it blocked? "
+    } else if app.input_mode == InputMode::EditingDescription {
+        " Description "
+    } else if app.input_mode == InputMode::EditingColumnDefaults {
+        " Defaults for New Tasks "
+    } else if app.input_mode == InputMode::Renaming {
+        match app.rename_target {
+            Some(RenameTarget::Column(_)) => " Rename Column ",
+            Some(RenameTarget::TodoItem(_)) => " Rename Item ",
+            _ => " Rename Task ",
         }
+    } else {
+        " New Item "
     };
 
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .title(title)
+        .style(Style::default().fg(colors().input_border));
+    if let Some(problem) = app.input_problem() {
+        block = block.title_bottom(Line::styled(format!(" {} ", problem), Style::default().fg(colors().alert)).right_aligned());
+    }
     let input = Paragraph::new(app.input_buffer.as_str())
-        .style(Style::default().fg(Color::Yellow))
-        .block(Block::default()
-            .borders(Borders::ALL)
-            .border_type(BorderType::Double)
-            .title(title)
-            .style(Style::default().fg(Color::Blue)));
-    
+        .style(Style::default().fg(colors().accent))
+        .wrap(Wrap { trim: false })
+        .block(block);
+
     f.render_widget(input, area);
+
+    if !app.suggestions.is_empty() {
+        draw_suggestions(f, app, area);
+    }
+}
+
+// Dropdown just below the input popup
+fn draw_suggestions(f: &mut Frame, app: &App, input_area: Rect) {
+    let frame = f.area();
+    let top = (input_area.y + input_area.height).min(frame.height);
+    let height = (app.suggestions.len() as u16 + 2).min(frame.height - top);
+    let area = Rect { x: input_area.x, y: top, width: input_area.width.min(30), height };
+    f.render_widget(Clear, area);
+
+    let items: Vec<ListItem> = app.suggestions.iter().enumerate().map(|(i, s)| {
+        let style = if i == app.suggestion_idx {
+            theme::selected()
+        } else {
+            Style::default()
+        };
+        ListItem::new(s.label.as_str()).style(style)
+    }).collect();
+
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(colors().border)));
+    f.render_widget(list, area);
 }
 
 fn draw_type_selection_popup(f: &mut Frame) {
@@ -286,9 +1106,9 @@ fn draw_type_selection_popup(f: &mut Frame) {
     let text = vec![
         Line::from("Select Content Type:"),
         Line::from(""),
-        Line::from(Span::styled("b - Kanban Board", Style::default().fg(COLOR_BOARD_ICON))),
-        Line::from(Span::styled("t - Todo List", Style::default().fg(COLOR_TODO_ICON))),
-        Line::from(Span::styled("n - Text Note", Style::default().fg(COLOR_TEXT_ICON))),
+        Line::from(Span::styled("b - Kanban Board", Style::default().fg(colors().board))),
+        Line::from(Span::styled("t - Todo List", Style::default().fg(colors().todo))),
+        Line::from(Span::styled("n - Text Note", Style::default().fg(colors().text))),
     ];
     
     let p = Paragraph::new(text)
@@ -297,6 +1117,57 @@ fn draw_type_selection_popup(f: &mut Frame) {
     f.render_widget(p, area);
 }
 
+fn draw_confirm_popup(f: &mut Frame, app: &App, prompt: &str) {
+    let (choices, area) = match app.column_delete_neighbor() {
+        Some(neighbor) => (format!("m - Move them to '{}'    d - Delete them    Esc - Cancel", neighbor), centered_rect(60, 25, f.area())),
+        None => ("y - Yes    n / Esc - No".to_string(), centered_rect(40, 20, f.area())),
+    };
+    f.render_widget(Clear, area);
+
+    let text = vec![
+        Line::from(prompt),
+        Line::from(""),
+        Line::from(Span::styled(choices, Style::default().fg(colors().dim))),
+    ];
+    let p = Paragraph::new(text)
+        .wrap(Wrap { trim: true })
+        .alignment(Alignment::Center)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Double)
+            .border_style(Style::default().fg(colors().alert))
+            .title(" Confirm "));
+    f.render_widget(p, area);
+}
+
+fn draw_replace_popup(f: &mut Frame, app: &App, session: &ReplaceSession) {
+    let Some((heading, before, found, after)) = session.preview(&app.root) else { return };
+    let area = centered_rect(60, 25, f.area());
+    f.render_widget(Clear, area);
+
+    let text = vec![
+        Line::from(Span::styled(heading, Style::default().fg(colors().heading))),
+        Line::from(""),
+        Line::from(vec![
+            Span::raw(format!("…{}", before)),
+            Span::styled(found, Style::default().fg(colors().selected_fg).bg(colors().alert)),
+            Span::raw(format!("{}…", after)),
+        ]),
+        Line::from(Span::styled(format!("→ {}", session.replace), Style::default().fg(colors().success))),
+        Line::from(""),
+        Line::from(Span::styled("y - Replace    n - Skip    a - All    Esc - Stop", Style::default().fg(colors().dim))),
+    ];
+    let p = Paragraph::new(text)
+        .wrap(Wrap { trim: false })
+        .alignment(Alignment::Center)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Double)
+            .border_style(Style::default().fg(colors().popup_border))
+            .title(format!(" Replace '{}' ({} done) ", session.find, session.replaced)));
+    f.render_widget(p, area);
+}
+
 fn draw_help_popup(f: &mut Frame) {
     let area = centered_rect(50, 60, f.area());
     f.render_widget(Clear, area);
@@ -307,20 +1178,45 @@ fn draw_help_popup(f: &mut Frame) {
         Row::new(vec!["j / Down", "Move Down"]),
         Row::new(vec!["k / Up", "Move Up"]),
         Row::new(vec!["l / Right", "Move Right"]),
+        Row::new(vec!["{ / }", "Previous / Next Swimlane"]),
         Row::new(vec!["Shift + ←/→", "Move Task"]),
         Row::new(vec!["Enter", "Drill Down / Edit"]),
         Row::new(vec!["Esc", "Go Back / Cancel"]),
+        Row::new(vec!["Alt + 1-9", "Jump to Breadcrumb"]),
+        Row::new(vec!["`", "Previous Board (again to return)"]),
+        Row::new(vec!["f", "Jump to a Card by Its Label"]),
         Row::new(vec!["a", "Add Item"]),
         Row::new(vec!["c", "Add Column"]),
         Row::new(vec!["d", "Delete Item"]),
+        Row::new(vec!["r / R", "Rename Task or Item / Column"]),
+        Row::new(vec!["X", "Delete Column"]),
+        Row::new(vec!["< / >", "Move Column Left / Right"]),
         Row::new(vec!["Space", "Toggle Todo"]),
+        Row::new(vec!["+ / - / m", "Resize / Merge Todo Panes"]),
+        Row::new(vec!["z / Z", "Snooze Task / Show Snoozed"]),
+        Row::new(vec!["w / W", "Waiting On... / Only Waiting Tasks"]),
+        Row::new(vec!["D", "Set Due Date"]),
+        Row::new(vec!["u / Ctrl + R", "Undo / Redo"]),
+        Row::new(vec!["/ then n / N", "Search This Board / Next / Previous Match"]),
+        Row::new(vec!["i / I", "Toggle Details / Edit Description"]),
+        Row::new(vec!["e", "Edit Note / Description in $EDITOR"]),
+        Row::new(vec!["A", "Archive Task (g A: Archive)"]),
+        Row::new(vec!["g x", "Trash: Restore Deleted Tasks and Columns"]),
+        Row::new(vec!["g d / g D", "Toggle / Edit Board Notes"]),
+        Row::new(vec!["g n", "Defaults for New Tasks in Column"]),
+        Row::new(vec!["g c", "Checklist of All Open Todos"]),
+        Row::new(vec!["g b", "Choose Card Badges"]),
+        Row::new(vec!["g /", "Search All Boards (:find)"]),
+        Row::new(vec!["g w", "Switch Workspace (:workspace)"]),
+        Row::new(vec![":", "Command Line (Tab completes, ↑/↓ history)"]),
         Row::new(vec!["?", "Toggle Help"]),
+        Row::new(vec!["Ctrl + S", "Save"]),
         Row::new(vec!["q", "Quit"]),
     ];
     
     let table = Table::new(rows, [Constraint::Percentage(30), Constraint::Percentage(70)])
-        .block(Block::default().borders(Borders::ALL).title(" Help / Shortcuts ").border_style(Style::default().fg(Color::Yellow)))
-        .style(Style::default().fg(Color::White));
+        .block(Block::default().borders(Borders::ALL).title(" Help / Shortcuts ").border_style(Style::default().fg(colors().popup_border)))
+        .style(Style::default().fg(colors().foreground));
         
     f.render_widget(table, area);
 }
@@ -0,0 +1,256 @@
+use crate::ics;
+use crate::model::{Board, TaskContent};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+// Undo/redo by inverse patches. Any action that sets `App::dirty` is recorded,
+// so new features are covered without writing an inverse for each one: the tree
+// is compared with `base`, its state as of the last recorded change, board by
+// board, and a step keeps only the boards the action changed, as they were
+// before it. Each board is held without the sub-boards of its cards (see
+// `split`), so a step costs what the changed boards hold, not the whole tree.
+
+const LIMIT: usize = 100;
+
+// Every board of a tree by the card holding it (None for the root), each with
+// its cards' sub-boards left empty
+type Pieces = HashMap<Option<Uuid>, Board>;
+
+// Boards to put in place of the ones there, None to drop one the change added
+type Patch = Vec<(Option<Uuid>, Option<Board>)>;
+
+struct Step {
+    patch: Patch,
+    path: Vec<Uuid>,      // Where the user was, see `App::path`
+    cursor: Option<Uuid>, // See `App::cursor`
+}
+
+pub struct UndoStack {
+    undo: Vec<Step>,
+    redo: Vec<Step>,
+    base: Pieces,
+}
+
+impl UndoStack {
+    pub fn new(root: &Board) -> Self {
+        Self { undo: Vec::new(), redo: Vec::new(), base: split(root.clone()) }
+    }
+
+    /// Records a change from `base` to `root`, made while the user was at `path`/`cursor`.
+    /// Returns false if the tree is unchanged after all.
+    pub fn record(&mut self, root: &mut Board, path: Vec<Uuid>, cursor: Option<Uuid>) -> bool {
+        let pieces = split(std::mem::replace(root, empty()));
+        let patch = diff(&self.base, &pieces);
+        for (holder, _) in &patch {
+            match pieces.get(holder) {
+                Some(board) => self.base.insert(*holder, board.clone()),
+                None => self.base.remove(holder),
+            };
+        }
+        *root = join(pieces);
+        if patch.is_empty() {
+            return false;
+        }
+        self.undo.push(Step { patch, path, cursor });
+        if self.undo.len() > LIMIT {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+        true
+    }
+
+    /// The tree as of the last recorded change, when it has a frozen board or
+    /// column for `model::frozen_change` to check; building it takes a copy.
+    pub fn frozen_base(&self) -> Option<Board> {
+        let frozen = self.base.values().any(|b| b.frozen || b.columns.iter().any(|c| c.frozen));
+        frozen.then(|| join(self.base.clone()))
+    }
+
+    /// The tree as of the last recorded change, when it has a subscribed task
+    /// for `ics::read_only_change` to check.
+    pub fn subscribed_base(&self) -> Option<Board> {
+        let subscribed = self.base.values().flat_map(|b| b.columns.iter().flat_map(|c| c.tasks.iter())).any(ics::is_read_only);
+        subscribed.then(|| join(self.base.clone()))
+    }
+
+    /// Takes `root` back to before the last change, and returns where the user was
+    /// when it was made. The user is now at `path`/`cursor`, for the redo.
+    pub fn undo(&mut self, root: &mut Board, path: Vec<Uuid>, cursor: Option<Uuid>) -> Option<(Vec<Uuid>, Option<Uuid>)> {
+        let step = self.undo.pop()?;
+        let (redo, at) = self.apply(root, step, path, cursor);
+        self.redo.push(redo);
+        Some(at)
+    }
+
+    /// Takes `root` forward to what the last undo took back.
+    pub fn redo(&mut self, root: &mut Board, path: Vec<Uuid>, cursor: Option<Uuid>) -> Option<(Vec<Uuid>, Option<Uuid>)> {
+        let step = self.redo.pop()?;
+        let (undo, at) = self.apply(root, step, path, cursor);
+        self.undo.push(undo);
+        Some(at)
+    }
+
+    // Patches `root` and `base` alike; the boards replaced make up the step back
+    fn apply(&mut self, root: &mut Board, step: Step, path: Vec<Uuid>, cursor: Option<Uuid>) -> (Step, (Vec<Uuid>, Option<Uuid>)) {
+        let mut pieces = split(std::mem::replace(root, empty()));
+        let mut back = Vec::with_capacity(step.patch.len());
+        for (holder, board) in step.patch {
+            match &board {
+                Some(b) => self.base.insert(holder, b.clone()),
+                None => self.base.remove(&holder),
+            };
+            let replaced = match board {
+                Some(b) => pieces.insert(holder, b),
+                None => pieces.remove(&holder),
+            };
+            back.push((holder, replaced));
+        }
+        *root = join(pieces);
+        (Step { patch: back, path, cursor }, (step.path, step.cursor))
+    }
+}
+
+// What turns `now` back into `before`
+fn diff(before: &Pieces, now: &Pieces) -> Patch {
+    let changed = before.iter()
+        .filter(|(holder, board)| now.get(holder) != Some(board))
+        .map(|(holder, board)| (*holder, Some(board.clone())));
+    let added = now.keys().filter(|holder| !before.contains_key(holder)).map(|holder| (*holder, None));
+    changed.chain(added).collect()
+}
+
+fn split(root: Board) -> Pieces {
+    let mut pieces = HashMap::new();
+    split_into(None, root, &mut pieces);
+    pieces
+}
+
+fn split_into(holder: Option<Uuid>, mut board: Board, pieces: &mut Pieces) {
+    for task in board.columns.iter_mut().flat_map(|c| c.tasks.iter_mut()) {
+        if let Some(TaskContent::Board(sub)) = &mut task.content {
+            let sub = std::mem::replace(sub, empty());
+            split_into(Some(task.id), sub, pieces);
+        }
+    }
+    pieces.insert(holder, board);
+}
+
+fn join(mut pieces: Pieces) -> Board {
+    join_from(None, &mut pieces)
+}
+
+fn join_from(holder: Option<Uuid>, pieces: &mut Pieces) -> Board {
+    let mut board = pieces.remove(&holder).unwrap_or_else(empty);
+    for task in board.columns.iter_mut().flat_map(|c| c.tasks.iter_mut()) {
+        if let Some(TaskContent::Board(sub)) = &mut task.content {
+            *sub = join_from(Some(task.id), pieces);
+        }
+    }
+    board
+}
+
+// Stands in for a sub-board while its board is held on its own
+fn empty() -> Board {
+    Board { title: String::new(), columns: Vec::new(), description: String::new(), frozen: false, wide_cards: false, archive: Vec::new(), trash: Vec::new(), lanes: None }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Column, Task};
+
+    // A root with a sub-board card, and the card's id
+    fn tree() -> (Board, Uuid) {
+        let mut root = Board::default();
+        let mut sub = Board { title: "Launch".to_string(), columns: vec![Column::new("Now")], ..Default::default() };
+        sub.columns[0].tasks.push(Task::new("Ship it", ""));
+        let mut card = Task::new("Launch", "");
+        card.content = Some(TaskContent::Board(sub));
+        let id = card.id;
+        root.columns[0].tasks.push(card);
+        (root, id)
+    }
+
+    fn sub(root: &mut Board) -> &mut Board {
+        match &mut root.columns[0].tasks[0].content {
+            Some(TaskContent::Board(sub)) => sub,
+            _ => unreachable!("made by tree()"),
+        }
+    }
+
+    #[test]
+    fn undo_and_redo_restore_each_tree() {
+        let (mut root, card) = tree();
+        let mut stack = UndoStack::new(&root);
+        let first = root.clone();
+
+        sub(&mut root).columns[0].tasks.push(Task::new("Announce", ""));
+        assert!(stack.record(&mut root, vec![card], None));
+        let second = root.clone();
+
+        root.columns[1].tasks.push(Task::new("Retro", ""));
+        root.title = "Renamed".to_string();
+        assert!(stack.record(&mut root, Vec::new(), None));
+        let third = root.clone();
+
+        assert_eq!(stack.undo(&mut root, Vec::new(), None), Some((Vec::new(), None)));
+        assert_eq!(root, second);
+        assert_eq!(stack.undo(&mut root, Vec::new(), None), Some((vec![card], None)));
+        assert_eq!(root, first);
+        assert_eq!(stack.undo(&mut root, Vec::new(), None), None);
+
+        stack.redo(&mut root, Vec::new(), None);
+        assert_eq!(root, second);
+        stack.redo(&mut root, Vec::new(), None);
+        assert_eq!(root, third);
+        assert_eq!(stack.redo(&mut root, Vec::new(), None), None);
+    }
+
+    #[test]
+    fn steps_hold_only_changed_boards() {
+        let (mut root, card) = tree();
+        let mut stack = UndoStack::new(&root);
+        assert!(!stack.record(&mut root, Vec::new(), None), "nothing changed");
+
+        sub(&mut root).title = "Launch day".to_string();
+        assert!(stack.record(&mut root, Vec::new(), None));
+        let patch = &stack.undo[0].patch;
+        assert_eq!(patch.len(), 1);
+        assert_eq!(patch[0].0, Some(card));
+    }
+
+    #[test]
+    fn added_and_removed_sub_boards() {
+        let (mut root, _) = tree();
+        let mut stack = UndoStack::new(&root);
+        let before = root.clone();
+
+        let card = root.columns[0].tasks.remove(0);
+        assert!(stack.record(&mut root, Vec::new(), None));
+        let removed = root.clone();
+        root.columns[2].tasks.push(card);
+        assert!(stack.record(&mut root, Vec::new(), None));
+        let moved = root.clone();
+
+        stack.undo(&mut root, Vec::new(), None);
+        assert_eq!(root, removed);
+        stack.undo(&mut root, Vec::new(), None);
+        assert_eq!(root, before);
+        stack.redo(&mut root, Vec::new(), None);
+        stack.redo(&mut root, Vec::new(), None);
+        assert_eq!(root, moved);
+    }
+
+    #[test]
+    fn new_changes_drop_the_redo_steps() {
+        let (mut root, _) = tree();
+        let mut stack = UndoStack::new(&root);
+        root.title = "One".to_string();
+        stack.record(&mut root, Vec::new(), None);
+        stack.undo(&mut root, Vec::new(), None);
+        root.title = "Two".to_string();
+        stack.record(&mut root, Vec::new(), None);
+        assert_eq!(stack.redo(&mut root, Vec::new(), None), None);
+        assert_eq!(root.title, "Two");
+    }
+}
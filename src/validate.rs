@@ -0,0 +1,46 @@
+use crate::config::LimitsConfig;
+
+// Checks on typed and pasted text before it lands on a board, against `[limits]`
+
+/// `text` without control characters: line breaks and tabs become spaces, the
+/// rest is dropped, along with whole color codes like `ESC[31m`.
+pub fn clean(text: &str) -> String {
+    strip(text, false)
+}
+
+/// Like `clean`, but keeps line breaks, as `\n`.
+pub fn clean_lines(text: &str) -> String {
+    strip(text, true)
+}
+
+fn strip(text: &str, keep_lines: bool) -> String {
+    let mut out = String::with_capacity(text.len());
+    let text = text.replace("\r\n", "\n");
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' if keep_lines => out.push('\n'),
+            '\n' | '\r' | '\t' => out.push(' '),
+            // CSI: parameters up to a final byte in @..~
+            '\u{1b}' if chars.next_if_eq(&'[').is_some() => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) { break; }
+                }
+            }
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Why `title` is too long, if it is.
+pub fn title(title: &str, limits: &LimitsConfig) -> Option<String> {
+    let length = title.trim().chars().count();
+    (length > limits.title_length).then(|| format!("{} characters, {} at most", length, limits.title_length))
+}
+
+/// Why a board with `count` columns can't take another, if it can't.
+pub fn new_column(count: usize, limits: &LimitsConfig) -> Option<String> {
+    (count >= limits.columns).then(|| format!("A board has {} columns at most", limits.columns))
+}
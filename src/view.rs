@@ -0,0 +1,229 @@
+//! What a frontend shows, worked out from an [`App`] without drawing anything.
+//! The terminal UI renders these, and so can any other frontend.
+
+use crate::app::{ActiveContentRef, App, BoardSummary, InputMode};
+use crate::config::Badge;
+use crate::lanes;
+use crate::model::{Board, Column, Task, TaskContent};
+use crate::theme;
+use crate::zone;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// The board in view, with only the cards the current filters let through.
+pub struct BoardView {
+    pub crumbs: Vec<String>, // Main board (or workspace) first, see `App::get_breadcrumbs`
+    pub columns: Vec<ColumnView>,
+}
+
+/// A swimlane of the board in view: the row of its cards in every column.
+pub struct LaneView {
+    pub title: Option<String>, // "@ana", "#epic-login", "No lane"; None on a board without lanes
+    pub count: usize,
+    pub selected: bool, // Holds the cursor
+    pub columns: Vec<ColumnView>,
+}
+
+pub struct ColumnView {
+    pub title: String,
+    pub count: String,  // "3", or "7/5" against a WIP limit
+    pub over_limit: bool,
+    pub markers: Vec<&'static str>, // "❄" icebox, "↻" resets, "⛔" asks a reason, "📡" subscribed, "⇅" sorted, "🔒" frozen
+    pub selected: bool,
+    pub cards: Vec<CardView>,
+}
+
+pub struct CardView {
+    pub id: Uuid,
+    pub kind: CardKind,
+    pub title: String,
+    pub badges: Vec<BadgeView>, // In the configured order
+    pub selected: bool,
+    pub search_hit: bool, // Matches the `/` search
+    pub jump_label: Option<String>, // While jumping, the keys that move the cursor here
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CardKind {
+    Board,
+    Todo,
+    Text,
+    Empty,
+}
+
+pub struct BadgeView {
+    pub badge: Badge,
+    pub text: String, // "📅 Nov 03", "2/5", ...
+    pub tone: Tone,
+}
+
+/// How loudly a badge should show; frontends pick the colors.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Tone {
+    Dim,
+    Warning, // Due soon, follow-up due
+    Alert,   // Overdue, blocked, long overdue follow-up
+    Todo,
+    Board,
+}
+
+impl CardKind {
+    pub fn of(task: &Task) -> Self {
+        match task.content {
+            Some(TaskContent::Board(_)) => CardKind::Board,
+            Some(TaskContent::Todo(_)) => CardKind::Todo,
+            Some(TaskContent::Text(_)) => CardKind::Text,
+            None => CardKind::Empty,
+        }
+    }
+
+    pub fn icon(self) -> &'static str {
+        match self {
+            CardKind::Board => theme::icon("📂", "[+]"),
+            CardKind::Todo => theme::icon("☑️", "[x]"),
+            CardKind::Text => theme::icon("📝", "[=]"),
+            CardKind::Empty => theme::icon("📄", "[ ]"),
+        }
+    }
+}
+
+/// The board in view; None when a todo list, note or empty task is open instead.
+pub fn board(app: &App) -> Option<BoardView> {
+    let ActiveContentRef::Board(board) = app.get_active_content() else { return None };
+    Some(BoardView { crumbs: app.get_breadcrumbs(), columns: columns(app, board) })
+}
+
+/// The board's cards by swimlane and column. A board without lanes is one
+/// untitled lane holding every column whole.
+pub fn lanes(app: &App, board: &Board) -> Vec<LaneView> {
+    let columns = columns(app, board);
+    let (Some(key), Some(order)) = (&board.lanes, app.lane_order(board).filter(|o| !o.is_empty())) else {
+        let count = columns.iter().map(|c| c.cards.len()).sum();
+        return vec![LaneView { title: None, count, selected: true, columns }];
+    };
+    let lane_of: HashMap<Uuid, usize> = board.columns.iter()
+        .flat_map(|c| &c.tasks)
+        .filter_map(|t| Some((t.id, lanes::position(&order, &key.of(t))?)))
+        .collect();
+    // Without a card under it, the cursor sits in the first lane
+    let cursor = columns.iter().flat_map(|c| &c.cards).find(|c| c.selected).and_then(|c| lane_of.get(&c.id).copied()).unwrap_or(0);
+    let mut lanes: Vec<LaneView> = order.into_iter().enumerate().map(|(i, title)| LaneView {
+        title: Some(title.unwrap_or_else(|| "No lane".to_string())),
+        count: 0,
+        selected: i == cursor,
+        columns: Vec::new(),
+    }).collect();
+    for column in columns {
+        let mut split: Vec<Vec<CardView>> = lanes.iter().map(|_| Vec::new()).collect();
+        for card in column.cards {
+            split[lane_of.get(&card.id).copied().unwrap_or(0)].push(card);
+        }
+        for (lane, cards) in lanes.iter_mut().zip(split) {
+            lane.count += cards.len();
+            lane.columns.push(ColumnView {
+                title: column.title.clone(),
+                count: cards.len().to_string(), // Of this lane; a WIP limit still shows as the title's color
+                over_limit: column.over_limit,
+                markers: column.markers.clone(),
+                selected: column.selected && lane.selected,
+                cards,
+            });
+        }
+    }
+    lanes
+}
+
+pub fn columns(app: &App, board: &Board) -> Vec<ColumnView> {
+    let (now, today) = (Utc::now(), zone::today());
+    let labels = if app.input_mode == InputMode::Jump { app.jump_labels() } else { Vec::new() };
+    board.columns.iter().enumerate().map(|(i, column)| {
+        let selected = i == app.pos.0;
+        let cards = column.tasks.iter().filter(|t| app.task_visible(t, today)).map(|task| CardView {
+            id: task.id,
+            kind: CardKind::of(task),
+            title: task.title.clone(),
+            badges: app.config.badges.show.iter().filter_map(|&b| badge(app, task, b, now, today)).collect(),
+            selected: selected && app.cursor == Some(task.id),
+            search_hit: app.search_hit(task),
+            jump_label: labels.iter().find(|(id, _)| *id == task.id).map(|(_, l)| l.clone()),
+        }).collect();
+        ColumnView {
+            title: column.title.clone(),
+            count: match column.wip_limit {
+                Some(limit) => format!("{}/{}", column.tasks.len(), limit),
+                None => column.tasks.len().to_string(),
+            },
+            over_limit: column.over_wip_limit(),
+            markers: markers(column),
+            selected,
+            cards,
+        }
+    }).collect()
+}
+
+fn markers(column: &Column) -> Vec<&'static str> {
+    [
+        (column.icebox, theme::icon("❄", "*")),
+        (column.reset.is_some(), theme::icon("↻", "@")),
+        (column.requires_reason, theme::icon("⛔", "!")),
+        (column.subscription.is_some(), theme::icon("📡", "~")),
+        (column.sort.is_some(), theme::icon("⇅", "^")),
+        (column.defaults.is_some(), theme::icon("✚", "+")),
+        (column.frozen, theme::icon("🔒", "#")),
+    ].into_iter().filter_map(|(on, marker)| on.then_some(marker)).collect()
+}
+
+/// `badge` as it shows on `task`'s card, if it has anything to say.
+pub fn badge(app: &App, task: &Task, badge: Badge, now: DateTime<Utc>, today: NaiveDate) -> Option<BadgeView> {
+    let (text, tone) = match badge {
+        Badge::Due => {
+            let due = task.due?;
+            let tone = match (zone::due_date(due) - today).num_days() {
+                _ if task.is_overdue(now) => Tone::Alert,
+                ..=1 => Tone::Warning,
+                _ => Tone::Dim,
+            };
+            (format!("{} {}", theme::icon("📅", "due"), zone::format_due(due, "%b %d")), tone)
+        }
+        // Age in column, once it is worth mentioning
+        Badge::Age => {
+            let age = task.days_in_column(now);
+            (age > 0).then(|| (format!("{}d", age), Tone::Dim))?
+        }
+        Badge::Snoozed => {
+            let until = task.snoozed_until.filter(|_| task.is_snoozed(today))?;
+            (format!("{} {}", theme::icon("💤", "zz"), until.format("%b %d")), Tone::Dim)
+        }
+        Badge::Waiting => {
+            let (waiting, days) = (task.waiting_on.as_ref()?, task.days_waiting(today)?);
+            // Escalates once a follow-up is due, and again at twice that
+            let follow_up = app.config.waiting.follow_up_days;
+            let tone = if days >= 2 * follow_up {
+                Tone::Alert
+            } else if days >= follow_up {
+                Tone::Warning
+            } else {
+                Tone::Dim
+            };
+            (format!("{} {} {}d", theme::icon("⏳", "wait"), waiting.who, days), tone)
+        }
+        Badge::Blocked => (format!("{} {}", theme::icon("⛔", "!"), task.blocked_reason.as_ref()?), Tone::Alert),
+        Badge::Progress => match &task.content {
+            Some(TaskContent::Todo(items)) if !items.is_empty() => {
+                let done = items.iter().filter(|i| i.done).count();
+                (format!("{}/{}", done, items.len()), Tone::Todo)
+            }
+            _ => return None,
+        },
+        Badge::Board => {
+            let BoardSummary { columns, open, .. } = app.board_summary(task)?;
+            (format!("{} col{} · {} open", columns, if columns == 1 { "" } else { "s" }, open), Tone::Board)
+        }
+        Badge::Alerts => {
+            let alerts = app.board_summary(task)?.alerts;
+            (alerts > 0).then(|| (format!("{} {}", theme::icon("‼", "!!"), alerts), Tone::Alert))?
+        }
+    };
+    Some(BadgeView { badge, text, tone })
+}
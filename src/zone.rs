@@ -0,0 +1,71 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, TimeZone, Utc};
+use chrono_tz::Tz;
+use std::sync::OnceLock;
+
+// The time zone due dates are shown and counted in: `[time] zone` from the
+// config, or the system's. Dues are stored as UTC instants; one without a time
+// of day is the start of that day here, so "today" and "overdue" follow the
+// user's calendar rather than UTC's.
+
+static ZONE: OnceLock<Tz> = OnceLock::new();
+
+/// Uses the IANA zone `name` (e.g. "Europe/Berlin") from now on; None keeps the system's.
+pub fn init(name: Option<&str>) -> Result<()> {
+    let Some(name) = name else { return Ok(()) };
+    let tz = name.parse::<Tz>().map_err(|_| anyhow!("unknown time zone '{}' (expected e.g. Europe/Berlin)", name))?;
+    let _ = ZONE.set(tz);
+    Ok(())
+}
+
+/// Wall-clock time of `at` in the zone.
+pub fn local(at: DateTime<Utc>) -> NaiveDateTime {
+    match ZONE.get() {
+        Some(tz) => at.with_timezone(tz).naive_local(),
+        None => at.with_timezone(&Local).naive_local(),
+    }
+}
+
+pub fn today() -> NaiveDate {
+    local(Utc::now()).date()
+}
+
+/// The instant a wall-clock time happens in the zone.
+pub fn utc(at: NaiveDateTime) -> DateTime<Utc> {
+    match ZONE.get() {
+        Some(tz) => resolve(tz, at),
+        None => resolve(&Local, at),
+    }
+}
+
+/// When the clocks fall back the first of the two instants counts; a time the
+/// clocks spring over moves to the end of the gap (some zones skip midnight itself).
+pub fn resolve<Z: TimeZone>(zone: &Z, at: NaiveDateTime) -> DateTime<Utc> {
+    (0..=24 * 4)
+        .find_map(|quarter| zone.from_local_datetime(&(at + TimeDelta::minutes(15 * quarter))).earliest())
+        .map_or_else(|| at.and_utc(), |t| t.with_timezone(&Utc))
+}
+
+pub fn start_of_day(day: NaiveDate) -> DateTime<Utc> {
+    utc(day.and_time(NaiveTime::MIN))
+}
+
+/// The day a due falls on in the zone.
+pub fn due_date(due: DateTime<Utc>) -> NaiveDate {
+    local(due).date()
+}
+
+/// False for a due that is just a day.
+pub fn has_time(due: DateTime<Utc>) -> bool {
+    due != start_of_day(due_date(due))
+}
+
+/// The day as `date_format` writes it, followed by the time of day if there is one.
+pub fn format_due(due: DateTime<Utc>, date_format: &str) -> String {
+    let at = local(due);
+    if has_time(due) {
+        format!("{} {}", at.format(date_format), at.format("%H:%M"))
+    } else {
+        at.format(date_format).to_string()
+    }
+}
@@ -0,0 +1,74 @@
+use clap::Parser;
+use kanban_cli::cli::{self, Cli};
+use kanban_cli::config::Config;
+use kanban_cli::model::{self, Board, Column, Task, TaskContent, TodoItem};
+use kanban_cli::storage;
+use std::fs;
+use std::path::Path;
+
+// Exports of a fixed board, ids included thanks to `model::seed_ids`, compared
+// with the files in tests/golden. Everything runs in one test: ids come from one
+// sequence per process, and tests in parallel would draw from it in any order.
+
+fn board() -> Board {
+    model::seed_ids(7);
+    let mut root = Board::default();
+
+    let mut docs = Task::new("Write docs #docs", "Cover install\nand first steps");
+    docs.content = Some(TaskContent::Todo(vec![
+        TodoItem { text: "Outline".to_string(), done: true },
+        TodoItem { text: "Draft, then review".to_string(), done: false },
+    ]));
+    root.columns[0].tasks.push(docs);
+
+    let mut launch = Board { title: "Launch".to_string(), columns: vec![Column::new("Now"), Column::new("Later")], ..Default::default() };
+    launch.columns[0].tasks.push(Task::new("Ship it #release", ""));
+    let mut card = Task::new("Launch", "");
+    card.content = Some(TaskContent::Board(launch));
+    root.columns[1].tasks.push(card);
+    let mut review = Task::new("Legal review", "");
+    review.blocked_reason = Some("waiting on \"legal\"".to_string());
+    root.columns[1].tasks.push(review);
+
+    let mut note = Task::new("Retro", "");
+    note.content = Some(TaskContent::Text("Went well\nShip sooner".to_string()));
+    // todo.txt writes when it was done; midday reads as the same date in most zones
+    note.entered_column_at = "2026-10-01T12:00:00Z".parse().unwrap();
+    root.columns[2].tasks.push(note);
+    root
+}
+
+fn export(config: &Config, dir: &Path, args: &[&str]) -> String {
+    let out = dir.join("out");
+    let out_arg = out.to_string_lossy().into_owned();
+    let mut argv = vec!["kanban", "export", &out_arg, "--force"];
+    argv.extend(args);
+    let command = Cli::try_parse_from(argv).expect("valid arguments").command.expect("a subcommand");
+    cli::run(command, config).expect("export runs");
+    fs::read_to_string(out).expect("export written")
+}
+
+#[test]
+fn exports_match_golden_files() {
+    let dir = std::env::temp_dir().join(format!("kanban-golden-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let mut config = Config::default();
+    config.board.file = dir.join("kanban.db");
+    storage::save(&config.board.file, &board()).unwrap();
+
+    let cases = [
+        ("export.md", vec!["--links"]),
+        ("export.csv", vec!["--format", "csv"]),
+        ("export-launch.md", vec!["--board", "Launch"]),
+        ("export.txt", vec!["--format", "todotxt", "--exclude", "in progress"]),
+    ];
+    for (golden, args) in cases {
+        let text = export(&config, &dir, &args);
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden").join(golden);
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            fs::write(&path, &text).unwrap();
+        }
+        assert_eq!(text, fs::read_to_string(&path).unwrap(), "{} differs", golden);
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}